@@ -10,7 +10,10 @@ use napi::{
 };
 use path_clean::clean;
 use swc_core::{
-    base::{config::Options, Compiler, TransformOutput},
+    base::{
+        config::{flush_resolution_caches, Options},
+        Compiler, TransformOutput,
+    },
     common::{comments::SingleThreadedComments, errors::Handler, FileName},
     ecma::ast::noop_pass,
     node::{get_deserialized, MapErr},
@@ -76,6 +79,14 @@ fn process_program_input(
     }
 }
 
+/// Resolvers outlive a transform, so caches of
+/// `jsc.experimental.resolutionCache` are written after each one.
+fn flush_resolution_caches_after(output: TransformOutput) -> Result<TransformOutput, Error> {
+    flush_resolution_caches()?;
+
+    Ok(output)
+}
+
 #[napi]
 impl Task for TransformTask {
     type JsValue = TransformOutput;
@@ -101,6 +112,7 @@ impl Task for TransformTask {
                     error_format,
                     |handler| c.run(|| process_program_input(&c, handler, program_input, &options)),
                 )
+                .and_then(flush_resolution_caches_after)
                 .convert_err()
             }
 
@@ -132,6 +144,7 @@ impl Task for TransformTask {
                     })
                 },
             )
+            .and_then(flush_resolution_caches_after)
             .convert_err(),
         }
     }
@@ -184,6 +197,7 @@ pub fn transform_sync(s: String, is_module: bool, opts: Buffer) -> napi::Result<
             error_format,
             |handler| c.run(|| process_program_input(&c, handler, program_input, &options)),
         )
+        .and_then(flush_resolution_caches_after)
         .convert_err()
     } else {
         let c = get_fresh_compiler();
@@ -206,6 +220,7 @@ pub fn transform_sync(s: String, is_module: bool, opts: Buffer) -> napi::Result<
                 })
             },
         )
+        .and_then(flush_resolution_caches_after)
         .convert_err()
     }
 }
@@ -257,6 +272,7 @@ pub fn transform_file_sync(
             error_format,
             |handler| c.run(|| process_program_input(&c, handler, program_input, &options)),
         )
+        .and_then(flush_resolution_caches_after)
         .convert_err()
     } else {
         let c = get_fresh_compiler();
@@ -272,6 +288,7 @@ pub fn transform_file_sync(
                 })
             },
         )
+        .and_then(flush_resolution_caches_after)
         .convert_err()
     }
 }
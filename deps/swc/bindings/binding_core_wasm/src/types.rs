@@ -535,9 +535,15 @@ export interface Options extends Config {
    *
    *  If an object is provided, it will be treated as the source map object itself.
    *
+   * If an array is provided, it's treated as source maps of previous stages of a
+   * pipeline, ordered from the first stage to the last one. They are composed into
+   * one source map, so the output maps to the sources of the first stage.
+   *
+   * Index maps are flattened.
+   *
    * Defaults to `true`.
    */
-  inputSourceMap?: boolean | string;
+  inputSourceMap?: boolean | string | string[];
 
   /**
    * The name to use for the file inside the source map object.
@@ -1,33 +1,48 @@
-use std::{
-    iter::once,
-    mem::take,
-    sync::{Arc, Mutex},
-};
+use std::collections::HashMap;
 
-use anyhow::Error;
-use error_reporter::SwcReportHandler;
 use js_sys::Uint8Array;
-use miette::{GraphicalTheme, LabeledSpan, ThemeCharacters, ThemeStyles};
-use serde::Serialize;
-use swc_common::{
-    errors::{DiagnosticBuilder, DiagnosticId, Emitter, Handler, HANDLER},
-    sync::Lrc,
-    SourceMap, Span, GLOBALS,
+use serde::{Deserialize, Serialize};
+use swc_common::{sync::Lrc, SourceMap, GLOBALS};
+use swc_error_reporters::json::{try_with_json_handler, JsonDiagnostic};
+use swc_ts_fast_strip::{
+    stack_trace::{StackFrame, Symbolicator},
+    Options, TransformOutput,
 };
-use swc_error_reporters::{convert_span, to_pretty_source_code};
-use swc_ts_fast_strip::{Options, TransformOutput};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::{future_to_promise, js_sys::Promise};
 
-mod error_reporter;
-
 /// Custom interface definitions for the @swc/wasm's public interface instead of
 /// auto generated one, which is not reflecting most of types in detail.
 #[wasm_bindgen(typescript_custom_section)]
 const INTERFACE_DEFINITIONS: &'static str = r#"
 export declare function transform(src: string | Uint8Array, opts?: Options): Promise<TransformOutput>;
 export declare function transformSync(src: string | Uint8Array, opts?: Options): TransformOutput;
-export type { Options, TransformOutput };
+/**
+ * Maps frames of `Error.stack` back to the original source.
+ *
+ * `sourceMaps` is keyed by the name of the generated file as it appears in
+ * the stack trace.
+ */
+export declare function symbolicate(stack: string, sourceMaps: Record<string, SourceMapInput>): string;
+export declare function symbolicateFrames(frames: StackFrame[], sourceMaps: Record<string, SourceMapInput>): StackFrame[];
+export type { Options, TransformOutput, Diagnostic, StackFrame, SourceMapInput };
+
+/**
+ * A source map, or a source map with the generated code, which is used to
+ * map function names.
+ */
+type SourceMapInput = string | { map: string; code?: string };
+
+interface Diagnostic {
+    code?: string;
+    message: string;
+    snippet?: string;
+    filename?: string;
+    startLine?: number;
+    startColumn?: number;
+    endLine?: number;
+    endColumn?: number;
+}
 "#;
 
 #[wasm_bindgen(skip_typescript)]
@@ -66,196 +81,62 @@ pub fn transform_sync(input: JsValue, options: JsValue) -> Result<JsValue, JsVal
     }
 }
 
-fn operate(input: String, options: Options) -> Result<TransformOutput, Vec<JsonDiagnostic>> {
-    let cm = Lrc::new(SourceMap::default());
-
-    try_with_json_handler(cm.clone(), |handler| {
-        swc_ts_fast_strip::operate(&cm, handler, input, options).map_err(anyhow::Error::new)
-    })
-}
+#[wasm_bindgen(skip_typescript)]
+pub fn symbolicate(stack: String, source_maps: JsValue) -> Result<String, JsValue> {
+    let symbolicator = build_symbolicator(source_maps)?;
 
-#[derive(Clone)]
-struct JsonErrorWriter {
-    errors: Arc<Mutex<Vec<JsonDiagnostic>>>,
-    reporter: SwcReportHandler,
-    cm: Lrc<SourceMap>,
+    Ok(symbolicator.symbolicate_stack(&stack))
 }
 
-fn try_with_json_handler<F, Ret>(cm: Lrc<SourceMap>, op: F) -> Result<Ret, Vec<JsonDiagnostic>>
-where
-    F: FnOnce(&Handler) -> Result<Ret, Error>,
-{
-    let wr = JsonErrorWriter {
-        errors: Default::default(),
-        reporter: SwcReportHandler::default().with_theme(GraphicalTheme {
-            characters: ThemeCharacters {
-                hbar: ' ',
-                vbar: ' ',
-                xbar: ' ',
-                vbar_break: ' ',
-                ltop: ' ',
-                rtop: ' ',
-                mtop: ' ',
-                lbot: ' ',
-                rbot: ' ',
-                mbot: ' ',
-                error: "".into(),
-                warning: "".into(),
-                advice: "".into(),
-                ..ThemeCharacters::ascii()
-            },
-            styles: ThemeStyles::none(),
-        }),
-        cm,
-    };
-    let emitter: Box<dyn Emitter> = Box::new(wr.clone());
-
-    let handler = Handler::with_emitter(true, false, emitter);
-
-    let ret = HANDLER.set(&handler, || op(&handler));
+#[wasm_bindgen(js_name = "symbolicateFrames", skip_typescript)]
+pub fn symbolicate_frames(frames: JsValue, source_maps: JsValue) -> Result<JsValue, JsValue> {
+    let frames: Vec<StackFrame> = serde_wasm_bindgen::from_value(frames)?;
+    let symbolicator = build_symbolicator(source_maps)?;
 
-    if handler.has_errors() {
-        let mut lock = wr.errors.lock().unwrap();
-        let error = take(&mut *lock);
-
-        Err(error)
-    } else {
-        Ok(ret.expect("it should not fail without emitting errors to handler"))
-    }
+    Ok(serde_wasm_bindgen::to_value(
+        &symbolicator.symbolicate_frames(&frames),
+    )?)
 }
 
-impl Emitter for JsonErrorWriter {
-    fn emit(&mut self, db: &mut DiagnosticBuilder) {
-        let d = &**db;
-
-        let snippet = d.span.primary_span().and_then(|span| {
-            let mut snippet = String::new();
-            match self.reporter.render_report(
-                &mut snippet,
-                &Snippet {
-                    source_code: &to_pretty_source_code(&self.cm, true),
-                    span,
-                },
-            ) {
-                Ok(()) => Some(snippet),
-                Err(_) => None,
-            }
-        });
-
-        let children = d
-            .children
-            .iter()
-            .map(|d| todo!("json subdiagnostic: {d:?}"))
-            .collect::<Vec<_>>();
-
-        let error_code = match &d.code {
-            Some(DiagnosticId::Error(s)) => Some(&**s),
-            Some(DiagnosticId::Lint(s)) => Some(&**s),
-            None => None,
-        };
-
-        let start = d
-            .span
-            .primary_span()
-            .and_then(|span| self.cm.try_lookup_char_pos(span.lo()).ok());
-
-        let end = d
-            .span
-            .primary_span()
-            .and_then(|span| self.cm.try_lookup_char_pos(span.hi()).ok());
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SourceMapInput {
+    Map(String),
+    WithCode { map: String, code: Option<String> },
+}
 
-        let filename = start.as_ref().map(|loc| loc.file.name.to_string());
+fn build_symbolicator(source_maps: JsValue) -> Result<Symbolicator, JsValue> {
+    let source_maps: HashMap<String, SourceMapInput> = serde_wasm_bindgen::from_value(source_maps)?;
 
-        let error = JsonDiagnostic {
-            code: error_code.map(|s| s.to_string()),
-            message: d.message[0].0.to_string(),
-            snippet,
-            filename,
-            start_line: start.as_ref().map(|loc| loc.line),
-            start_column: start.as_ref().map(|loc| loc.col_display),
-            end_line: end.as_ref().map(|loc| loc.line),
-            end_column: end.as_ref().map(|loc| loc.col_display),
-            children,
+    let mut symbolicator = Symbolicator::default();
+    for (file, input) in source_maps {
+        let (map, code) = match input {
+            SourceMapInput::Map(map) => (map, None),
+            SourceMapInput::WithCode { map, code } => (map, code),
         };
 
-        self.errors.lock().unwrap().push(error);
+        symbolicator
+            .add_source_map(file, map.as_bytes(), code)
+            .map_err(|err| JsValue::from_str(&format!("{err:?}")))?;
     }
 
-    fn take_diagnostics(&mut self) -> Vec<String> {
-        Default::default()
-    }
+    Ok(symbolicator)
 }
 
 #[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct JsonDiagnostic {
-    /// Error code
-    #[serde(skip_serializing_if = "Option::is_none")]
-    code: Option<String>,
-    message: String,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    snippet: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    filename: Option<String>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    start_line: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    start_column: Option<usize>,
-
-    #[serde(skip_serializing_if = "Option::is_none")]
-    end_line: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    end_column: Option<usize>,
+struct Output {
+    #[serde(flatten)]
+    output: TransformOutput,
 
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    children: Vec<JsonSubdiagnostic>,
+    warnings: Vec<JsonDiagnostic>,
 }
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-struct JsonSubdiagnostic {
-    message: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    snippet: Option<String>,
-    filename: String,
-    line: usize,
-}
-
-struct Snippet<'a> {
-    source_code: &'a dyn miette::SourceCode,
-    span: Span,
-}
-
-impl miette::Diagnostic for Snippet<'_> {
-    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
-        if self.span.lo().is_dummy() || self.span.hi().is_dummy() {
-            return None;
-        }
-
-        Some(self.source_code)
-    }
-
-    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
-        Some(Box::new(once(LabeledSpan::new_with_span(
-            None,
-            convert_span(self.span),
-        ))))
-    }
-}
-
-impl std::error::Error for Snippet<'_> {}
-
-impl std::fmt::Display for Snippet<'_> {
-    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Ok(())
-    }
-}
+fn operate(input: String, options: Options) -> Result<Output, Vec<JsonDiagnostic>> {
+    let cm = Lrc::new(SourceMap::default());
 
-impl std::fmt::Debug for Snippet<'_> {
-    fn fmt(&self, _: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Ok(())
-    }
+    try_with_json_handler(cm.clone(), |handler| {
+        swc_ts_fast_strip::operate(&cm, handler, input, options).map_err(anyhow::Error::new)
+    })
+    .map(|(output, warnings)| Output { output, warnings })
 }
@@ -14,7 +14,7 @@ pub use swc::PrintArgs;
 use swc::{config::ErrorFormat, Compiler, HandlerOpts};
 #[doc(hidden)]
 pub use swc::{
-    config::{Options, ParseOptions, SourceMapsConfig},
+    config::{flush_resolution_caches, Options, ParseOptions, SourceMapsConfig},
     try_with_handler,
 };
 #[doc(hidden)]
@@ -399,6 +399,9 @@ macro_rules! build_transform_sync {
                       Err(v) => unsafe { c.process_js(handler, $crate::wasm::serde_wasm_bindgen::from_value(v).expect(""), &opts)? },
                   };
 
+                  // Resolvers outlive a transform, so their caches are written after each one.
+                  $crate::wasm::flush_resolution_caches()?;
+
                   out
                     .serialize($crate::wasm::compat_serializer().as_ref())
                     .map_err(|e| $crate::wasm::anyhow::anyhow!("failed to serialize transform result: {}", e))
@@ -5,12 +5,7 @@ use std::{
     sync::Arc,
 };
 
-#[cfg(any(
-    feature = "module",
-    all(feature = "plugin", not(target_arch = "wasm32"))
-))]
-use anyhow::Context;
-use anyhow::{bail, Error};
+use anyhow::{bail, Context, Error};
 use bytes_str::BytesStr;
 use dashmap::DashMap;
 use either::Either;
@@ -41,10 +36,15 @@ use swc_ecma_ext_transforms::jest;
 use swc_ecma_lints::{
     config::LintConfig,
     rules::{lint_pass, LintParams},
+    suppression::{suppressed_rule, Suppressions},
 };
 #[cfg(feature = "module")]
-use swc_ecma_loader::resolvers::{
-    lru::CachingResolver, node::NodeModulesResolver, tsc::TsConfigResolver,
+use swc_ecma_loader::{
+    resolve::Resolve,
+    resolvers::{
+        lru::CachingResolver, node::NodeModulesResolver, persistent::PersistentCachingResolver,
+        tsc::TsConfigResolver,
+    },
 };
 pub use swc_ecma_minifier::js::*;
 use swc_ecma_minifier::option::terser::TerserTopLevelOptions;
@@ -82,10 +82,12 @@ use swc_ecma_utils::NodeIgnoringSpan;
 use swc_ecma_visit::VisitMutWith;
 use swc_visit::Optional;
 
+#[cfg(feature = "lint")]
+pub use crate::plugin::LintPlugins;
 pub use crate::plugin::PluginConfig;
-#[cfg(feature = "module")]
-use crate::SwcImportResolver;
 use crate::{builder::MinifierPass, dropped_comments_preserver::dropped_comments_preserver};
+#[cfg(feature = "module")]
+use crate::{resolver::persistent_resolver, SwcImportResolver};
 
 #[cfg(test)]
 mod tests;
@@ -248,6 +250,14 @@ impl Options {
 pub enum InputSourceMap {
     Bool(bool),
     Str(String),
+    /// Source maps of previous stages of a pipeline, ordered from the first
+    /// stage to the last one.
+    ///
+    /// They are composed into one source map using
+    /// [compose](swc_sourcemap::compose), so the output source map maps to the
+    /// original sources. Each source is traced through the map whose `file`
+    /// is the name of the source.
+    Chain(Vec<String>),
 }
 
 impl Default for InputSourceMap {
@@ -636,13 +646,15 @@ impl Options {
         };
 
         let paths = paths.into_iter().collect();
-        let resolver = ModuleConfig::get_resolver(
+        let resolver = ModuleConfig::try_get_resolver(
             &base_url,
             paths,
             base,
             cfg.module.as_ref(),
             preserve_symlinks,
-        );
+            experimental.resolution_cache.as_deref().map(Path::new),
+        )
+        .context("invalid `jsc.paths`")?;
 
         let target = es_version;
         let inject_helpers = !self.skip_helper_injection;
@@ -802,13 +814,8 @@ impl Options {
 
         #[cfg(feature = "plugin")]
         let plugin_transforms: Box<dyn Pass> = {
-            let transform_filename = match base {
-                FileName::Real(path) => path.as_os_str().to_str().map(String::from),
-                FileName::Custom(filename) => Some(filename.to_owned()),
-                _ => None,
-            };
             let transform_metadata_context = Arc::new(TransformPluginMetadataContext::new(
-                transform_filename,
+                crate::plugin::transform_filename(base),
                 self.env_name.to_owned(),
                 None,
             ));
@@ -835,7 +842,7 @@ impl Options {
 
                 Box::new(crate::plugin::plugins(
                     experimental.plugins,
-                    experimental.plugin_env_vars,
+                    experimental.plugin_env_vars.clone(),
                     transform_metadata_context,
                     comments.cloned(),
                     cm.clone(),
@@ -901,15 +908,45 @@ impl Options {
                 let disable_all_lints = experimental.disable_all_lints.into_bool();
                 let unresolved_ctxt = SyntaxContext::empty().apply_mark(unresolved_mark);
                 let top_level_ctxt = SyntaxContext::empty().apply_mark(top_level_mark);
+                let suppressions = match comments {
+                    Some(comments) => {
+                        let (leading, trailing) = comments.borrow_all();
+                        Suppressions::new(
+                            cm.clone(),
+                            leading.values().chain(trailing.values()).flatten(),
+                        )
+                    }
+                    None => Suppressions::new(cm.clone(), []),
+                };
+                let mut rules = swc_ecma_lints::rules::all(LintParams {
+                    program: &program,
+                    lint_config: &lints,
+                    top_level_ctxt,
+                    unresolved_ctxt,
+                    es_version,
+                    source_map: cm.clone(),
+                });
+                if !disable_all_lints {
+                    let lint_plugins = LintPlugins::get(
+                        &lints.plugins,
+                        experimental.cache_root.as_deref(),
+                        experimental.plugin_env_vars.clone(),
+                        &self.runtime_options,
+                    )
+                    .context("Failed to load lint plugins")?;
+                    rules.extend(lint_plugins.rules(
+                        Arc::new(TransformPluginMetadataContext::new(
+                            crate::plugin::transform_filename(base),
+                            self.env_name.to_owned(),
+                            None,
+                        )),
+                        comments,
+                        cm.clone(),
+                        unresolved_mark,
+                    ));
+                }
                 Optional::new(
-                    lint_pass(swc_ecma_lints::rules::all(LintParams {
-                        program: &program,
-                        lint_config: &lints,
-                        top_level_ctxt,
-                        unresolved_ctxt,
-                        es_version,
-                        source_map: cm.clone(),
-                    })),
+                    lint_pass(suppressed_rule(rules, suppressions)),
                     !disable_all_lints,
                 )
             };
@@ -1511,6 +1548,15 @@ pub struct JscExperimental {
     #[serde(default)]
     pub cache_root: Option<String>,
 
+    /// Directory where module resolutions of `jsc.paths` are cached across
+    /// runs of swc.
+    ///
+    /// Entries are invalidated when files which affected them change. The
+    /// cache is written by [flush_resolution_caches], which the CLI calls once
+    /// all files are compiled and the bindings call after each transform.
+    #[serde(default)]
+    pub resolution_cache: Option<String>,
+
     #[serde(default)]
     pub run_plugin_first: BoolConfig<false>,
 
@@ -1606,6 +1652,10 @@ pub enum ModuleConfig {
     NodeNext(serde_json::Value),
 }
 
+/// The base file of imports and the resolver of [ModuleConfig::get_resolver].
+#[cfg(feature = "module")]
+pub type ModuleResolver = (FileName, Arc<dyn ImportResolver>);
+
 #[cfg(feature = "module")]
 impl ModuleConfig {
     pub fn build<'cmt>(
@@ -1665,17 +1715,39 @@ impl ModuleConfig {
         Box::new(transform_pass)
     }
 
+    /// Panics if an entry of `paths` is malformed. Use
+    /// [ModuleConfig::try_get_resolver] to get an error instead.
     pub fn get_resolver(
         base_url: &Path,
         paths: CompiledPaths,
         base: &FileName,
         config: Option<&ModuleConfig>,
         preserve_symlinks: bool,
+        resolution_cache: Option<&Path>,
     ) -> Option<(FileName, Arc<dyn ImportResolver>)> {
+        Self::try_get_resolver(
+            base_url,
+            paths,
+            base,
+            config,
+            preserve_symlinks,
+            resolution_cache,
+        )
+        .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_get_resolver(
+        base_url: &Path,
+        paths: CompiledPaths,
+        base: &FileName,
+        config: Option<&ModuleConfig>,
+        preserve_symlinks: bool,
+        resolution_cache: Option<&Path>,
+    ) -> Result<Option<ModuleResolver>, Error> {
         let skip_resolver = base_url.as_os_str().is_empty() && paths.is_empty();
 
         if skip_resolver {
-            return None;
+            return Ok(None);
         }
 
         let base = match base {
@@ -1691,49 +1763,61 @@ impl ModuleConfig {
                 base_url,
                 paths,
                 false,
+                false,
                 &util::Config::default_js_ext(),
                 preserve_symlinks,
+                resolution_cache,
             ),
             Some(ModuleConfig::Es6(config)) | Some(ModuleConfig::NodeNext(config)) => {
                 build_resolver(
                     base_url,
                     paths,
                     config.config.resolve_fully,
+                    config.config.resolve_subpath_imports,
                     &config.config.out_file_extension,
                     preserve_symlinks,
+                    resolution_cache,
                 )
             }
             Some(ModuleConfig::CommonJs(config)) => build_resolver(
                 base_url,
                 paths,
                 config.resolve_fully,
+                config.resolve_subpath_imports,
                 &config.out_file_extension,
                 preserve_symlinks,
+                resolution_cache,
             ),
             Some(ModuleConfig::Umd(config)) => build_resolver(
                 base_url,
                 paths,
                 config.config.resolve_fully,
+                config.config.resolve_subpath_imports,
                 &config.config.out_file_extension,
                 preserve_symlinks,
+                resolution_cache,
             ),
             Some(ModuleConfig::Amd(config)) => build_resolver(
                 base_url,
                 paths,
                 config.config.resolve_fully,
+                config.config.resolve_subpath_imports,
                 &config.config.out_file_extension,
                 preserve_symlinks,
+                resolution_cache,
             ),
             Some(ModuleConfig::SystemJs(config)) => build_resolver(
                 base_url,
                 paths,
                 config.config.resolve_fully,
+                config.config.resolve_subpath_imports,
                 &config.config.out_file_extension,
                 preserve_symlinks,
+                resolution_cache,
             ),
-        };
+        }?;
 
-        Some((base, resolver))
+        Ok(Some((base, resolver)))
     }
 }
 
@@ -1759,9 +1843,23 @@ impl ModuleConfig {
         _base: &FileName,
         _config: Option<&ModuleConfig>,
         _preserve_symlinks: bool,
+        _resolution_cache: Option<&Path>,
     ) -> Option<(FileName, Arc<dyn swc_ecma_loader::resolve::Resolve>)> {
         None
     }
+
+    /// Returns None when module feature is disabled.
+    #[allow(clippy::type_complexity)]
+    pub fn try_get_resolver(
+        _base_url: &Path,
+        _paths: CompiledPaths,
+        _base: &FileName,
+        _config: Option<&ModuleConfig>,
+        _preserve_symlinks: bool,
+        _resolution_cache: Option<&Path>,
+    ) -> Result<Option<(FileName, Arc<dyn swc_ecma_loader::resolve::Resolve>)>, Error> {
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Merge)]
@@ -2339,17 +2437,31 @@ pub(crate) fn default_env_name() -> String {
     }
 }
 
+/// Arguments of [build_resolver], which share a resolver.
+#[cfg(feature = "module")]
+#[derive(PartialEq, Eq, Hash)]
+struct ResolverCacheKey {
+    base_url: PathBuf,
+    paths: CompiledPaths,
+    resolve_fully: bool,
+    resolve_subpath_imports: bool,
+    file_extension: String,
+    preserve_symlinks: bool,
+    resolution_cache: Option<PathBuf>,
+}
+
 #[cfg(feature = "module")]
 fn build_resolver(
     mut base_url: PathBuf,
     paths: CompiledPaths,
     resolve_fully: bool,
+    resolve_subpath_imports: bool,
     file_extension: &str,
     preserve_symlinks: bool,
-) -> SwcImportResolver {
-    static CACHE: Lazy<
-        DashMap<(PathBuf, CompiledPaths, bool, String, bool), SwcImportResolver, FxBuildHasher>,
-    > = Lazy::new(Default::default);
+    resolution_cache: Option<&Path>,
+) -> Result<SwcImportResolver, Error> {
+    static CACHE: Lazy<DashMap<ResolverCacheKey, SwcImportResolver, FxBuildHasher>> =
+        Lazy::new(Default::default);
 
     // On Windows, we need to normalize path as UNC path.
     if cfg!(target_os = "windows") {
@@ -2365,14 +2477,18 @@ fn build_resolver(
             .unwrap();
     }
 
-    if let Some(cached) = CACHE.get(&(
-        base_url.clone(),
-        paths.clone(),
+    let key = ResolverCacheKey {
+        base_url: base_url.clone(),
+        paths: paths.clone(),
         resolve_fully,
-        file_extension.to_owned(),
+        resolve_subpath_imports,
+        file_extension: file_extension.to_owned(),
         preserve_symlinks,
-    )) {
-        return cached.clone();
+        resolution_cache: resolution_cache.map(Path::to_path_buf),
+    };
+
+    if let Some(cached) = CACHE.get(&key) {
+        return Ok(cached.clone());
     }
 
     let r = {
@@ -2382,15 +2498,37 @@ fn build_resolver(
             true,
         );
 
-        let r = CachingResolver::new(1024, r);
-
-        let r = TsConfigResolver::new(r, base_url.clone(), paths.clone());
+        let r: Box<dyn Resolve> = match resolution_cache {
+            Some(cache_dir) => {
+                // The persistent cache can not see paths checked by another cache.
+                let r = TsConfigResolver::try_new(r, base_url.clone(), paths.clone())?;
+                let r = Arc::new(persistent_resolver(
+                    cache_dir,
+                    format!("{base_url:?} {paths:?}"),
+                    r,
+                ));
+                RESOLUTION_CACHES
+                    .lock()
+                    .expect("Failed to access the resolution cache lock")
+                    .push(r.clone());
+                Box::new(r)
+            }
+            None => {
+                let r = CachingResolver::new(1024, r);
+                Box::new(TsConfigResolver::try_new(
+                    r,
+                    base_url.clone(),
+                    paths.clone(),
+                )?)
+            }
+        };
         let r = CachingResolver::new(256, r);
 
         let cfg = modules::path::Config {
             base_dir: Some(base_url.clone()),
             resolve_fully,
             file_extension: file_extension.to_owned(),
+            resolve_subpath_imports,
         };
         let r = if preserve_symlinks {
             NodeImportResolver::with_config_preserving_symlinks(r, cfg)
@@ -2400,16 +2538,37 @@ fn build_resolver(
         Arc::new(r)
     };
 
-    CACHE.insert(
-        (
-            base_url,
-            paths,
-            resolve_fully,
-            file_extension.to_owned(),
-            preserve_symlinks,
-        ),
-        r.clone(),
-    );
+    CACHE.insert(key, r.clone());
+
+    Ok(r)
+}
+
+#[cfg(feature = "module")]
+type PersistentResolver = Arc<PersistentCachingResolver<TsConfigResolver<NodeModulesResolver>>>;
+
+#[cfg(feature = "module")]
+static RESOLUTION_CACHES: Lazy<std::sync::Mutex<Vec<PersistentResolver>>> =
+    Lazy::new(Default::default);
 
-    r
+/// Writes caches of `jsc.experimental.resolutionCache` to disk.
+///
+/// Resolvers are shared by all files, so this should be called once all files
+/// are processed.
+#[cfg(feature = "module")]
+pub fn flush_resolution_caches() -> Result<(), Error> {
+    for cache in RESOLUTION_CACHES
+        .lock()
+        .expect("Failed to access the resolution cache lock")
+        .iter()
+    {
+        cache.flush()?;
+    }
+
+    Ok(())
+}
+
+/// Stub impl when module feature is disabled
+#[cfg(not(feature = "module"))]
+pub fn flush_resolution_caches() -> Result<(), Error> {
+    Ok(())
 }
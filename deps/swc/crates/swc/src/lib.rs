@@ -144,9 +144,7 @@ use swc_ecma_ast::{
 };
 use swc_ecma_codegen::Node;
 #[cfg(feature = "module")]
-use swc_ecma_loader::resolvers::{
-    lru::CachingResolver, node::NodeModulesResolver, tsc::TsConfigResolver,
-};
+use swc_ecma_loader::resolvers::lru::CachingResolver;
 use swc_ecma_minifier::option::{MangleCache, MinifyOptions, TopLevelOptions};
 use swc_ecma_parser::{
     error::SyntaxError, parse_file_as_program, parse_file_as_script, EsSyntax, Syntax,
@@ -180,11 +178,18 @@ mod dropped_comments_preserver;
 mod plugin;
 pub mod wasm_analysis;
 pub mod resolver {
-    use std::path::PathBuf;
+    use std::{
+        hash::{Hash, Hasher},
+        path::{Path, PathBuf},
+    };
 
-    use rustc_hash::FxHashMap;
+    use rustc_hash::{FxHashMap, FxHasher};
     use swc_ecma_loader::{
-        resolvers::{lru::CachingResolver, node::NodeModulesResolver, tsc::TsConfigResolver},
+        resolve::Resolve,
+        resolvers::{
+            lru::CachingResolver, node::NodeModulesResolver, persistent::PersistentCachingResolver,
+            tsc::TsConfigResolver,
+        },
         TargetEnv,
     };
 
@@ -192,6 +197,26 @@ pub mod resolver {
 
     pub type NodeResolver = CachingResolver<NodeModulesResolver>;
 
+    /// Caches resolutions of `inner` in `cache_dir`, which is set by
+    /// `jsc.experimental.resolutionCache`.
+    ///
+    /// `salt` should identify the configuration of `inner`. Each configuration
+    /// has its own file in `cache_dir`, so they do not invalidate each other.
+    pub fn persistent_resolver<R>(
+        cache_dir: &Path,
+        salt: String,
+        inner: R,
+    ) -> PersistentCachingResolver<R>
+    where
+        R: Resolve,
+    {
+        let mut hasher = FxHasher::default();
+        salt.hash(&mut hasher);
+        let path = cache_dir.join(format!("{:016x}.json", hasher.finish()));
+
+        PersistentCachingResolver::new(path, salt, inner)
+    }
+
     pub fn paths_resolver(
         target_env: TargetEnv,
         alias: FxHashMap<String, String>,
@@ -220,9 +245,8 @@ pub mod resolver {
 }
 
 #[cfg(feature = "module")]
-type SwcImportResolver = Arc<
-    NodeImportResolver<CachingResolver<TsConfigResolver<CachingResolver<NodeModulesResolver>>>>,
->;
+type SwcImportResolver =
+    Arc<NodeImportResolver<CachingResolver<Box<dyn swc_ecma_loader::resolve::Resolve>>>>;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum FlowScriptLikeModuleKind {
@@ -231,6 +255,11 @@ enum FlowScriptLikeModuleKind {
     RuntimeModule(Span),
 }
 
+/// Reads a source map, flattening it if it's an index map.
+fn read_source_map<R: std::io::Read>(rdr: R) -> Result<sourcemap::SourceMap, sourcemap::Error> {
+    Ok(sourcemap::decode(rdr)?.flatten()?.into_owned())
+}
+
 fn emit_parser_recoverable_errors(
     handler: &Handler,
     errors: Vec<swc_ecma_parser::error::Error>,
@@ -459,7 +488,7 @@ impl Compiler {
                         .decode(content.as_bytes())
                         .context("failed to decode base64-encoded source map")?;
 
-                    Ok(Some(sourcemap::SourceMap::from_slice(&res).context(
+                    Ok(Some(read_source_map(&*res).context(
                         "failed to read input source map from inlined base64 encoded string",
                     )?))
                 };
@@ -548,14 +577,12 @@ impl Compiler {
                                         }
                                     };
 
-                                    Ok(Some(sourcemap::SourceMap::from_reader(file).with_context(
-                                        || {
-                                            format!(
-                                                "failed to read input source map
+                                    Ok(Some(read_source_map(file).with_context(|| {
+                                        format!(
+                                            "failed to read input source map
                                 from file at {path}"
-                                            )
-                                        },
-                                    )?))
+                                        )
+                                    })?))
                                 }
                                 None => Ok(None),
                             }
@@ -600,13 +627,22 @@ impl Compiler {
                         Ok(read_sourcemap())
                     } else {
                         // Load source map passed by user
-                        Ok(Some(
-                            swc_sourcemap::SourceMap::from_slice(s.as_bytes()).context(
-                                "failed to read input source map from user-provided sourcemap",
-                            )?,
-                        ))
+                        Ok(Some(read_source_map(s.as_bytes()).context(
+                            "failed to read input source map from user-provided sourcemap",
+                        )?))
                     }
                 }
+                InputSourceMap::Chain(ref maps) => {
+                    let maps = maps
+                        .iter()
+                        .map(|s| sourcemap::decode_slice(s.as_bytes()))
+                        .collect::<Result<Vec<_>, _>>()
+                        .context("failed to read input source map from user-provided chain")?;
+
+                    Ok(Some(
+                        sourcemap::compose(&maps).context("failed to compose input source maps")?,
+                    ))
+                }
             }
         })
     }
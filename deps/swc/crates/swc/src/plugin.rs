@@ -18,6 +18,8 @@ use swc_common::errors::{DiagnosticId, HANDLER};
 use swc_ecma_ast::Pass;
 #[cfg(feature = "plugin")]
 use swc_ecma_ast::*;
+#[cfg(feature = "lint")]
+use swc_ecma_lints::config::LintPluginConfig;
 use swc_ecma_loader::{
     resolve::Resolve,
     resolvers::{lru::CachingResolver, node::NodeModulesResolver},
@@ -230,6 +232,264 @@ impl Fold for RustPlugins {
     }
 }
 
+/// Returns the filename passed to plugins as metadata.
+pub(crate) fn transform_filename(base: &FileName) -> Option<String> {
+    match base {
+        FileName::Real(path) => path.as_os_str().to_str().map(String::from),
+        FileName::Custom(filename) => Some(filename.to_owned()),
+        _ => None,
+    }
+}
+
+/// Wasm plugins which provide lint rules, which are configured by
+/// `jsc.lints.plugins`.
+///
+/// Plugins are resolved and compiled once by [LintPlugins::get], and create
+/// lint rules for each file with [LintPlugins::rules].
+#[cfg(feature = "lint")]
+#[derive(Default)]
+pub struct LintPlugins {
+    #[cfg(all(feature = "plugin", not(target_arch = "wasm32")))]
+    plugins: Vec<(LintPluginConfig, PluginConfig)>,
+    #[cfg(all(feature = "plugin", not(target_arch = "wasm32")))]
+    plugin_env_vars: Option<std::sync::Arc<Vec<Atom>>>,
+    #[cfg(all(feature = "plugin", not(target_arch = "wasm32")))]
+    plugin_runtime: Option<Arc<dyn PluginRuntime>>,
+}
+
+/// Serialized configuration of lint plugins and the identifier of the plugin
+/// runtime.
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+type LintPluginsKey = (String, &'static str);
+
+/// Lint plugins which are already compiled, so they are resolved once even if
+/// they are configured for each file.
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+static LINT_PLUGINS: once_cell::sync::Lazy<
+    std::sync::Mutex<rustc_hash::FxHashMap<LintPluginsKey, Arc<LintPlugins>>>,
+> = once_cell::sync::Lazy::new(Default::default);
+
+#[cfg(feature = "lint")]
+impl LintPlugins {
+    /// Resolves and compiles `lint_plugins`, or returns the ones compiled for
+    /// the same configuration.
+    pub fn get(
+        lint_plugins: &[LintPluginConfig],
+        cache_root: Option<&str>,
+        plugin_env_vars: Option<Vec<Atom>>,
+        runtime_options: &crate::config::RuntimeOptions,
+    ) -> Result<Arc<Self>> {
+        if lint_plugins.is_empty() {
+            return Ok(Default::default());
+        }
+
+        #[cfg(all(feature = "plugin", not(target_arch = "wasm32")))]
+        {
+            let plugin_runtime = runtime_options.plugin_runtime.clone().context(
+                "Lint plugins are not supported by this build of swc, as it has no plugin runtime",
+            )?;
+
+            let key = (
+                serde_json::to_string(&(lint_plugins, cache_root, &plugin_env_vars))?,
+                plugin_runtime.identifier(),
+            );
+
+            let mut cache = LINT_PLUGINS.lock().unwrap();
+            if let Some(compiled) = cache.get(&key) {
+                return Ok(compiled.clone());
+            }
+
+            let plugins = lint_plugins
+                .iter()
+                .map(|plugin| PluginConfig(plugin.0.clone(), plugin.plugin_config()))
+                .collect::<Vec<_>>();
+
+            compile_wasm_plugins(cache_root, &plugins, &*plugin_runtime)
+                .context("Failed to compile wasm lint plugins")?;
+
+            let compiled = Arc::new(LintPlugins {
+                plugins: lint_plugins.iter().cloned().zip(plugins).collect(),
+                plugin_env_vars: plugin_env_vars.map(std::sync::Arc::new),
+                plugin_runtime: Some(plugin_runtime),
+            });
+            cache.insert(key, compiled.clone());
+
+            Ok(compiled)
+        }
+
+        #[cfg(not(all(feature = "plugin", not(target_arch = "wasm32"))))]
+        anyhow::bail!("Lint plugins are not supported by this build of swc")
+    }
+
+    /// Creates lint rules of the plugins for a file.
+    ///
+    /// The program and the comments are serialized once and shared by the
+    /// plugins, and the programs returned by plugins are discarded.
+    pub fn rules(
+        &self,
+        metadata_context: std::sync::Arc<
+            swc_common::plugin::metadata::TransformPluginMetadataContext,
+        >,
+        comments: Option<&swc_common::comments::SingleThreadedComments>,
+        source_map: std::sync::Arc<swc_common::SourceMap>,
+        unresolved_mark: swc_common::Mark,
+    ) -> Vec<Box<dyn swc_ecma_lints::rule::Rule>> {
+        #[cfg(all(feature = "plugin", not(target_arch = "wasm32")))]
+        if let Some(plugin_runtime) = &self.plugin_runtime {
+            // Rules are run in parallel, so the plugins have their own copy of the
+            // comments.
+            let mut comments = comments.map(|comments| {
+                let (leading, trailing) = comments.borrow_all();
+                (leading.clone(), trailing.clone())
+            });
+
+            let configs = self
+                .plugins
+                .iter()
+                .map(|(lint_plugin, _)| lint_plugin.clone())
+                .collect::<Vec<_>>();
+            let runner = LintPluginRunner {
+                plugins: self
+                    .plugins
+                    .iter()
+                    .map(|(_, plugin)| plugin.clone())
+                    .collect(),
+                plugin_env_vars: self.plugin_env_vars.clone(),
+                metadata_context,
+                source_map,
+                unresolved_mark,
+                plugin_runtime: plugin_runtime.clone(),
+            };
+
+            return swc_ecma_lints::plugin::plugin_rule(
+                &configs,
+                move |program| LintPluginInput::new(program, comments.take()),
+                move |index, input| runner.run(index, input),
+            )
+            .into_iter()
+            .collect();
+        }
+
+        Vec::new()
+    }
+}
+
+/// The program and the comments of a file, which are shared by lint plugins.
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+struct LintPluginInput {
+    program: Result<swc_common::plugin::serialized::PluginSerializedBytes>,
+    comments: Option<swc_common::comments::SingleThreadedComments>,
+}
+
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+impl LintPluginInput {
+    fn new(
+        program: Program,
+        comments: Option<(
+            swc_common::comments::SingleThreadedCommentsMapInner,
+            swc_common::comments::SingleThreadedCommentsMapInner,
+        )>,
+    ) -> Self {
+        use std::{cell::RefCell, rc::Rc};
+
+        use swc_common::{
+            comments::SingleThreadedComments,
+            plugin::serialized::{PluginSerializedBytes, VersionedSerializable},
+        };
+
+        LintPluginInput {
+            program: PluginSerializedBytes::try_serialize(&VersionedSerializable::new(program)),
+            comments: comments.map(|(leading, trailing)| {
+                SingleThreadedComments::from_leading_and_trailing(
+                    Rc::new(RefCell::new(leading)),
+                    Rc::new(RefCell::new(trailing)),
+                )
+            }),
+        }
+    }
+}
+
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+struct LintPluginRunner {
+    plugins: Vec<PluginConfig>,
+    plugin_env_vars: Option<std::sync::Arc<Vec<Atom>>>,
+    metadata_context: std::sync::Arc<swc_common::plugin::metadata::TransformPluginMetadataContext>,
+    source_map: std::sync::Arc<swc_common::SourceMap>,
+    unresolved_mark: swc_common::Mark,
+    plugin_runtime: Arc<dyn PluginRuntime>,
+}
+
+#[cfg(all(feature = "lint", feature = "plugin", not(target_arch = "wasm32")))]
+impl LintPluginRunner {
+    /// Runs the plugin at `index` of the configuration.
+    fn run(&self, index: usize, input: &LintPluginInput) {
+        let plugin = &self.plugins[index];
+
+        #[cfg(feature = "manual-tokio-runtime")]
+        let ret = self.run_inner(plugin, input);
+
+        #[cfg(not(feature = "manual-tokio-runtime"))]
+        let ret = {
+            let fut = async move { self.run_inner(plugin, input) };
+            if let Ok(handle) = tokio::runtime::Handle::try_current() {
+                handle.block_on(fut)
+            } else {
+                SHARED_RUNTIME.block_on(fut)
+            }
+        };
+
+        if let Err(err) = ret {
+            HANDLER.with(|handler| {
+                handler.err_with_code(
+                    &format!("failed to invoke `{}` as lint plugin: {err:#}", plugin.0),
+                    DiagnosticId::Error("plugin".into()),
+                );
+            });
+        }
+    }
+
+    fn run_inner(
+        &self,
+        plugin: &PluginConfig,
+        input: &LintPluginInput,
+    ) -> Result<(), anyhow::Error> {
+        let serialized = match &input.program {
+            Ok(serialized) => serialized,
+            Err(err) => anyhow::bail!("failed to serialize the program: {err:#}"),
+        };
+        let should_enable_comments_proxy = input.comments.is_some();
+
+        swc_plugin_proxy::COMMENTS.set(
+            &swc_plugin_proxy::HostCommentsStorage {
+                inner: input.comments.clone(),
+            },
+            || {
+                let plugin_module_bytes = crate::config::PLUGIN_MODULE_CACHE
+                    .inner
+                    .get()
+                    .unwrap()
+                    .lock()
+                    .get(&*self.plugin_runtime, &plugin.0)
+                    .expect("plugin module should be loaded");
+
+                let mut lint_plugin_executor = swc_plugin_runner::create_plugin_transform_executor(
+                    &self.source_map,
+                    &self.unresolved_mark,
+                    &self.metadata_context,
+                    self.plugin_env_vars.clone(),
+                    plugin_module_bytes,
+                    Some(plugin.1.clone()),
+                    self.plugin_runtime.clone(),
+                );
+
+                lint_plugin_executor.transform(serialized, Some(should_enable_comments_proxy))?;
+
+                Ok(())
+            },
+        )
+    }
+}
+
 #[cfg(feature = "plugin")]
 pub(crate) fn compile_wasm_plugins(
     cache_root: Option<&str>,
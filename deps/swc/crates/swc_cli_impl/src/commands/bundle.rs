@@ -1,10 +1,445 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context};
 use clap::Parser;
+use par_iter::prelude::*;
+use path_absolutize::Absolutize;
+use swc_core::{
+    atoms::Atom,
+    base::{
+        config::{Config, Options, SourceMapsConfig},
+        resolver::persistent_resolver,
+        Compiler, PrintArgs,
+    },
+    bundler::{node::loaders::swc::SwcLoader, BundleKind, Bundler, Hook, ModuleRecord, Resolve},
+    common::{FileName, Globals, Span, GLOBALS},
+    ecma::{
+        ast::{
+            Bool, Expr, IdentName, KeyValueProp, Lit, MemberExpr, MemberProp, MetaPropExpr,
+            MetaPropKind, Module, PropName, Str,
+        },
+        loader::{
+            resolve::Resolution,
+            resolvers::{lru::CachingResolver, node::NodeModulesResolver, tsc::TsConfigResolver},
+            TargetEnv, NODE_BUILTINS,
+        },
+    },
+};
+use url::Url;
+
+use super::compile::{emit_directory_output, new_compiler, parse_config};
 
+/// Configuration option for bundling files.
 #[derive(Parser)]
-pub struct BundleOptions {}
+pub struct BundleOptions {
+    /// Experimental: provide an additional JSON config object to override the
+    /// .swcrc. `jsc.paths` and `jsc.baseUrl` are also used to resolve imports.
+    #[clap(long = "config-json", value_parser = parse_config)]
+    config: Option<Config>,
+
+    /// The output directory. Each entry is bundled to `<out-dir>/<name>.js`,
+    /// where `<name>` is the file name of the entry without its extension.
+    #[clap(long)]
+    out_dir: PathBuf,
+
+    /// The environment to resolve imports for.
+    /// Values: browser (default), node
+    #[clap(long, value_parser = parse_target, default_value = "browser")]
+    target: TargetEnv,
+
+    /// Modules which are not bundled. Node.js builtins, with or without the
+    /// `node:` prefix, are always external when targeting node.
+    #[clap(long)]
+    external: Vec<String>,
+
+    /// Values: true (default)|false|inline
+    #[clap(long, default_value = "true")]
+    source_maps: String,
+
+    /// Minify the output.
+    #[clap(long)]
+    minify: bool,
+
+    /// Do not resolve symlinks of imported modules, like `node
+    /// --preserve-symlinks`.
+    #[clap(long)]
+    preserve_symlinks: bool,
+
+    /// Entry files to bundle
+    #[clap(required = true)]
+    entries: Vec<PathBuf>,
+}
+
+/// Builtin modules of Node.js which are only available with the `node:`
+/// prefix.
+const NODE_PREFIXED_BUILTINS: &[&str] = &[
+    "node:sea",
+    "node:sqlite",
+    "node:test",
+    "node:test/reporters",
+];
+
+fn parse_target(s: &str) -> Result<TargetEnv, String> {
+    match s {
+        "browser" => Ok(TargetEnv::Browser),
+        "node" => Ok(TargetEnv::Node),
+        _ => Err(format!(
+            "Invalid target '{s}'. Valid values are: browser, node"
+        )),
+    }
+}
+
+impl BundleOptions {
+    fn build_options(&self) -> Options {
+        Options {
+            config: self.config.to_owned().unwrap_or_default(),
+            ..Options::default()
+        }
+    }
+
+    fn source_maps(&self) -> SourceMapsConfig {
+        match self.source_maps.as_str() {
+            "false" => SourceMapsConfig::Bool(false),
+            "true" => SourceMapsConfig::Bool(true),
+            value => SourceMapsConfig::Str(value.to_string()),
+        }
+    }
+
+    /// Maps the name of each bundle to its entry file.
+    fn collect_entries(&self) -> anyhow::Result<HashMap<String, FileName>> {
+        let mut entries = HashMap::default();
+
+        for entry in &self.entries {
+            let path = entry.absolutize()?.into_owned();
+            let name = path
+                .file_stem()
+                .with_context(|| format!("invalid entry {}", entry.display()))?
+                .to_string_lossy()
+                .into_owned();
+
+            if entries.insert(name.clone(), FileName::Real(path)).is_some() {
+                bail!("Multiple entries are bundled to {name}.js");
+            }
+        }
+
+        Ok(entries)
+    }
+
+    fn build_resolver(&self, options: &Options) -> StrictResolver {
+        let jsc = &options.config.jsc;
+
+        let resolver: Box<dyn Resolve> = if jsc.paths.is_empty() {
+            Box::new(NodeModulesResolver::new(
+                self.target,
+                Default::default(),
+                self.preserve_symlinks,
+            ))
+        } else {
+            let paths = jsc
+                .paths
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+
+            Box::new(TsConfigResolver::new(
+                NodeModulesResolver::without_node_modules(
+                    self.target,
+                    Default::default(),
+                    self.preserve_symlinks,
+                ),
+                jsc.base_url.clone(),
+                paths,
+            ))
+        };
+
+        StrictResolver(match &jsc.experimental.resolution_cache {
+            // The persistent cache can not see paths checked by another cache, so it
+            // wraps the resolver directly. The cache is written when the resolver is
+            // dropped.
+            Some(cache_dir) => Box::new(persistent_resolver(
+                Path::new(cache_dir),
+                format!(
+                    "bundle {:?} {:?} {:?} {}",
+                    self.target, jsc.base_url, jsc.paths, self.preserve_symlinks
+                ),
+                resolver,
+            )),
+            None => Box::new(CachingResolver::new(40, resolver)),
+        })
+    }
+
+    fn external_modules(&self) -> Vec<Atom> {
+        let builtins = match self.target {
+            TargetEnv::Node => NODE_BUILTINS
+                .iter()
+                .flat_map(|name| [Atom::from(*name), Atom::from(format!("node:{name}"))])
+                .chain(NODE_PREFIXED_BUILTINS.iter().map(|name| Atom::from(*name)))
+                .collect(),
+            TargetEnv::Browser => Vec::new(),
+        };
+
+        builtins
+            .into_iter()
+            .chain(self.external.iter().map(|s| Atom::from(s.as_str())))
+            .collect()
+    }
+
+    fn bundle(&self, compiler: &Arc<Compiler>) -> anyhow::Result<Vec<(String, Module)>> {
+        let options = self.build_options();
+        let entries = self.collect_entries()?;
+
+        let loader = SwcLoader::new(compiler.clone(), options.clone());
+        let resolver = self.build_resolver(&options);
+
+        let globals = Globals::default();
+        GLOBALS.set(&globals, || {
+            let mut bundler = Bundler::new(
+                &globals,
+                compiler.cm.clone(),
+                loader,
+                resolver,
+                swc_core::bundler::Config {
+                    require: true,
+                    external_modules: self.external_modules(),
+                    ..Default::default()
+                },
+                Box::new(ImportMetaHook),
+            );
+
+            bundler
+                .bundle(entries)?
+                .into_iter()
+                .map(|bundle| match bundle.kind {
+                    BundleKind::Named { name } | BundleKind::Lib { name } => {
+                        Ok((name, bundle.module))
+                    }
+                    BundleKind::Dynamic => bail!("unimplemented: dynamic code splitting"),
+                })
+                .collect()
+        })
+    }
+
+    fn emit_bundle(
+        &self,
+        compiler: &Compiler,
+        out_dir: &Path,
+        name: &str,
+        module: &Module,
+    ) -> anyhow::Result<()> {
+        let output_file_path = out_dir.join(format!("{name}.js"));
+
+        // Defaults to es5
+        let target = self
+            .config
+            .as_ref()
+            .and_then(|config| config.jsc.target)
+            .unwrap_or_default();
+
+        let output = compiler.print(
+            module,
+            PrintArgs {
+                output_path: Some(output_file_path.clone()),
+                inline_sources_content: true,
+                source_map: self.source_maps(),
+                emit_source_map_columns: true,
+                codegen_config: swc_core::ecma::codegen::Config::default()
+                    .with_target(target)
+                    .with_minify(self.minify),
+                ..Default::default()
+            },
+        )?;
+
+        emit_directory_output(output, &output_file_path)
+    }
+}
 
 impl super::CommandRunner for BundleOptions {
     fn execute(&self) -> anyhow::Result<()> {
-        unimplemented!("Bundle command is not yet implemented")
+        let compiler = new_compiler();
+        let bundles = self.bundle(&compiler)?;
+
+        let out_dir = self.out_dir.absolutize()?.into_owned();
+
+        bundles.into_par_iter().try_for_each(|(name, module)| {
+            self.emit_bundle(&compiler, &out_dir, &name, &module)
+                .with_context(|| format!("failed to emit bundle {name}"))
+        })
+    }
+}
+
+/// Fails to resolve Node.js builtin modules which are not external, as
+/// [SwcLoader] loads them as empty modules.
+struct StrictResolver(Box<dyn Resolve>);
+
+impl Resolve for StrictResolver {
+    fn resolve(&self, base: &FileName, module_specifier: &str) -> anyhow::Result<Resolution> {
+        let resolution = self.0.resolve(base, module_specifier)?;
+
+        if let FileName::Custom(name) = &resolution.filename {
+            if name.starts_with("node:") {
+                bail!(
+                    "{module_specifier} is a builtin module of Node.js, which can't be bundled. \
+                     Pass it to --external"
+                );
+            }
+        }
+
+        Ok(resolution)
+    }
+}
+
+/// Provides `import.meta.url` and `import.meta.main` of bundled modules.
+struct ImportMetaHook;
+
+impl Hook for ImportMetaHook {
+    fn get_import_meta_props(
+        &self,
+        span: Span,
+        module_record: &ModuleRecord,
+    ) -> anyhow::Result<Vec<KeyValueProp>> {
+        let url = match &module_record.file_name {
+            FileName::Real(path) => Url::from_file_path(path)
+                .map_err(|()| anyhow!("failed to convert {} to a URL", path.display()))?
+                .to_string(),
+            file_name => file_name.to_string(),
+        };
+
+        Ok(vec![
+            KeyValueProp {
+                key: PropName::Ident(IdentName::new("url".into(), span)),
+                value: Box::new(Expr::Lit(Lit::Str(Str {
+                    span,
+                    raw: None,
+                    value: url.into(),
+                }))),
+            },
+            KeyValueProp {
+                key: PropName::Ident(IdentName::new("main".into(), span)),
+                value: Box::new(if module_record.is_entry {
+                    Expr::Member(MemberExpr {
+                        span,
+                        obj: Box::new(Expr::MetaProp(MetaPropExpr {
+                            span,
+                            kind: MetaPropKind::ImportMeta,
+                        })),
+                        prop: MemberProp::Ident(IdentName::new("main".into(), span)),
+                    })
+                } else {
+                    Expr::Lit(Lit::Bool(Bool { span, value: false }))
+                }),
+            },
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::{super::CommandRunner, *};
+
+    fn bundle_options(args: &[&str], entries: &[&Path], out_dir: &Path) -> BundleOptions {
+        let args = ["bundle".as_ref(), "--out-dir".as_ref(), out_dir.as_os_str()]
+            .into_iter()
+            .chain(args.iter().map(|arg| arg.as_ref()))
+            .chain(entries.iter().map(|entry| entry.as_os_str()));
+
+        BundleOptions::try_parse_from(args).unwrap()
+    }
+
+    #[test]
+    fn bundles_imported_modules() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let entry = dir.path().join("main.js");
+        fs::write(
+            &entry,
+            "import { name } from './name.js';\nconsole.log(name, import.meta.url, \
+             import.meta.main);\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("name.js"),
+            "export const name = 'bundled';\nconsole.log(import.meta.main);\n",
+        )
+        .unwrap();
+        let out_dir = dir.path().join("out");
+
+        bundle_options(&[], &[&entry], &out_dir).execute().unwrap();
+
+        let code = fs::read_to_string(out_dir.join("main.js")).unwrap();
+        assert!(
+            code.contains("'bundled'") || code.contains("\"bundled\""),
+            "{code}"
+        );
+        assert!(!code.contains("./name.js"), "{code}");
+        assert!(
+            code.contains(Url::from_file_path(&entry).unwrap().as_str()),
+            "{code}"
+        );
+        // Only the entry is the main module.
+        assert!(code.contains("main: false"), "{code}");
+        assert!(code.contains("main: import.meta.main"), "{code}");
+        assert!(out_dir.join("main.js.map").is_file());
+    }
+
+    #[test]
+    fn keeps_node_builtins_external() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let entry = dir.path().join("main.js");
+        fs::write(
+            &entry,
+            "import fs from 'fs';\nimport test from 'node:test';\nconsole.log(fs, test);\n",
+        )
+        .unwrap();
+        let out_dir = dir.path().join("out");
+
+        bundle_options(
+            &["--target", "node", "--source-maps", "false"],
+            &[&entry],
+            &out_dir,
+        )
+        .execute()
+        .unwrap();
+
+        let code = fs::read_to_string(out_dir.join("main.js")).unwrap();
+        assert!(code.contains("from 'fs'"), "{code}");
+        assert!(code.contains("from 'node:test'"), "{code}");
+        assert!(!out_dir.join("main.js.map").exists());
+    }
+
+    #[test]
+    fn fails_on_unknown_node_builtins() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let entry = dir.path().join("main.js");
+        fs::write(&entry, "import a from 'node:unknown';\nconsole.log(a);\n").unwrap();
+
+        let err = bundle_options(&["--target", "node"], &[&entry], &dir.path().join("out"))
+            .execute()
+            .unwrap_err();
+
+        assert!(
+            format!("{err:#}").contains(
+                "node:unknown is a builtin module of Node.js, which can't be bundled. Pass it to \
+                 --external"
+            ),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn fails_on_entries_with_the_same_name() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let a = dir.path().join("a/main.js");
+        let b = dir.path().join("b/main.js");
+
+        let err = bundle_options(&[], &[&a, &b], &dir.path().join("out"))
+            .collect_entries()
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "Multiple entries are bundled to main.js");
     }
 }
@@ -15,11 +15,12 @@ use glob::Pattern;
 use par_iter::prelude::*;
 use path_absolutize::Absolutize;
 use pathdiff::diff_paths;
-use paths::resolve_output_path;
+pub(super) use paths::resolve_output_path;
 use swc_core::{
     base::{
         config::{
-            default_swcrc, Config, ConfigFile, Options, PluginConfig, RootMode, SourceMapsConfig,
+            default_swcrc, flush_resolution_caches, Config, ConfigFile, Options, PluginConfig,
+            RootMode, SourceMapsConfig,
         },
         try_with_handler, Compiler, HandlerOpts, TransformOutput,
     },
@@ -121,14 +122,14 @@ pub struct CompileOptions {
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-enum InputOrigin {
+pub(super) enum InputOrigin {
     Explicit,
     Discovered,
 }
 
-struct InputFile {
-    path: PathBuf,
-    origin: InputOrigin,
+pub(super) struct InputFile {
+    pub(super) path: PathBuf,
+    pub(super) origin: InputOrigin,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -194,11 +195,11 @@ impl WatchInputPaths {
     }
 }
 
-fn parse_config(s: &str) -> Result<Config, serde_json::Error> {
+pub(super) fn parse_config(s: &str) -> Result<Config, serde_json::Error> {
     serde_json::from_str(s)
 }
 
-fn parse_root_mode(s: &str) -> Result<RootMode, String> {
+pub(super) fn parse_root_mode(s: &str) -> Result<RootMode, String> {
     match s {
         "root" => Ok(RootMode::Root),
         "upward" => Ok(RootMode::Upward),
@@ -210,20 +211,35 @@ fn parse_root_mode(s: &str) -> Result<RootMode, String> {
 }
 
 /// List of file extensions supported by default.
-static DEFAULT_EXTENSIONS: &[&str] = &["js", "jsx", "es6", "es", "mjs", "ts", "tsx", "cts", "mts"];
+pub(super) static DEFAULT_EXTENSIONS: &[&str] =
+    &["js", "jsx", "es6", "es", "mjs", "ts", "tsx", "cts", "mts"];
+
+/// Plugin imports which are explicitly relative, starting with `.` or `..`,
+/// are resolved from the current directory.
+pub(super) fn resolve_plugin_path(plugin: String) -> String {
+    let path = Path::new(&plugin);
+    match path.components().next() {
+        Some(Component::CurDir) | Some(Component::ParentDir) => {
+            path.absolutize().unwrap().display().to_string()
+        }
+        _ => plugin,
+    }
+}
 
-fn new_compiler() -> Arc<Compiler> {
+pub(super) fn new_compiler() -> Arc<Compiler> {
     let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
     Arc::new(Compiler::new(cm))
 }
 
-fn parse_ignore_pattern(ignore_pattern: Option<&str>) -> anyhow::Result<Option<Pattern>> {
+pub(super) fn parse_ignore_pattern(
+    ignore_pattern: Option<&str>,
+) -> anyhow::Result<Option<Pattern>> {
     ignore_pattern
         .map(|ignore_pattern| Pattern::new(ignore_pattern).context("invalid --ignore pattern"))
         .transpose()
 }
 
-fn is_compilable_extension(file_path: &Path, extensions: &[String]) -> bool {
+pub(super) fn is_compilable_extension(file_path: &Path, extensions: &[String]) -> bool {
     let extension = match file_path.extension().and_then(|ext| ext.to_str()) {
         Some(extension) => extension,
         None => return false,
@@ -276,7 +292,7 @@ fn collect_absolute_paths(paths: &[PathBuf]) -> BTreeSet<PathBuf> {
 
 /// Infer list of files from cli arguments.
 #[cfg_attr(debug_assertions, tracing::instrument(level = "info", skip_all))]
-fn collect_input_files(
+pub(super) fn collect_input_files(
     raw_files_input: &[PathBuf],
     ignore_pattern: Option<&Pattern>,
     excluded_dir: Option<&Path>,
@@ -368,7 +384,7 @@ fn execute_transform(
     .map_err(|error| error.to_pretty_error())
 }
 
-fn resolve_source_map_path(output_file_path: &Path) -> PathBuf {
+pub(super) fn resolve_source_map_path(output_file_path: &Path) -> PathBuf {
     let extension = match output_file_path.extension() {
         Some(extension) => format!("{}.map", extension.to_string_lossy()),
         None => String::from("map"),
@@ -397,7 +413,7 @@ fn remove_dir_if_exists(path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn emit_directory_output(
+pub(super) fn emit_directory_output(
     mut output: TransformOutput,
     output_file_path: &Path,
 ) -> anyhow::Result<()> {
@@ -459,18 +475,7 @@ impl CompileOptions {
             options.config.jsc.experimental.plugins.map(|plugins| {
                 plugins
                     .into_iter()
-                    .map(|plugin| {
-                        let path = Path::new(&plugin.0);
-                        PluginConfig(
-                            match path.components().next() {
-                                Some(Component::CurDir) | Some(Component::ParentDir) => {
-                                    path.absolutize().unwrap().display().to_string()
-                                }
-                                _ => plugin.0,
-                            },
-                            plugin.1,
-                        )
-                    })
+                    .map(|plugin| PluginConfig(resolve_plugin_path(plugin.0), plugin.1))
                     .collect()
             });
 
@@ -1108,6 +1113,7 @@ impl CompileOptions {
         if let Err(error) = self.execute_out_dir_once(out_dir) {
             eprintln!("{error:#}");
         }
+        flush_resolution_caches_in_watch_mode();
 
         loop {
             let changes = watcher.recv_changes()?;
@@ -1119,6 +1125,7 @@ impl CompileOptions {
                 ignore_pattern.as_ref(),
                 &watch_inputs,
             );
+            flush_resolution_caches_in_watch_mode();
         }
     }
 
@@ -1130,6 +1137,7 @@ impl CompileOptions {
         if let Err(error) = self.execute_existing_out_file_once(single_out_file) {
             eprintln!("{error:#}");
         }
+        flush_resolution_caches_in_watch_mode();
 
         let output_files = self.out_file_output_paths(single_out_file);
         let output_files = collect_absolute_paths(&output_files);
@@ -1186,10 +1194,19 @@ impl CompileOptions {
                 }
                 Err(error) => eprintln!("{error:#}"),
             }
+            flush_resolution_caches_in_watch_mode();
         }
     }
 }
 
+/// Resolvers live as long as the process, so persistent resolution caches are
+/// written after every watch batch instead of on drop.
+fn flush_resolution_caches_in_watch_mode() {
+    if let Err(error) = flush_resolution_caches() {
+        eprintln!("{error:#}");
+    }
+}
+
 impl super::CommandRunner for CompileOptions {
     fn execute(&self) -> anyhow::Result<()> {
         self.validate()?;
@@ -1207,7 +1224,7 @@ impl super::CommandRunner for CompileOptions {
                 bail!("--watch requires --out-file or --out-dir");
             }
         } else {
-            self.execute_once()
+            self.execute_once().and_then(|_| flush_resolution_caches())
         };
 
         result
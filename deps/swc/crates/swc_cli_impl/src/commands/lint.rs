@@ -1,10 +1,717 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{bail, Context};
 use clap::Parser;
+use par_iter::prelude::*;
+use swc_core::{
+    base::{
+        config::{default_swcrc, Config, ConfigFile, LintPlugins, Options, Rc, RootMode},
+        Compiler,
+    },
+    common::{
+        comments::SingleThreadedComments,
+        errors::{ColorConfig, Diagnostic, DiagnosticBuilder, Emitter, Handler, Level, HANDLER},
+        plugin::metadata::TransformPluginMetadataContext,
+        Mark, SourceFile, SourceMap, SyntaxContext, GLOBALS,
+    },
+    config::merge::Merge,
+    ecma::{
+        ast::Program,
+        lints::{
+            fix::{fix_until_stable, fixes_from_diagnostics},
+            rule::Rule,
+            rules::{self, LintParams},
+            suppression::{suppressed_rule, Suppressions},
+        },
+        transforms::base::resolver,
+    },
+};
+use swc_error_reporters::{
+    github::to_github_annotation,
+    handler::to_pretty_handler,
+    json::JsonRecord,
+    sarif::{to_sarif, SarifTool},
+    ToPrettyDiagnostic,
+};
+
+use self::cache::{LintCache, DEFAULT_CACHE_LOCATION};
+use super::compile::{
+    collect_input_files, is_compilable_extension, new_compiler, parse_config, parse_ignore_pattern,
+    parse_root_mode, resolve_plugin_path, InputOrigin, DEFAULT_EXTENSIONS,
+};
+
+mod cache;
 
+/// Configuration option for linting files.
 #[derive(Parser)]
-pub struct LintOptions {}
+pub struct LintOptions {
+    /// Experimental: provide an additional JSON config object to override the
+    /// .swcrc. Lint rules are configured with `jsc.lints`.
+    #[clap(long = "config-json", value_parser = parse_config)]
+    config: Option<Config>,
+
+    /// Path to a .swcrc file to use
+    #[clap(long)]
+    config_file: Option<PathBuf>,
+
+    /// The mode to use for resolving the project root and .swcrc file.
+    /// Values: root (default), upward, upward-optional
+    #[clap(long, value_parser = parse_root_mode)]
+    root_mode: Option<RootMode>,
+
+    /// List of glob paths to not lint.
+    #[clap(long)]
+    ignore: Option<String>,
+
+    /// Specify specific file extensions to lint.
+    #[clap(long)]
+    extensions: Option<Vec<String>>,
+
+    /// Output format of diagnostics.
+    /// Values: human (default), json, jsonl, sarif, github
+    #[clap(long, value_parser = parse_format, default_value = "human")]
+    format: LintFormat,
+
+    /// Apply fixes of lint rules to the files, and report the remaining
+    /// problems.
+    #[clap(long)]
+    fix: bool,
+
+    /// Report `swc-lint-disable` comments which suppress no problem.
+    #[clap(long)]
+    report_unused_suppressions: bool,
+
+    /// Skip files which are not changed since the last run, by reusing their
+    /// cached results.
+    #[clap(long)]
+    cache: bool,
+
+    /// Directory of the cache, which is cleared by deleting it.
+    /// Defaults to `.swc/lint`
+    #[clap(long, requires = "cache")]
+    cache_location: Option<PathBuf>,
+
+    /// Files or directories to lint
+    #[clap(required = true)]
+    files: Vec<PathBuf>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum LintFormat {
+    Human,
+    Json,
+    JsonLines,
+    Sarif,
+    Github,
+}
+
+fn parse_format(s: &str) -> Result<LintFormat, String> {
+    match s {
+        "human" => Ok(LintFormat::Human),
+        "json" => Ok(LintFormat::Json),
+        "jsonl" => Ok(LintFormat::JsonLines),
+        "sarif" => Ok(LintFormat::Sarif),
+        "github" => Ok(LintFormat::Github),
+        _ => Err(format!(
+            "Invalid format '{s}'. Valid values are: human, json, jsonl, sarif, github"
+        )),
+    }
+}
+
+/// Diagnostics of a file, in the order of their spans as the lint rules sort
+/// them before emitting.
+struct LintResult {
+    file_path: PathBuf,
+    diagnostics: Vec<Diagnostic>,
+    /// The number of applied fixes.
+    fixed: usize,
+}
+
+#[derive(Default, Clone)]
+struct Collector {
+    diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl Emitter for Collector {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        self.diagnostics
+            .lock()
+            .expect("Failed to access the diagnostics lock")
+            .push(db.take());
+    }
+}
+
+fn is_error(d: &Diagnostic) -> bool {
+    matches!(
+        d.level,
+        Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error
+    )
+}
+
+impl LintOptions {
+    fn build_options(&self) -> Options {
+        let config_file = self.config_file.as_ref().map(|config_file_path| {
+            ConfigFile::Str(config_file_path.to_string_lossy().to_string())
+        });
+
+        let mut options = Options {
+            config: self.config.to_owned().unwrap_or_default(),
+            config_file,
+            swcrc: default_swcrc(),
+            ..Options::default()
+        };
+
+        for plugin in &mut options.config.jsc.lints.plugins {
+            plugin.0 = resolve_plugin_path(std::mem::take(&mut plugin.0));
+        }
+
+        if let Some(root_mode) = self.root_mode {
+            options.root_mode = root_mode;
+        }
+
+        options
+    }
+
+    fn included_extensions(&self) -> Vec<String> {
+        self.extensions.clone().unwrap_or_else(|| {
+            DEFAULT_EXTENSIONS
+                .iter()
+                .map(|value| value.to_string())
+                .collect()
+        })
+    }
+
+    /// Files in directories are filtered by extension, like `swc compile`.
+    fn collect_lint_file_paths(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let ignore_pattern = parse_ignore_pattern(self.ignore.as_deref())?;
+        let extensions = self.included_extensions();
+
+        let mut file_paths = Vec::new();
+        for input in &self.files {
+            let inputs =
+                collect_input_files(std::slice::from_ref(input), ignore_pattern.as_ref(), None)?;
+
+            file_paths.extend(
+                inputs
+                    .into_iter()
+                    .filter(|input| {
+                        input.origin == InputOrigin::Explicit
+                            || is_compilable_extension(&input.path, &extensions)
+                    })
+                    .map(|input| input.path),
+            );
+        }
+
+        Ok(file_paths)
+    }
+
+    fn lint_file(
+        &self,
+        compiler: &Compiler,
+        options: &Options,
+        cache: Option<&LintCache>,
+        file_path: PathBuf,
+    ) -> anyhow::Result<LintResult> {
+        let fm = compiler
+            .cm
+            .load_file(&file_path)
+            .context("Failed to open the file")?;
+
+        let Some(swcrc) = compiler.read_config(options, &fm.name)? else {
+            // Excluded by .swcrc
+            return Ok(LintResult {
+                file_path,
+                diagnostics: Vec::new(),
+                fixed: 0,
+            });
+        };
+
+        let mut config = options.config.clone();
+        config.merge(swcrc);
+        config.adjust(&file_path);
+
+        // Infer the syntax from the extension if .swcrc doesn't specify it, like
+        // the default .swcrc.
+        if config.jsc.syntax.is_none() {
+            config.jsc.syntax = Rc::default()
+                .into_config(Some(&file_path))?
+                .and_then(|config| config.jsc.syntax);
+        }
+
+        let cache_key = cache
+            .map(|cache| cache.key(&config, self.report_unused_suppressions, &fm))
+            .transpose()?;
+
+        if let (Some(cache), Some(key)) = (cache, &cache_key) {
+            // Files with fixes are linted again to apply them.
+            if let Some(diagnostics) = cache.load(key, &fm).filter(|diagnostics| {
+                !self.fix || fixes_from_diagnostics(diagnostics, fm.start_pos).is_empty()
+            }) {
+                return Ok(LintResult {
+                    file_path,
+                    diagnostics,
+                    fixed: 0,
+                });
+            }
+        }
+
+        // Plugins are resolved once for all passes of fixes.
+        let lint_plugins = LintPlugins::get(
+            &config.jsc.lints.plugins,
+            config.jsc.experimental.cache_root.as_deref(),
+            config.jsc.experimental.plugin_env_vars.clone(),
+            &options.runtime_options,
+        )
+        .context("Failed to load lint plugins")?;
+
+        if !self.fix {
+            let (_, diagnostics) =
+                self.lint_source_file(compiler, options, &config, &lint_plugins, fm.clone());
+
+            if let (Some(cache), Some(key)) = (cache, &cache_key) {
+                // Failing to cache results should not fail linting.
+                if let Err(error) = cache.store(key, &fm, &diagnostics) {
+                    tracing::warn!(error = %error, "failed to store lint results in cache");
+                }
+            }
+
+            return Ok(LintResult {
+                file_path,
+                diagnostics,
+                fixed: 0,
+            });
+        }
+
+        // Source files can not be removed from a source map, so intermediate code
+        // is linted using a source map which is dropped after the pass.
+        let mut last = None;
+        let output = fix_until_stable(fm.src.to_string(), |code| -> anyhow::Result<_> {
+            let scratch;
+            let (pass_compiler, pass_fm) = if *fm.src == *code {
+                (compiler, fm.clone())
+            } else {
+                scratch = new_compiler();
+                let pass_fm = scratch
+                    .cm
+                    .new_source_file(fm.name.clone(), code.to_string());
+                (&*scratch, pass_fm)
+            };
+
+            let (parsed, diagnostics) = self.lint_source_file(
+                pass_compiler,
+                options,
+                &config,
+                &lint_plugins,
+                pass_fm.clone(),
+            );
+            let fixes = parsed.then(|| fixes_from_diagnostics(&diagnostics, pass_fm.start_pos));
+
+            // Only diagnostics of `fm` can be reported.
+            last = Arc::ptr_eq(&pass_fm, &fm).then_some(diagnostics);
+
+            Ok(fixes)
+        })?;
+
+        if output.applied > 0 {
+            fs::write(&file_path, &output.code).context("Failed to write the fixed code")?;
+        }
+
+        // Fixes are reverted if they produce invalid code.
+        let (fm, diagnostics) = match last {
+            Some(diagnostics) if *fm.src == *output.code => (fm, diagnostics),
+            _ => {
+                let fm = if *fm.src == *output.code {
+                    fm
+                } else {
+                    compiler.cm.new_source_file(fm.name.clone(), output.code)
+                };
+                let diagnostics = self
+                    .lint_source_file(compiler, options, &config, &lint_plugins, fm.clone())
+                    .1;
+                (fm, diagnostics)
+            }
+        };
+
+        if let Some(cache) = cache {
+            let key = cache.key(&config, self.report_unused_suppressions, &fm)?;
+            if let Err(error) = cache.store(&key, &fm, &diagnostics) {
+                tracing::warn!(error = %error, "failed to store lint results in cache");
+            }
+        }
+
+        Ok(LintResult {
+            file_path,
+            diagnostics,
+            fixed: output.applied,
+        })
+    }
+
+    /// Files which fail to be linted are reported as errors without spans, so
+    /// other files are still linted.
+    fn lint_files(
+        &self,
+        compiler: &Compiler,
+        options: &Options,
+        cache: Option<&LintCache>,
+        file_paths: Vec<PathBuf>,
+    ) -> Vec<LintResult> {
+        file_paths
+            .into_par_iter()
+            .map(|file_path| {
+                GLOBALS.set(&Default::default(), || {
+                    self.lint_file(compiler, options, cache, file_path.clone())
+                        .unwrap_or_else(|err| LintResult {
+                            diagnostics: vec![Diagnostic::new(
+                                Level::Error,
+                                &format!("Failed to lint {}: {err:#}", file_path.display()),
+                            )],
+                            file_path,
+                            fixed: 0,
+                        })
+                })
+            })
+            .collect()
+    }
+
+    /// Returns diagnostics of `fm`, and `false` if it has syntax errors.
+    fn lint_source_file(
+        &self,
+        compiler: &Compiler,
+        options: &Options,
+        config: &Config,
+        lint_plugins: &LintPlugins,
+        fm: Arc<SourceFile>,
+    ) -> (bool, Vec<Diagnostic>) {
+        let syntax = config.jsc.syntax.unwrap_or_default();
+        let es_version = config.jsc.target.unwrap_or_default();
+        let is_module = config.is_module.unwrap_or_default();
+
+        let collector = Collector::default();
+        let handler = Handler::with_emitter(true, false, Box::new(collector.clone()));
+
+        let filename = fm.name.to_string();
+
+        let parsed = HANDLER.set(&handler, || {
+            let comments = SingleThreadedComments::default();
+            let Ok(mut program) =
+                compiler.parse_js(fm, &handler, es_version, syntax, is_module, Some(&comments))
+            else {
+                // Syntax errors are reported to the handler.
+                return false;
+            };
+
+            let unresolved_mark = Mark::new();
+            let top_level_mark = Mark::new();
+            program.mutate(resolver(
+                unresolved_mark,
+                top_level_mark,
+                syntax.typescript(),
+            ));
+
+            let suppressions = {
+                let (leading, trailing) = comments.borrow_all();
+                Suppressions::new(
+                    compiler.cm.clone(),
+                    leading.values().chain(trailing.values()).flatten(),
+                )
+                .report_unused(self.report_unused_suppressions)
+            };
+
+            let mut rules = rules::all(LintParams {
+                program: &program,
+                lint_config: &config.jsc.lints,
+                unresolved_ctxt: SyntaxContext::empty().apply_mark(unresolved_mark),
+                top_level_ctxt: SyntaxContext::empty().apply_mark(top_level_mark),
+                es_version,
+                source_map: compiler.cm.clone(),
+            });
+
+            rules.extend(lint_plugins.rules(
+                Arc::new(TransformPluginMetadataContext::new(
+                    Some(filename),
+                    options.env_name.clone(),
+                    None,
+                )),
+                Some(&comments),
+                compiler.cm.clone(),
+                unresolved_mark,
+            ));
+
+            let mut rules = suppressed_rule(rules, suppressions);
+
+            match &program {
+                Program::Module(module) => rules.lint_module(module),
+                Program::Script(script) => rules.lint_script(script),
+            }
+
+            true
+        });
+
+        let diagnostics = std::mem::take(
+            &mut *collector
+                .diagnostics
+                .lock()
+                .expect("Failed to access the diagnostics lock"),
+        );
+
+        (parsed, diagnostics)
+    }
+
+    fn print_human(&self, cm: &SourceMap, results: &[LintResult]) {
+        let handler = to_pretty_handler(ColorConfig::Auto);
+
+        for d in results.iter().flat_map(|result| &result.diagnostics) {
+            eprintln!("{}", d.to_pretty_string(cm, false, &handler));
+        }
+    }
+
+    fn print_json(&self, cm: &SourceMap, results: &[LintResult]) -> anyhow::Result<()> {
+        let records = results
+            .iter()
+            .flat_map(|result| {
+                result
+                    .diagnostics
+                    .iter()
+                    .map(|d| json_record(cm, &result.file_path, d))
+            })
+            .collect::<Vec<_>>();
+
+        println!("{}", serde_json::to_string_pretty(&records)?);
+
+        Ok(())
+    }
+
+    fn print_json_lines(&self, cm: &SourceMap, results: &[LintResult]) -> anyhow::Result<()> {
+        for result in results {
+            for d in &result.diagnostics {
+                println!(
+                    "{}",
+                    serde_json::to_string(&json_record(cm, &result.file_path, d))?
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn print_sarif(&self, cm: &SourceMap, results: &[LintResult]) -> anyhow::Result<()> {
+        let tool = SarifTool {
+            version: Some(env!("CARGO_PKG_VERSION").into()),
+            ..Default::default()
+        };
+        let sarif = to_sarif(
+            cm,
+            &tool,
+            results.iter().flat_map(|result| &result.diagnostics),
+        );
+
+        println!("{}", serde_json::to_string_pretty(&sarif)?);
+
+        Ok(())
+    }
+
+    fn print_github(&self, cm: &SourceMap, results: &[LintResult]) {
+        for d in results.iter().flat_map(|result| &result.diagnostics) {
+            println!("{}", to_github_annotation(cm, d));
+        }
+    }
+}
+
+/// Diagnostics without spans, like failures to read a file, are reported with
+/// the path of the file.
+fn json_record(cm: &SourceMap, file_path: &Path, d: &Diagnostic) -> JsonRecord {
+    let mut record = JsonRecord::new(cm, d);
+    record
+        .diagnostic
+        .filename
+        .get_or_insert_with(|| file_path.to_string_lossy().into_owned());
+    record
+}
 
 impl super::CommandRunner for LintOptions {
     fn execute(&self) -> anyhow::Result<()> {
-        unimplemented!("Lint command is not yet implemented")
+        let file_paths = self.collect_lint_file_paths()?;
+        let compiler = new_compiler();
+        let options = self.build_options();
+        let cache = self
+            .cache
+            .then(|| {
+                LintCache::new(
+                    self.cache_location
+                        .clone()
+                        .unwrap_or_else(|| PathBuf::from(DEFAULT_CACHE_LOCATION)),
+                )
+            })
+            .transpose()?;
+
+        let results = self.lint_files(&compiler, &options, cache.as_ref(), file_paths);
+
+        match self.format {
+            LintFormat::Human => self.print_human(&compiler.cm, &results),
+            LintFormat::Json => self.print_json(&compiler.cm, &results)?,
+            LintFormat::JsonLines => self.print_json_lines(&compiler.cm, &results)?,
+            LintFormat::Sarif => self.print_sarif(&compiler.cm, &results)?,
+            LintFormat::Github => self.print_github(&compiler.cm, &results),
+        }
+
+        let (errors, warnings) = results.iter().flat_map(|result| &result.diagnostics).fold(
+            (0, 0),
+            |(errors, warnings), d| match d.level {
+                _ if is_error(d) => (errors + 1, warnings),
+                Level::Warning => (errors, warnings + 1),
+                _ => (errors, warnings),
+            },
+        );
+
+        let fixed = results.iter().map(|result| result.fixed).sum::<usize>();
+
+        if self.format == LintFormat::Human && fixed > 0 {
+            eprintln!("Fixed {fixed} problem(s)");
+        }
+
+        if self.format == LintFormat::Human && errors + warnings > 0 {
+            eprintln!("Found {errors} error(s) and {warnings} warning(s)");
+        }
+
+        if errors > 0 {
+            bail!("Lint failed with {errors} error(s)");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::CommandRunner, *};
+
+    const CONFIG: &str =
+        r#"{ "jsc": { "lints": { "no-debugger": ["error"], "no-var": ["warning"] } } }"#;
+
+    fn lint_options(args: &[&Path]) -> LintOptions {
+        let args = ["lint".as_ref(), "--config-json".as_ref(), CONFIG.as_ref()]
+            .into_iter()
+            .chain(args.iter().map(|path| path.as_os_str()));
+
+        LintOptions::try_parse_from(args).unwrap()
+    }
+
+    fn lint(options: &LintOptions) -> Vec<LintResult> {
+        let compiler = new_compiler();
+        let file_paths = options.collect_lint_file_paths().unwrap();
+
+        options.lint_files(&compiler, &options.build_options(), None, file_paths)
+    }
+
+    fn messages(result: &LintResult) -> Vec<String> {
+        result.diagnostics.iter().map(|d| d.message()).collect()
+    }
+
+    #[test]
+    fn reports_files_which_fail_to_lint() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let invalid = dir.path().join("invalid.js");
+        let debugger = dir.path().join("debugger.js");
+        fs::write(&invalid, b"\xff\xfe").unwrap();
+        fs::write(&debugger, "debugger;\n").unwrap();
+
+        let options = lint_options(&[&invalid, &debugger]);
+        let results = lint(&options);
+
+        assert_eq!(results.len(), 2);
+        let invalid_messages = messages(&results[0]);
+        assert_eq!(invalid_messages.len(), 1);
+        assert!(
+            invalid_messages[0].starts_with(&format!("Failed to lint {}: ", invalid.display())),
+            "{invalid_messages:?}"
+        );
+        assert!(is_error(&results[0].diagnostics[0]));
+        assert_eq!(
+            results[1].diagnostics.len(),
+            1,
+            "{:?}",
+            messages(&results[1])
+        );
+        assert!(is_error(&results[1].diagnostics[0]));
+
+        let err = options.execute().unwrap_err();
+        assert_eq!(err.to_string(), "Lint failed with 2 error(s)");
+    }
+
+    #[test]
+    fn passes_with_warnings() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("var.js");
+        fs::write(&file, "var a = 1;\nexport { a };\n").unwrap();
+
+        let options = lint_options(&[&file]);
+        let results = lint(&options);
+
+        assert_eq!(
+            results[0].diagnostics.len(),
+            1,
+            "{:?}",
+            messages(&results[0])
+        );
+        assert_eq!(results[0].diagnostics[0].level, Level::Warning);
+        options.execute().unwrap();
+    }
+
+    #[test]
+    fn sorts_diagnostics_by_span() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("var.js");
+        fs::write(
+            &file,
+            "// swc-lint-disable-next-line no-var\nlet a = 1;\ndebugger;\nexport { a };\n",
+        )
+        .unwrap();
+
+        // Unused directives are only known after all rules have run.
+        let options = LintOptions {
+            report_unused_suppressions: true,
+            ..lint_options(&[&file])
+        };
+        let results = lint(&options);
+
+        assert_eq!(
+            results[0]
+                .diagnostics
+                .iter()
+                .map(|d| d.level)
+                .collect::<Vec<_>>(),
+            [Level::Warning, Level::Error],
+            "{:?}",
+            messages(&results[0])
+        );
+    }
+
+    #[test]
+    fn fixes_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("var.js");
+        fs::write(&file, "var a = 1;\nexport { a };\n").unwrap();
+
+        let options = LintOptions {
+            fix: true,
+            ..lint_options(&[&file])
+        };
+        let results = lint(&options);
+
+        assert_eq!(results[0].fixed, 1);
+        assert_eq!(
+            results[0].diagnostics.len(),
+            0,
+            "{:?}",
+            messages(&results[0])
+        );
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "let a = 1;\nexport { a };\n"
+        );
     }
 }
@@ -0,0 +1,340 @@
+//! On-disk cache of lint results.
+//!
+//! Each file has an entry, which is named after its file name and is replaced
+//! when the file is linted again. Entries are keyed on the file name, the
+//! content of the file, the configuration which affects linting and the
+//! version of swc, and an entry with another key is a miss.
+//!
+//! Entries of files which are deleted or renamed are not removed. The cache is
+//! cleared by deleting its directory.
+
+use std::{
+    fs,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use swc_core::{
+    base::config::Config,
+    common::{
+        errors::{Diagnostic, DiagnosticId},
+        BytePos, MultiSpan, SourceFile, Span,
+    },
+};
+
+use super::is_error;
+
+/// The default directory of the cache, next to the cache of plugins.
+pub const DEFAULT_CACHE_LOCATION: &str = ".swc/lint";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntry {
+    key: String,
+    /// The start position of the file when the diagnostics were reported.
+    start_pos: BytePos,
+    diagnostics: Vec<Diagnostic>,
+}
+
+pub struct LintCache {
+    dir: PathBuf,
+    /// Used to name temporary files, as the same entry may be stored by
+    /// multiple threads.
+    tmp_count: AtomicUsize,
+}
+
+impl LintCache {
+    pub fn new(dir: PathBuf) -> anyhow::Result<Self> {
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create lint cache {}", dir.display()))?;
+
+        Ok(Self {
+            dir,
+            tmp_count: AtomicUsize::new(0),
+        })
+    }
+
+    /// Returns the key of the result of linting `fm` with `config`.
+    pub fn key(
+        &self,
+        config: &Config,
+        report_unused_suppressions: bool,
+        fm: &SourceFile,
+    ) -> anyhow::Result<String> {
+        // Plugins may be rebuilt without changes of the configuration.
+        let plugins = config
+            .jsc
+            .lints
+            .plugins
+            .iter()
+            .map(|plugin| {
+                let modified = fs::metadata(&plugin.0)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .map(|modified| modified.as_nanos().to_string());
+
+                json!({ "name": plugin.0, "modified": modified })
+            })
+            .collect::<Vec<_>>();
+
+        let options = json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "syntax": config.jsc.syntax,
+            "target": config.jsc.target,
+            "isModule": config.is_module,
+            "lints": config.jsc.lints,
+            "plugins": plugins,
+            "pluginEnvVars": config.jsc.experimental.plugin_env_vars,
+            "reportUnusedSuppressions": report_unused_suppressions,
+        });
+
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(serde_json::to_string(&options)?.as_bytes());
+        // Rules and diagnostics may depend on the file name.
+        hasher.update(fm.name.to_string().as_bytes());
+        hasher.update(&[0]);
+        hasher.update(fm.src.as_bytes());
+
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+
+    /// Returns the cached diagnostics of `fm`, with spans of `fm`.
+    pub fn load(&self, key: &str, fm: &SourceFile) -> Option<Vec<Diagnostic>> {
+        let entry = fs::read(self.entry_path(fm)).ok()?;
+        let entry = serde_json::from_slice::<CacheEntry>(&entry)
+            .ok()
+            .filter(|entry| entry.key == key)?;
+
+        let len = fm.end_pos.0 - fm.start_pos.0;
+        let mut is_valid = true;
+
+        let mut diagnostics = entry.diagnostics;
+        for d in &mut diagnostics {
+            map_spans(d, &mut |pos| match pos.0.checked_sub(entry.start_pos.0) {
+                Some(offset) if offset <= len => BytePos(fm.start_pos.0 + offset),
+                _ => {
+                    is_valid = false;
+                    pos
+                }
+            });
+        }
+
+        is_valid.then_some(diagnostics)
+    }
+
+    /// Stores `diagnostics` of `fm`, replacing the previous entry of the file.
+    ///
+    /// Results with errors which are not reported by lint rules, like syntax
+    /// errors and failures of plugins, are not stored, as they may be
+    /// transient. Results with spans of other files are not stored either.
+    pub fn store(
+        &self,
+        key: &str,
+        fm: &SourceFile,
+        diagnostics: &[Diagnostic],
+    ) -> anyhow::Result<()> {
+        if diagnostics
+            .iter()
+            .any(|d| is_error(d) && !matches!(d.code, Some(DiagnosticId::Lint(..))))
+        {
+            return Ok(());
+        }
+
+        let mut is_in_file = true;
+        for d in diagnostics {
+            map_spans(&mut d.clone(), &mut |pos| {
+                is_in_file &= fm.start_pos <= pos && pos <= fm.end_pos;
+                pos
+            });
+        }
+        if !is_in_file {
+            return Ok(());
+        }
+
+        let entry = serde_json::to_vec(&CacheEntry {
+            key: key.to_string(),
+            start_pos: fm.start_pos,
+            diagnostics: diagnostics.to_vec(),
+        })?;
+
+        // Entries are written at once, so they are never read partially.
+        let path = self.entry_path(fm);
+        let tmp_path = path.with_extension(format!(
+            "{}.{}.tmp",
+            std::process::id(),
+            self.tmp_count.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        fs::write(&tmp_path, entry)
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .with_context(|| format!("Failed to write lint cache {}", path.display()))
+    }
+
+    /// Returns the path of the entry of `fm`.
+    fn entry_path(&self, fm: &SourceFile) -> PathBuf {
+        let name = blake3::hash(fm.name.to_string().as_bytes());
+        self.dir.join(format!("{}.json", name.to_hex()))
+    }
+}
+
+/// Applies `op` to positions of all spans of `d`, except dummy ones.
+fn map_spans(d: &mut Diagnostic, op: &mut impl FnMut(BytePos) -> BytePos) {
+    let mut map_span = |span: Span| {
+        if span.is_dummy() {
+            span
+        } else {
+            Span::new(op(span.lo), op(span.hi))
+        }
+    };
+
+    d.span = map_multi_span(&d.span, &mut map_span);
+
+    for child in &mut d.children {
+        child.span = map_multi_span(&child.span, &mut map_span);
+        child.render_span = child
+            .render_span
+            .as_ref()
+            .map(|render_span| map_multi_span(render_span, &mut map_span));
+    }
+
+    for part in d
+        .suggestions
+        .iter_mut()
+        .flat_map(|suggestion| &mut suggestion.substitutions)
+        .flat_map(|substitution| &mut substitution.parts)
+    {
+        part.span = map_span(part.span);
+    }
+}
+
+fn map_multi_span(span: &MultiSpan, op: &mut impl FnMut(Span) -> Span) -> MultiSpan {
+    let mut mapped = MultiSpan::from_spans(span.primary_spans().iter().map(|&s| op(s)).collect());
+
+    // Primary spans without labels are also returned as labels.
+    for label in span.span_labels() {
+        if let Some(text) = label.label {
+            mapped.push_span_label(op(label.span), text);
+        }
+    }
+
+    mapped
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use swc_core::{
+        base::config::Config,
+        common::{
+            errors::{Diagnostic, DiagnosticId, Level},
+            sync::Lrc,
+            BytePos, FileName, SourceFile, SourceMap, Span,
+        },
+    };
+
+    use super::LintCache;
+
+    fn source_file(cm: &SourceMap, name: &str, src: &str) -> Lrc<SourceFile> {
+        cm.new_source_file(FileName::Real(PathBuf::from(name)).into(), src.to_string())
+    }
+
+    fn lint_warning(fm: &SourceFile) -> Diagnostic {
+        let mut d = Diagnostic::new_with_code(
+            Level::Warning,
+            Some(DiagnosticId::Lint("no-var".into())),
+            "Unexpected var, use let or const instead",
+        );
+        d.set_span(Span::new(fm.start_pos, fm.start_pos + BytePos(3)));
+        d
+    }
+
+    #[test]
+    fn loads_stored_diagnostics() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::new(dir.path().join("lint")).unwrap();
+        let config = Config::default();
+
+        let cm = SourceMap::default();
+        let fm = source_file(&cm, "a.js", "var a = 1;");
+        let key = cache.key(&config, false, &fm).unwrap();
+        assert_eq!(cache.load(&key, &fm), None);
+
+        cache.store(&key, &fm, &[lint_warning(&fm)]).unwrap();
+
+        // Spans are mapped to the file loaded again.
+        let _ = source_file(&cm, "b.js", "let b = 1;");
+        let loaded = source_file(&cm, "a.js", "var a = 1;");
+        let key = cache.key(&config, false, &loaded).unwrap();
+        assert_eq!(cache.load(&key, &loaded), Some(vec![lint_warning(&loaded)]));
+    }
+
+    #[test]
+    fn misses_changed_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::new(dir.path().to_path_buf()).unwrap();
+        let config = Config::default();
+
+        let cm = SourceMap::default();
+        let fm = source_file(&cm, "a.js", "var a = 1;");
+        let key = cache.key(&config, false, &fm).unwrap();
+        cache.store(&key, &fm, &[lint_warning(&fm)]).unwrap();
+
+        let changed = source_file(&cm, "a.js", "var a = 2;");
+        let changed_key = cache.key(&config, false, &changed).unwrap();
+        assert_ne!(changed_key, key);
+        assert_eq!(cache.load(&changed_key, &changed), None);
+
+        // Files with the same content have their own entries.
+        let renamed = source_file(&cm, "b.js", "var a = 1;");
+        let renamed_key = cache.key(&config, false, &renamed).unwrap();
+        assert_ne!(renamed_key, key);
+        assert_eq!(cache.load(&renamed_key, &renamed), None);
+
+        assert_ne!(cache.key(&config, true, &fm).unwrap(), key);
+    }
+
+    #[test]
+    fn replaces_entries_of_changed_files() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::new(dir.path().to_path_buf()).unwrap();
+        let config = Config::default();
+
+        let cm = SourceMap::default();
+        let fm = source_file(&cm, "a.js", "var a = 1;");
+        let key = cache.key(&config, false, &fm).unwrap();
+        cache.store(&key, &fm, &[lint_warning(&fm)]).unwrap();
+
+        let changed = source_file(&cm, "a.js", "let a = 1;");
+        let changed_key = cache.key(&config, false, &changed).unwrap();
+        cache.store(&changed_key, &changed, &[]).unwrap();
+
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+        assert_eq!(cache.load(&changed_key, &changed), Some(Vec::new()));
+        assert_eq!(cache.load(&key, &fm), None);
+    }
+
+    #[test]
+    fn skips_results_with_other_errors() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let cache = LintCache::new(dir.path().to_path_buf()).unwrap();
+
+        let cm = SourceMap::default();
+        let fm = source_file(&cm, "a.js", "var a = ;");
+        let key = cache.key(&Config::default(), false, &fm).unwrap();
+        cache
+            .store(
+                &key,
+                &fm,
+                &[Diagnostic::new(Level::Error, "Expression expected")],
+            )
+            .unwrap();
+
+        assert_eq!(cache.load(&key, &fm), None);
+    }
+}
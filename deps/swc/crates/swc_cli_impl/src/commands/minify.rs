@@ -1,10 +1,702 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
 use clap::Parser;
+use par_iter::prelude::*;
+use serde_json::{Map, Value};
+use swc_core::{
+    base::{
+        config::JsMinifyOptions,
+        sourcemap::{DecodedMap, SourceMap as RawSourceMap, SourceMapIndex, SourceMapSection},
+        try_with_handler, Compiler, HandlerOpts, JsMinifyExtras, TransformOutput,
+    },
+    common::{errors::ColorConfig, SourceFile, GLOBALS},
+};
+
+use super::compile::{
+    collect_input_files, emit_directory_output, is_compilable_extension, new_compiler,
+    parse_ignore_pattern, resolve_output_path, resolve_source_map_path, InputOrigin,
+};
 
+/// Configuration option for minifying files, which is compatible with the
+/// `terser` command.
 #[derive(Parser)]
-pub struct MinifyOptions {}
+pub struct MinifyOptions {
+    /// Path to a JSON file of minify options, which is the same as the second
+    /// argument of `minify()` of terser and @swc/core. Other flags override
+    /// options of the file.
+    #[clap(long)]
+    config_file: Option<PathBuf>,
+
+    /// Enable the compressor, optionally with terser compress options like
+    /// `passes=2,drop_console`. A JSON object is also accepted.
+    #[clap(short, long)]
+    compress: Option<Option<String>>,
+
+    /// Enable the mangler, optionally with terser mangle options like
+    /// `reserved=['$'],keep_classnames`. A JSON object is also accepted.
+    #[clap(short, long)]
+    mangle: Option<Option<String>>,
+
+    /// Emit source maps, optionally with terser source map options:
+    /// `content=<input source map>`, `url=<sourceMappingURL>`,
+    /// `root=<sourceRoot>` and `includeSources`. Requires --out-file or
+    /// --out-dir.
+    #[clap(long)]
+    source_map: Option<Option<String>>,
+
+    /// Treat input files as ES modules, which implies --toplevel.
+    #[clap(long)]
+    module: bool,
+
+    /// Compress and mangle variables of the top-level scope.
+    #[clap(long)]
+    toplevel: bool,
+
+    /// The ECMAScript version of the output, like `2015`.
+    #[clap(long)]
+    ecma: Option<String>,
+
+    /// Do not mangle or drop class names.
+    #[clap(long)]
+    keep_classnames: bool,
+
+    /// Do not mangle or drop function names.
+    #[clap(long)]
+    keep_fnames: bool,
+
+    /// List of glob paths to not minify.
+    #[clap(long)]
+    ignore: Option<String>,
+
+    /// Specify specific file extensions to minify.
+    #[clap(long)]
+    extensions: Option<Vec<String>>,
+
+    /// Minify all input files into a single file.
+    #[clap(short = 'o', long, group = "output")]
+    out_file: Option<PathBuf>,
+
+    /// The output directory
+    #[clap(long, group = "output")]
+    out_dir: Option<PathBuf>,
+
+    /// Remove the leading directory from emitted output paths.
+    #[clap(long)]
+    strip_leading_paths: bool,
+
+    /// Files or directories to minify
+    #[clap(required = true)]
+    files: Vec<PathBuf>,
+}
+
+/// List of file extensions minified by default.
+static DEFAULT_EXTENSIONS: &[&str] = &["js", "mjs", "cjs"];
+
+/// Parses terser-style options like `passes=2,drop_console,pure_funcs=['a']`.
+///
+/// A key without a value is `true`. Values are parsed as JSON, with single
+/// quoted strings, and are strings if they are not valid JSON.
+fn parse_terser_options(s: &str) -> anyhow::Result<Map<String, Value>> {
+    let s = s.trim();
+    if s.starts_with('{') {
+        return serde_json::from_str(s).context("invalid JSON options");
+    }
+
+    let mut options = Map::new();
+
+    for item in split_top_level(s) {
+        let item = item.trim();
+        if item.is_empty() {
+            continue;
+        }
+
+        let (key, value) = match item.split_once('=') {
+            Some((key, value)) => {
+                let value = value.trim();
+                let json = single_quotes_to_json(value);
+
+                (
+                    key.trim(),
+                    serde_json::from_str(&json).unwrap_or_else(|_| Value::from(value)),
+                )
+            }
+            None => (item, Value::Bool(true)),
+        };
+
+        options.insert(key.to_string(), value);
+    }
+
+    Ok(options)
+}
+
+/// Converts single quoted strings in `s` to double quoted ones.
+fn single_quotes_to_json(s: &str) -> String {
+    let mut json = String::with_capacity(s.len());
+    let mut quote = None;
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(_), '\\') => match chars.next() {
+                // `\'` is not a valid escape in JSON.
+                Some('\'') => json.push('\''),
+                Some(c) => {
+                    json.push('\\');
+                    json.push(c);
+                }
+                None => json.push('\\'),
+            },
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                json.push('"');
+            }
+            (Some(q), c) if c == q => {
+                quote = None;
+                json.push('"');
+            }
+            (Some('\''), '"') => json.push_str("\\\""),
+            _ => json.push(c),
+        }
+    }
+
+    json
+}
+
+/// Splits `s` by commas which are not in brackets or quotes.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+    let mut quote = None;
+    let mut start = 0;
+
+    for (idx, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '{' | '(') => depth += 1,
+            (None, ']' | '}' | ')') => depth = depth.saturating_sub(1),
+            (None, ',') if depth == 0 => {
+                items.push(&s[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&s[start..]);
+
+    items
+}
+
+/// `true` if the flag is given without options, or the options.
+fn flag_options(value: &Option<String>) -> anyhow::Result<Value> {
+    match value {
+        Some(s) => Ok(Value::Object(parse_terser_options(s)?)),
+        None => Ok(Value::Bool(true)),
+    }
+}
+
+fn minify_file(
+    compiler: &Compiler,
+    fm: Arc<SourceFile>,
+    options: &JsMinifyOptions,
+) -> anyhow::Result<TransformOutput> {
+    try_with_handler(
+        compiler.cm.clone(),
+        HandlerOpts {
+            color: ColorConfig::Always,
+            skip_filename: false,
+        },
+        |handler| {
+            GLOBALS.set(&Default::default(), || {
+                compiler.minify(fm, handler, options, JsMinifyExtras::default())
+            })
+        },
+    )
+    .map_err(|error| error.to_pretty_error())
+}
+
+/// Writes `map` next to `output_path`. Like terser, `code` refers to the source
+/// map only if a URL is given.
+fn emit_source_map(
+    code: &mut String,
+    map: &[u8],
+    output_path: &Path,
+    source_map_url: Option<&str>,
+) -> anyhow::Result<()> {
+    fs::write(resolve_source_map_path(output_path), map).context("failed to write source map")?;
+
+    if let Some(url) = source_map_url {
+        code.push_str("\n//# sourceMappingURL=");
+        code.push_str(url);
+    }
+
+    Ok(())
+}
+
+impl MinifyOptions {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.strip_leading_paths && self.out_dir.is_none() {
+            bail!("--strip-leading-paths requires --out-dir");
+        }
+
+        if self.source_map.is_some() && self.out_file.is_none() && self.out_dir.is_none() {
+            bail!("--source-map requires --out-file or --out-dir");
+        }
+
+        Ok(())
+    }
+
+    /// Builds options from the config file and flags.
+    ///
+    /// Like terser, the compressor and the mangler are disabled unless they are
+    /// enabled by flags or the config file.
+    fn build_minify_options(&self) -> anyhow::Result<(JsMinifyOptions, Option<String>)> {
+        let mut options = match &self.config_file {
+            Some(path) => {
+                let config = fs::read_to_string(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?;
+                serde_json::from_str(&config)
+                    .with_context(|| format!("failed to parse {}", path.display()))?
+            }
+            None => Map::new(),
+        };
+
+        options.entry("compress").or_insert(Value::Bool(false));
+        options.entry("mangle").or_insert(Value::Bool(false));
+
+        if let Some(compress) = &self.compress {
+            options.insert("compress".into(), flag_options(compress)?);
+        }
+
+        if let Some(mangle) = &self.mangle {
+            options.insert("mangle".into(), flag_options(mangle)?);
+        }
+
+        // Unlike the API, input files may be either scripts or modules by default.
+        if self.module {
+            options.insert("module".into(), Value::Bool(true));
+        } else {
+            options.entry("module").or_insert(Value::from("unknown"));
+        }
+
+        if self.toplevel {
+            options.insert("toplevel".into(), Value::Bool(true));
+        }
+
+        if let Some(ecma) = &self.ecma {
+            options.insert(
+                "ecma".into(),
+                ecma.parse::<u64>()
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::from(ecma.as_str())),
+            );
+        }
+
+        if self.keep_classnames {
+            options.insert("keepClassnames".into(), Value::Bool(true));
+        }
+
+        if self.keep_fnames {
+            options.insert("keepFnames".into(), Value::Bool(true));
+        }
+
+        // `url` is applied by us, so it can be added once for --out-file.
+        let mut source_map_url = None;
+
+        if let Some(source_map) = &self.source_map {
+            let mut source_map = match source_map {
+                Some(s) => parse_terser_options(s)?,
+                None => Map::new(),
+            };
+
+            if let Some(include_sources) = source_map.remove("includeSources") {
+                options.insert("inlineSourcesContent".into(), include_sources);
+            }
+
+            if let Some(url) = source_map.remove("url") {
+                source_map_url = url.as_str().map(String::from);
+            }
+
+            if let Some(content) = source_map.get_mut("content") {
+                let Some(path) = content.as_str() else {
+                    bail!("`content` of --source-map should be a path");
+                };
+                if self.files.len() != 1 || self.files[0].is_dir() {
+                    bail!("`content` of --source-map requires a single input file");
+                }
+
+                *content = Value::from(
+                    fs::read_to_string(path)
+                        .with_context(|| format!("failed to read source map {path}"))?,
+                );
+            }
+
+            options.insert("sourceMap".into(), Value::Object(source_map));
+        }
+
+        let options = serde_json::from_value(Value::Object(options))
+            .context("failed to parse minify options")?;
+
+        Ok((options, source_map_url))
+    }
+
+    fn included_extensions(&self) -> Vec<String> {
+        self.extensions.clone().unwrap_or_else(|| {
+            DEFAULT_EXTENSIONS
+                .iter()
+                .map(|value| value.to_string())
+                .collect()
+        })
+    }
+
+    fn collect_minify_file_paths(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let ignore_pattern = parse_ignore_pattern(self.ignore.as_deref())?;
+        let extensions = self.included_extensions();
+
+        let mut file_paths = Vec::new();
+        for input in &self.files {
+            let inputs =
+                collect_input_files(std::slice::from_ref(input), ignore_pattern.as_ref(), None)?;
+
+            file_paths.extend(
+                inputs
+                    .into_iter()
+                    .filter(|input| {
+                        input.origin == InputOrigin::Explicit
+                            || is_compilable_extension(&input.path, &extensions)
+                    })
+                    .map(|input| input.path),
+            );
+        }
+
+        Ok(file_paths)
+    }
+
+    fn minify_path(
+        &self,
+        compiler: &Compiler,
+        options: &JsMinifyOptions,
+        file_path: &Path,
+        output_path: Option<&Path>,
+    ) -> anyhow::Result<TransformOutput> {
+        let fm = compiler
+            .cm
+            .load_file(file_path)
+            .context(format!("Failed to open file {}", file_path.display()))?;
+
+        let mut options = options.clone();
+        if let Some(output_path) = output_path {
+            options.output_path = Some(output_path.to_string_lossy().into_owned());
+        }
+
+        minify_file(compiler, fm, &options)
+    }
+
+    fn execute_out_dir(
+        &self,
+        compiler: &Compiler,
+        options: &JsMinifyOptions,
+        source_map_url: Option<&str>,
+        out_dir: &Path,
+        file_paths: Vec<PathBuf>,
+    ) -> anyhow::Result<()> {
+        file_paths.into_par_iter().try_for_each(|file_path| {
+            let output_path = resolve_output_path(
+                out_dir,
+                &self.files,
+                &file_path,
+                None,
+                self.strip_leading_paths,
+            )?;
+            let mut output = self.minify_path(compiler, options, &file_path, Some(&output_path))?;
+
+            if let Some(map) = output.map.take() {
+                let parent = output_path.parent().expect("Parent should be available");
+                fs::create_dir_all(parent)?;
+
+                emit_source_map(
+                    &mut output.code,
+                    map.as_bytes(),
+                    &output_path,
+                    source_map_url,
+                )?;
+            }
+
+            emit_directory_output(output, &output_path)
+        })
+    }
+
+    fn execute_out_file(
+        &self,
+        compiler: &Compiler,
+        options: &JsMinifyOptions,
+        source_map_url: Option<&str>,
+        out_file: &Path,
+        file_paths: Vec<PathBuf>,
+    ) -> anyhow::Result<()> {
+        let outputs = file_paths
+            .into_par_iter()
+            .map(|file_path| self.minify_path(compiler, options, &file_path, Some(out_file)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let parent = out_file.parent().expect("Parent should be available");
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut code = String::new();
+        let mut sections = Vec::new();
+
+        for output in outputs {
+            if !code.is_empty() {
+                code.push('\n');
+            }
+
+            if let Some(map) = &output.map {
+                let map = RawSourceMap::from_slice(map.as_bytes())
+                    .context("failed to parse generated source map")?;
+                let line = code.matches('\n').count() as u32;
+
+                sections.push(SourceMapSection::new(
+                    (line, 0),
+                    None,
+                    Some(DecodedMap::Regular(map)),
+                ));
+            }
+
+            code.push_str(&output.code);
+        }
+
+        if !sections.is_empty() {
+            let file = out_file
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned().into());
+            let map = SourceMapIndex::new(file, sections)
+                .flatten()
+                .context("failed to merge source maps")?;
+
+            let mut buf = Vec::new();
+            map.to_writer(&mut buf)
+                .context("failed to serialize source map")?;
+
+            emit_source_map(&mut code, &buf, out_file, source_map_url)?;
+        }
+
+        let mut buf = File::create(out_file)?;
+        buf.write_all(code.as_bytes())?;
+        buf.flush()
+            .context("Failed to write output into single file")
+    }
+
+    fn execute_stdout(
+        &self,
+        compiler: &Compiler,
+        options: &JsMinifyOptions,
+        file_paths: Vec<PathBuf>,
+    ) -> anyhow::Result<()> {
+        let outputs = file_paths
+            .into_par_iter()
+            .map(|file_path| self.minify_path(compiler, options, &file_path, None))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for output in outputs {
+            println!("{}", output.code);
+        }
+
+        Ok(())
+    }
+}
 
 impl super::CommandRunner for MinifyOptions {
     fn execute(&self) -> anyhow::Result<()> {
-        unimplemented!("Minify command is not yet implemented")
+        self.validate()?;
+
+        let (options, source_map_url) = self.build_minify_options()?;
+        let file_paths = self.collect_minify_file_paths()?;
+        let compiler = new_compiler();
+
+        if let Some(out_dir) = &self.out_dir {
+            self.execute_out_dir(
+                &compiler,
+                &options,
+                source_map_url.as_deref(),
+                out_dir,
+                file_paths,
+            )
+        } else if let Some(out_file) = &self.out_file {
+            self.execute_out_file(
+                &compiler,
+                &options,
+                source_map_url.as_deref(),
+                out_file,
+                file_paths,
+            )
+        } else {
+            self.execute_stdout(&compiler, &options, file_paths)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{super::CommandRunner, *};
+
+    fn minify_options(args: &[&str]) -> MinifyOptions {
+        MinifyOptions::try_parse_from(["minify"].iter().chain(args)).unwrap()
+    }
+
+    #[test]
+    fn parses_terser_options() {
+        assert_eq!(
+            Value::Object(
+                parse_terser_options("passes=2, drop_console,pure_funcs=['a','b'],ecma=es5")
+                    .unwrap()
+            ),
+            json!({
+                "passes": 2,
+                "drop_console": true,
+                "pure_funcs": ["a", "b"],
+                "ecma": "es5",
+            })
+        );
+        assert_eq!(
+            Value::Object(parse_terser_options(r#"{ "reserved": ["$"] }"#).unwrap()),
+            json!({ "reserved": ["$"] })
+        );
+    }
+
+    #[test]
+    fn converts_single_quotes_to_json() {
+        assert_eq!(
+            single_quotes_to_json(r#"['it\'s', "a \"b\"", 'c "d"']"#),
+            r#"["it's", "a \"b\"", "c \"d\""]"#
+        );
+    }
+
+    #[test]
+    fn source_maps_require_output_files() {
+        let err = minify_options(&["--source-map", "--", "a.js"])
+            .validate()
+            .unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "--source-map requires --out-file or --out-dir"
+        );
+    }
+
+    #[test]
+    fn minifies_files_into_dir() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(
+            src.join("a.js"),
+            "function add(first, second) {\n    return first + second;\n}\nadd(1, 2);\n",
+        )
+        .unwrap();
+        fs::write(src.join("b.ts"), "let b: number = 1;\n").unwrap();
+        let out_dir = dir.path().join("out");
+
+        minify_options(&[
+            "-c",
+            "-m",
+            "--source-map",
+            "url=a.js.map",
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+            "--strip-leading-paths",
+            src.to_str().unwrap(),
+        ])
+        .execute()
+        .unwrap();
+
+        let code = fs::read_to_string(out_dir.join("a.js")).unwrap();
+        assert!(!code.contains("first"), "{code}");
+        assert!(code.ends_with("\n//# sourceMappingURL=a.js.map"), "{code}");
+        assert!(out_dir.join("a.js.map").is_file());
+        assert!(!out_dir.join("b.js").exists());
+    }
+
+    #[test]
+    fn concatenates_files_into_out_file() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let a = dir.path().join("a.js");
+        let b = dir.path().join("b.js");
+        fs::write(&a, "console.log(  'a'  );\n").unwrap();
+        fs::write(&b, "console.log(  'b'  );\n").unwrap();
+        let out_file = dir.path().join("out/bundle.js");
+
+        minify_options(&[
+            "--source-map",
+            "--out-file",
+            out_file.to_str().unwrap(),
+            a.to_str().unwrap(),
+            b.to_str().unwrap(),
+        ])
+        .execute()
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&out_file).unwrap(),
+            "console.log(\"a\");\nconsole.log(\"b\");"
+        );
+
+        let map =
+            RawSourceMap::from_reader(File::open(dir.path().join("out/bundle.js.map")).unwrap())
+                .unwrap();
+        assert_eq!(map.get_file().map(|s| &**s), Some("bundle.js"));
+        assert_eq!(map.get_source_count(), 2);
+        // The second file starts on the second line.
+        let token = map.lookup_token(1, 0).unwrap();
+        assert!(token.get_source().unwrap().ends_with("b.js"), "{token:?}");
+    }
+
+    #[test]
+    fn refers_to_source_maps_only_with_url() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let a = dir.path().join("a.js");
+        fs::write(&a, "console.log(  'a'  );\n").unwrap();
+        let out_dir = dir.path().join("out");
+        let out_file = dir.path().join("bundle.js");
+
+        minify_options(&[
+            "--source-map",
+            "--out-dir",
+            out_dir.to_str().unwrap(),
+            "--strip-leading-paths",
+            a.to_str().unwrap(),
+        ])
+        .execute()
+        .unwrap();
+        minify_options(&[
+            "--source-map",
+            "url=bundle.js.map",
+            "--out-file",
+            out_file.to_str().unwrap(),
+            a.to_str().unwrap(),
+        ])
+        .execute()
+        .unwrap();
+
+        let code = fs::read_to_string(out_dir.join("a.js")).unwrap();
+        assert!(!code.contains("sourceMappingURL"), "{code}");
+        assert!(out_dir.join("a.js.map").is_file());
+
+        let code = fs::read_to_string(&out_file).unwrap();
+        assert!(
+            code.ends_with("\n//# sourceMappingURL=bundle.js.map"),
+            "{code}"
+        );
+        assert!(dir.path().join("bundle.js.map").is_file());
     }
 }
@@ -5,12 +5,14 @@ mod compile;
 mod lint;
 mod minify;
 mod plugin;
+mod strip;
 
 pub use bundle::*;
 pub use compile::*;
 pub use lint::*;
 pub use minify::*;
 pub use plugin::PluginSubcommand;
+pub use strip::*;
 
 // Set of subcommands supported by the `swc` command.
 #[derive(Subcommand)]
@@ -20,9 +22,14 @@ pub enum Command {
     Plugin(PluginSubcommand),
     /// Run SWC's transformer.
     Compile(Box<CompileOptions>),
-    Bundle(BundleOptions),
-    Minify(MinifyOptions),
-    Lint(LintOptions),
+    /// Bundle entry files and their dependencies.
+    Bundle(Box<BundleOptions>),
+    /// Minify files, compatible with the `terser` command.
+    Minify(Box<MinifyOptions>),
+    /// Run SWC's linter.
+    Lint(Box<LintOptions>),
+    /// Strip types of TypeScript files, like type stripping of Node.js.
+    Strip(Box<StripOptions>),
 }
 
 #[derive(Parser)]
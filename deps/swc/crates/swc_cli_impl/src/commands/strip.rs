@@ -0,0 +1,410 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context};
+use clap::Parser;
+use par_iter::prelude::*;
+use swc_core::{
+    base::{try_with_handler, HandlerOpts},
+    common::{errors::ColorConfig, sync::Lrc, SourceMap, GLOBALS},
+};
+use swc_ts_fast_strip::{Mode, Options, TransformOutput};
+
+use super::compile::{
+    collect_input_files, emit_directory_output, is_compilable_extension, parse_ignore_pattern,
+    resolve_output_path, InputOrigin,
+};
+
+/// Configuration option for stripping types of files, which produces the same
+/// output as type stripping of Node.js.
+#[derive(Parser)]
+pub struct StripOptions {
+    /// Values: strip-only (default), transform
+    ///
+    /// `strip-only` replaces types with whitespace so positions are preserved,
+    /// and fails on syntax which is not erasable. `transform` also transforms
+    /// enums, namespaces and parameter properties.
+    #[clap(long, value_parser = parse_mode, default_value = "strip-only")]
+    mode: Mode,
+
+    /// Parse input files as modules (true) or scripts (false). By default,
+    /// `.mts` files are modules, `.cts` files are scripts and others are
+    /// detected from their content.
+    #[clap(long)]
+    module: Option<bool>,
+
+    /// Report `module` declarations, which are deprecated in favor of
+    /// `namespace`, as errors.
+    #[clap(long)]
+    deprecated_ts_module_as_error: bool,
+
+    /// Generate source maps. Only useful for `--mode transform`, as
+    /// `strip-only` preserves positions.
+    #[clap(long)]
+    source_maps: bool,
+
+    /// Only report syntax which can not be stripped, without writing any output.
+    #[clap(long)]
+    check: bool,
+
+    /// List of glob paths to not strip.
+    #[clap(long)]
+    ignore: Option<String>,
+
+    /// The output directory. Output is printed to stdout if not specified.
+    #[clap(long)]
+    out_dir: Option<PathBuf>,
+
+    /// Remove the leading directory from emitted output paths.
+    #[clap(long)]
+    strip_leading_paths: bool,
+
+    /// Files or directories to strip
+    #[clap(required = true)]
+    files: Vec<PathBuf>,
+}
+
+/// List of file extensions stripped by default.
+static DEFAULT_EXTENSIONS: &[&str] = &["ts", "mts", "cts"];
+
+fn parse_mode(s: &str) -> Result<Mode, String> {
+    match s {
+        "strip-only" => Ok(Mode::StripOnly),
+        "transform" => Ok(Mode::Transform),
+        _ => Err(format!(
+            "Invalid mode '{s}'. Valid values are: strip-only, transform"
+        )),
+    }
+}
+
+/// Returns the extension of the output for `.ts`, `.mts` and `.cts` files.
+fn output_extension(file_path: &Path) -> &'static str {
+    match file_path.extension().and_then(|ext| ext.to_str()) {
+        Some("mts") => "mjs",
+        Some("cts") => "cjs",
+        _ => "js",
+    }
+}
+
+fn is_declaration_file(file_path: &Path) -> bool {
+    file_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            name.ends_with(".d.ts") || name.ends_with(".d.mts") || name.ends_with(".d.cts")
+        })
+}
+
+impl StripOptions {
+    fn validate(&self) -> anyhow::Result<()> {
+        if self.strip_leading_paths && self.out_dir.is_none() {
+            bail!("--strip-leading-paths requires --out-dir");
+        }
+
+        if self.source_maps && self.out_dir.is_none() {
+            bail!("--source-maps requires --out-dir");
+        }
+
+        if self.check && self.out_dir.is_some() {
+            bail!("--check can not be used with --out-dir");
+        }
+
+        Ok(())
+    }
+
+    fn collect_strip_file_paths(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let ignore_pattern = parse_ignore_pattern(self.ignore.as_deref())?;
+        let extensions = DEFAULT_EXTENSIONS
+            .iter()
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>();
+
+        let mut file_paths = Vec::new();
+        for input in &self.files {
+            let inputs =
+                collect_input_files(std::slice::from_ref(input), ignore_pattern.as_ref(), None)?;
+
+            // Declaration files are never loaded by Node.js.
+            file_paths.extend(
+                inputs
+                    .into_iter()
+                    .filter(|input| {
+                        input.origin == InputOrigin::Explicit
+                            || (is_compilable_extension(&input.path, &extensions)
+                                && !is_declaration_file(&input.path))
+                    })
+                    .map(|input| input.path),
+            );
+        }
+
+        Ok(file_paths)
+    }
+
+    fn build_strip_options(&self, file_path: &Path, output_path: Option<&Path>) -> Options {
+        let module = match file_path.extension().and_then(|ext| ext.to_str()) {
+            _ if self.module.is_some() => self.module,
+            Some("mts") => Some(true),
+            Some("cts") => Some(false),
+            _ => None,
+        };
+
+        Options {
+            module,
+            filename: Some(file_path.to_string_lossy().into_owned()),
+            // `--check` reports syntax which is not supported by strip-only mode.
+            mode: if self.check {
+                Mode::StripOnly
+            } else {
+                self.mode
+            },
+            deprecated_ts_module_as_error: Some(self.deprecated_ts_module_as_error),
+            source_map: self.source_maps,
+            source_map_file: output_path
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().into_owned()),
+            ..Default::default()
+        }
+    }
+
+    fn strip_file(
+        &self,
+        file_path: &Path,
+        output_path: Option<&Path>,
+    ) -> anyhow::Result<TransformOutput> {
+        let input = fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to open file {}", file_path.display()))?;
+        let options = self.build_strip_options(file_path, output_path);
+
+        // Spans are resolved against `input`, so each file needs its own
+        // source map.
+        let cm = Lrc::new(SourceMap::default());
+
+        try_with_handler(
+            cm.clone(),
+            HandlerOpts {
+                color: ColorConfig::Always,
+                skip_filename: false,
+            },
+            |handler| {
+                GLOBALS.set(&Default::default(), || {
+                    swc_ts_fast_strip::operate(&cm, handler, input, options)
+                        .map_err(anyhow::Error::new)
+                })
+            },
+        )
+        .map_err(|error| error.to_pretty_error())
+    }
+
+    fn execute_check(&self, file_paths: Vec<PathBuf>) -> anyhow::Result<()> {
+        let errors = file_paths
+            .into_par_iter()
+            .filter_map(|file_path| self.strip_file(&file_path, None).err())
+            .collect::<Vec<_>>();
+
+        for error in &errors {
+            eprintln!("{error}");
+        }
+
+        if !errors.is_empty() {
+            bail!("Found {} file(s) which can not be stripped", errors.len());
+        }
+
+        Ok(())
+    }
+
+    fn execute_out_dir(&self, out_dir: &Path, file_paths: Vec<PathBuf>) -> anyhow::Result<()> {
+        file_paths.into_par_iter().try_for_each(|file_path| {
+            let output_path = resolve_output_path(
+                out_dir,
+                &self.files,
+                &file_path,
+                Some(output_extension(&file_path)),
+                self.strip_leading_paths,
+            )?;
+            let output = self.strip_file(&file_path, Some(&output_path))?;
+
+            emit_directory_output(
+                swc_core::base::TransformOutput {
+                    code: output.code,
+                    map: output.map,
+                    output: None,
+                    extracted_comments: None,
+                    diagnostics: Default::default(),
+                },
+                &output_path,
+            )
+        })
+    }
+
+    fn execute_stdout(&self, file_paths: Vec<PathBuf>) -> anyhow::Result<()> {
+        let outputs = file_paths
+            .into_par_iter()
+            .map(|file_path| self.strip_file(&file_path, None))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        for output in outputs {
+            println!("{}", output.code);
+        }
+
+        Ok(())
+    }
+}
+
+impl super::CommandRunner for StripOptions {
+    fn execute(&self) -> anyhow::Result<()> {
+        self.validate()?;
+
+        let file_paths = self.collect_strip_file_paths()?;
+        if self.check {
+            self.execute_check(file_paths)
+        } else if let Some(out_dir) = &self.out_dir {
+            self.execute_out_dir(out_dir, file_paths)
+        } else {
+            self.execute_stdout(file_paths)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{super::CommandRunner, *};
+
+    fn strip_options(args: &[&str], files: &[&Path]) -> StripOptions {
+        let args = ["strip".as_ref()]
+            .into_iter()
+            .chain(args.iter().map(|arg| arg.as_ref()))
+            .chain(files.iter().map(|file| file.as_os_str()));
+
+        StripOptions::try_parse_from(args).unwrap()
+    }
+
+    #[test]
+    fn strips_files_into_dir() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("a.ts"), "let a: number = 1;\n").unwrap();
+        fs::write(src.join("b.mts"), "export const b: string = '';\n").unwrap();
+        fs::write(src.join("c.cts"), "const c = <number[]>[];\n").unwrap();
+        fs::write(src.join("d.d.ts"), "declare const d: number;\n").unwrap();
+        let out_dir = dir.path().join("out");
+        let options = strip_options(
+            &[
+                "--out-dir",
+                out_dir.to_str().unwrap(),
+                "--strip-leading-paths",
+            ],
+            &[&src],
+        );
+
+        // Type assertions with angle brackets can not be stripped.
+        options.execute().unwrap_err();
+
+        fs::write(src.join("c.cts"), "const c = [] as const;\n").unwrap();
+        options.execute().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(out_dir.join("a.js")).unwrap(),
+            "let a         = 1;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(out_dir.join("b.mjs")).unwrap(),
+            "export const b         = '';\n"
+        );
+        assert_eq!(
+            fs::read_to_string(out_dir.join("c.cjs")).unwrap(),
+            "const c = []         ;\n"
+        );
+        assert!(!out_dir.join("d.d.js").exists());
+        assert!(!out_dir.join("d.js").exists());
+    }
+
+    #[test]
+    fn transforms_files_with_source_maps() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let file = dir.path().join("enum.ts");
+        fs::write(&file, "enum E { A }\nconsole.log(E.A);\n").unwrap();
+        let out_dir = dir.path().join("out");
+
+        strip_options(
+            &[
+                "--mode",
+                "transform",
+                "--source-maps",
+                "--out-dir",
+                out_dir.to_str().unwrap(),
+                "--strip-leading-paths",
+            ],
+            &[&file],
+        )
+        .execute()
+        .unwrap();
+
+        let code = fs::read_to_string(out_dir.join("enum.js")).unwrap();
+        assert!(code.contains("var E = /*#__PURE__*/ function(E)"), "{code}");
+        assert!(code.ends_with("//# sourceMappingURL=enum.js.map"), "{code}");
+
+        let map = fs::read_to_string(out_dir.join("enum.js.map")).unwrap();
+        assert!(map.contains(r#""file":"enum.js""#), "{map}");
+    }
+
+    #[test]
+    fn checks_files_without_output() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let valid = dir.path().join("valid.ts");
+        let invalid = dir.path().join("enum.ts");
+        fs::write(&valid, "let a: number = 1;\n").unwrap();
+        fs::write(&invalid, "enum E { A }\n").unwrap();
+
+        strip_options(&["--check"], &[&valid]).execute().unwrap();
+
+        let err = strip_options(&["--mode", "transform", "--check"], &[&valid, &invalid])
+            .execute()
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Found 1 file(s) which can not be stripped");
+    }
+
+    #[test]
+    fn detects_modules_from_extensions() {
+        let options = strip_options(&[], &[Path::new("a.ts")]);
+
+        assert_eq!(
+            options.build_strip_options(Path::new("a.mts"), None).module,
+            Some(true)
+        );
+        assert_eq!(
+            options.build_strip_options(Path::new("a.cts"), None).module,
+            Some(false)
+        );
+        assert_eq!(
+            options.build_strip_options(Path::new("a.ts"), None).module,
+            None
+        );
+
+        let options = strip_options(&["--module", "false"], &[Path::new("a.mts")]);
+        assert_eq!(
+            options.build_strip_options(Path::new("a.mts"), None).module,
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn source_maps_require_out_dir() {
+        let err = strip_options(&["--source-maps"], &[Path::new("a.ts")])
+            .validate()
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "--source-maps requires --out-dir");
+    }
+
+    #[test]
+    fn check_conflicts_with_out_dir() {
+        let err = strip_options(&["--check", "--out-dir", "x"], &[Path::new("a.ts")])
+            .validate()
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "--check can not be used with --out-dir");
+    }
+}
@@ -13,5 +13,6 @@ pub fn run() -> anyhow::Result<()> {
         Command::Minify(options) => options.execute(),
         Command::Bundle(options) => options.execute(),
         Command::Lint(options) => options.execute(),
+        Command::Strip(options) => options.execute(),
     }
 }
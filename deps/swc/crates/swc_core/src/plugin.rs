@@ -1,4 +1,4 @@
-// #[plugin_transform] macro
+// #[plugin_transform] and #[plugin_lint] macros
 #[cfg(any(
     all(
         docsrs,
@@ -17,7 +17,7 @@
         feature = "__ecma_plugin_transform"
     )))
 )]
-pub use swc_plugin_macro::plugin_transform;
+pub use swc_plugin_macro::{plugin_lint, plugin_transform};
 
 /// exported __alloc / __free fn for the guest (plugin)
 /// allows to allocate memory from the host side.
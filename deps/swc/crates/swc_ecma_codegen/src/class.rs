@@ -133,6 +133,10 @@ impl MacroNode for Class {
 #[node_impl]
 impl MacroNode for ClassMember {
     fn emit(&mut self, emitter: &mut Macro) -> Result {
+        if emitter.emit_original_node(self)? {
+            return Ok(());
+        }
+
         match self {
             ClassMember::Constructor(ref n) => emit!(n),
             ClassMember::ClassProp(ref n) => emit!(n),
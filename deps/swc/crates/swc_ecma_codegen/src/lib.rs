@@ -41,6 +41,7 @@ mod lit;
 mod module_decls;
 mod object;
 mod pat;
+pub mod preserve;
 mod scope_helpers;
 mod stmt;
 #[cfg(test)]
@@ -1553,6 +1554,7 @@ impl MacroNode for Script {
 #[node_impl]
 impl MacroNode for ModuleItem {
     fn emit(&mut self, emitter: &mut Macro) -> Result {
+        emitter.emit_preserved_blank_line(self.span())?;
         emitter.emit_leading_comments_of_span(self.span(), false)?;
         match self {
             ModuleItem::Stmt(stmt) => emit!(stmt),
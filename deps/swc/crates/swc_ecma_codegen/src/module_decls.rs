@@ -9,6 +9,10 @@ use crate::{util::StartsWithAlphaNum, ListFormat};
 #[node_impl]
 impl MacroNode for ModuleDecl {
     fn emit(&mut self, emitter: &mut Macro) -> Result {
+        if emitter.emit_original_node(self)? {
+            return Ok(());
+        }
+
         emitter.emit_leading_comments_of_span(self.span(), false)?;
 
         match self {
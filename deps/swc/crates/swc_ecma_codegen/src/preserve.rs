@@ -0,0 +1,641 @@
+//! Formatting-preserving code generation.
+//!
+//! [Emitter] prints every node from the AST, so formatting of the input is
+//! lost. When an [OriginalSource] is set using [with_original_source], the
+//! emitter reuses the original source text of statements, module declarations
+//! and class members which were not modified since the snapshot was taken,
+//! and only prints the rest. Blank lines between them are preserved as well.
+//!
+//! ```ignore
+//! let original = OriginalSource::new(&program);
+//!
+//! program.mutate(&mut resolver(unresolved_mark, top_level_mark, true));
+//! program.mutate(&mut typescript::strip(unresolved_mark, top_level_mark));
+//! program.mutate(&mut hygiene());
+//!
+//! with_original_source(&original, &program, || program.emit_with(&mut emitter))?;
+//! ```
+//!
+//! Nodes are compared ignoring [SyntaxContext], which is not printed.
+//!
+//! Reused text is re-indented, so its first line starts at the current
+//! indentation of the writer and the other lines keep their indentation
+//! relative to it. Line breaks in string and template literals are
+//! significant, so lines starting in them are kept as-is.
+//! [JsWriter::set_indent_str](crate::text_writer::JsWriter::set_indent_str)
+//! should be set to the indentation of the input for the best result.
+//!
+//! A `;` is written after reused text which ends with an expression but not
+//! with a `;`, as the next node may be printed and continue the expression.
+//!
+//! This is disabled if [Config::minify](crate::Config::minify) is set.
+
+use std::{
+    cell::{Cell, RefCell},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use rustc_hash::FxHashMap;
+use swc_common::{util::take::Take, BytePos, SourceMapper, Span, Spanned, SyntaxContext};
+use swc_ecma_ast::{ClassMember, Decl, ModuleDecl, Program, Stmt, Str, Tpl};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use crate::{text_writer::WriteJs, Emitter, Result, SourceMapperExt};
+
+better_scoped_tls::scoped_tls!(static ORIGINAL: OriginalSource);
+
+/// Snapshot of the statements, module declarations and class members of a
+/// program, keyed by span.
+#[derive(Debug, Default)]
+pub struct OriginalSource {
+    /// `None` if multiple nodes have the span.
+    nodes: FxHashMap<Span, Option<u64>>,
+
+    /// Fingerprints of the nodes being emitted, keyed by address.
+    current: RefCell<FxHashMap<usize, u64>>,
+
+    /// The position for which a blank line was emitted last, to avoid
+    /// emitting it twice for nested nodes starting at the same position.
+    last_blank_line: Cell<BytePos>,
+}
+
+impl OriginalSource {
+    pub fn new(program: &Program) -> Self {
+        let mut nodes = FxHashMap::default();
+        for ((span, _), fingerprint) in preservable_nodes(program)
+            .into_iter()
+            .zip(fingerprints(program))
+        {
+            if span.is_dummy() {
+                continue;
+            }
+
+            nodes
+                .entry(span)
+                .and_modify(|v| *v = None)
+                .or_insert(Some(fingerprint));
+        }
+
+        OriginalSource {
+            nodes,
+            current: Default::default(),
+            last_blank_line: Cell::new(BytePos::DUMMY),
+        }
+    }
+
+    fn is_unchanged<N: Spanned>(&self, node: &N) -> bool {
+        let current = self
+            .current
+            .borrow()
+            .get(&(node as *const N as usize))
+            .copied();
+
+        current.is_some() && self.nodes.get(&node.span()) == Some(&current)
+    }
+}
+
+/// Emits code using the original source text of unmodified nodes of `program`
+/// while `op` is running.
+///
+/// `op` should emit `program`, which should not be moved. Other nodes are
+/// always printed.
+pub fn with_original_source<F, Ret>(original: &OriginalSource, program: &Program, op: F) -> Ret
+where
+    F: FnOnce() -> Ret,
+{
+    *original.current.borrow_mut() = preservable_nodes(program)
+        .into_iter()
+        .map(|(_, addr)| addr)
+        .zip(fingerprints(program))
+        .collect();
+
+    let ret = ORIGINAL.set(original, op);
+
+    original.current.borrow_mut().clear();
+
+    ret
+}
+
+/// Returns the span and the address of the nodes which can be preserved, in
+/// the order of [fingerprints].
+fn preservable_nodes(program: &Program) -> Vec<(Span, usize)> {
+    let mut v = Collector::default();
+    program.visit_with(&mut v);
+    v.nodes
+}
+
+#[derive(Default)]
+struct Collector {
+    nodes: Vec<(Span, usize)>,
+}
+
+impl Collector {
+    fn add<N: Spanned>(&mut self, n: &N) {
+        self.nodes.push((n.span(), n as *const N as usize));
+    }
+}
+
+impl Visit for Collector {
+    fn visit_class_member(&mut self, n: &ClassMember) {
+        self.add(n);
+        n.visit_children_with(self);
+    }
+
+    fn visit_module_decl(&mut self, n: &ModuleDecl) {
+        self.add(n);
+        n.visit_children_with(self);
+    }
+
+    fn visit_stmt(&mut self, n: &Stmt) {
+        self.add(n);
+        n.visit_children_with(self);
+    }
+}
+
+/// Returns the fingerprints of the nodes which can be preserved, in the order
+/// of [preservable_nodes].
+///
+/// A node is hashed after replacing its preservable children with the
+/// fingerprints of them, so each node is hashed only once.
+fn fingerprints(program: &Program) -> Vec<u64> {
+    let mut v = Fingerprinter::default();
+    program.clone().visit_mut_with(&mut v);
+    v.fingerprints
+}
+
+#[derive(Default)]
+struct Fingerprinter {
+    fingerprints: Vec<u64>,
+    /// Fingerprints of the children of the nodes being visited.
+    children: Vec<Vec<u64>>,
+}
+
+impl Fingerprinter {
+    fn fingerprint<N>(&mut self, n: &mut N)
+    where
+        N: Hash + Take + VisitMutWith<Self>,
+    {
+        let idx = self.fingerprints.len();
+        self.fingerprints.push(0);
+
+        self.children.push(Vec::new());
+        n.visit_mut_children_with(self);
+        let children = self.children.pop().unwrap();
+
+        // Collisions result in stale output, so we use a hasher with a good
+        // distribution.
+        let mut hasher = DefaultHasher::new();
+        n.hash(&mut hasher);
+        children.hash(&mut hasher);
+        let fingerprint = hasher.finish();
+
+        self.fingerprints[idx] = fingerprint;
+        if let Some(parent) = self.children.last_mut() {
+            parent.push(fingerprint);
+        }
+
+        n.take();
+    }
+}
+
+impl VisitMut for Fingerprinter {
+    fn visit_mut_class_member(&mut self, n: &mut ClassMember) {
+        self.fingerprint(n);
+    }
+
+    fn visit_mut_module_decl(&mut self, n: &mut ModuleDecl) {
+        self.fingerprint(n);
+    }
+
+    fn visit_mut_stmt(&mut self, n: &mut Stmt) {
+        self.fingerprint(n);
+    }
+
+    fn visit_mut_syntax_context(&mut self, n: &mut SyntaxContext) {
+        *n = SyntaxContext::empty();
+    }
+}
+
+/// Nodes which can be emitted using their original source text.
+pub(crate) trait Preservable: Spanned {
+    /// Returns `true` if the node may end with an expression, which the next
+    /// node would continue if the original text has no `;` after it.
+    fn ends_with_expr(&self) -> bool;
+
+    /// Returns the spans of string and template literals in the node.
+    fn literal_spans(&self) -> Vec<Span>;
+}
+
+impl Preservable for Stmt {
+    fn ends_with_expr(&self) -> bool {
+        match self {
+            Stmt::Expr(..) | Stmt::Return(..) | Stmt::Throw(..) => true,
+            Stmt::Decl(decl) => decl_ends_with_expr(decl),
+            Stmt::If(s) => s.alt.as_ref().unwrap_or(&s.cons).ends_with_expr(),
+            Stmt::For(s) => s.body.ends_with_expr(),
+            Stmt::ForIn(s) => s.body.ends_with_expr(),
+            Stmt::ForOf(s) => s.body.ends_with_expr(),
+            Stmt::Labeled(s) => s.body.ends_with_expr(),
+            Stmt::While(s) => s.body.ends_with_expr(),
+            Stmt::With(s) => s.body.ends_with_expr(),
+            _ => false,
+        }
+    }
+
+    fn literal_spans(&self) -> Vec<Span> {
+        literal_spans(self)
+    }
+}
+
+impl Preservable for ModuleDecl {
+    fn ends_with_expr(&self) -> bool {
+        match self {
+            ModuleDecl::ExportDecl(d) => decl_ends_with_expr(&d.decl),
+            ModuleDecl::ExportDefaultExpr(..)
+            | ModuleDecl::TsExportAssignment(..)
+            | ModuleDecl::TsImportEquals(..) => true,
+            _ => false,
+        }
+    }
+
+    fn literal_spans(&self) -> Vec<Span> {
+        literal_spans(self)
+    }
+}
+
+impl Preservable for ClassMember {
+    fn ends_with_expr(&self) -> bool {
+        match self {
+            ClassMember::ClassProp(..)
+            | ClassMember::PrivateProp(..)
+            | ClassMember::AutoAccessor(..)
+            | ClassMember::TsIndexSignature(..) => true,
+            // Overloads have no body.
+            ClassMember::Method(m) => m.function.body.is_none(),
+            ClassMember::PrivateMethod(m) => m.function.body.is_none(),
+            ClassMember::Constructor(c) => c.body.is_none(),
+            _ => false,
+        }
+    }
+
+    fn literal_spans(&self) -> Vec<Span> {
+        literal_spans(self)
+    }
+}
+
+fn decl_ends_with_expr(decl: &Decl) -> bool {
+    matches!(
+        decl,
+        Decl::Var(..) | Decl::Using(..) | Decl::TsTypeAlias(..)
+    )
+}
+
+fn literal_spans<N: VisitWith<LiteralCollector>>(node: &N) -> Vec<Span> {
+    let mut v = LiteralCollector::default();
+    node.visit_with(&mut v);
+    v.spans
+}
+
+#[derive(Default)]
+struct LiteralCollector {
+    spans: Vec<Span>,
+}
+
+impl Visit for LiteralCollector {
+    fn visit_str(&mut self, n: &Str) {
+        self.spans.push(n.span);
+    }
+
+    fn visit_tpl(&mut self, n: &Tpl) {
+        self.spans.push(n.span);
+        n.visit_children_with(self);
+    }
+}
+
+impl<W, S: SourceMapper> Emitter<'_, W, S>
+where
+    W: WriteJs,
+    S: SourceMapperExt,
+{
+    fn is_preserving_source(&self) -> bool {
+        !self.cfg.minify && ORIGINAL.is_set()
+    }
+
+    /// Emits the original source text of `node` if it's not modified.
+    ///
+    /// Returns `false` if `node` should be printed.
+    pub(crate) fn emit_original_node<N: Preservable>(&mut self, node: &N) -> std::io::Result<bool> {
+        if !self.is_preserving_source() {
+            return Ok(false);
+        }
+
+        self.emit_preserved_blank_line(node.span())?;
+
+        if !ORIGINAL.with(|original| original.is_unchanged(node)) {
+            return Ok(false);
+        }
+
+        self.emit_original_text(node)
+    }
+
+    /// Emits a blank line if there's one before `span` (and its leading
+    /// comments) in the original source.
+    pub(crate) fn emit_preserved_blank_line(&mut self, span: Span) -> Result {
+        if !self.is_preserving_source() || span.is_dummy() {
+            return Ok(());
+        }
+
+        if ORIGINAL.with(|original| original.last_blank_line.get() == span.lo) {
+            return Ok(());
+        }
+
+        let pos = self
+            .comments
+            .and_then(|c| c.get_leading(span.lo))
+            .and_then(|c| c.first().map(|c| c.span.lo))
+            .unwrap_or(span.lo);
+
+        let file = self.cm.lookup_char_pos(pos).file;
+        let before = &file.src[..(pos - file.start_pos).0 as usize];
+
+        let mut line_breaks = 0;
+        for c in before.chars().rev() {
+            match c {
+                '\n' => line_breaks += 1,
+                c if c.is_whitespace() => {}
+                _ => {
+                    if line_breaks >= 2 {
+                        ORIGINAL.with(|original| original.last_blank_line.set(span.lo));
+                        self.wr.write_blank_line()?;
+                    }
+
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn emit_original_text<N: Preservable>(&mut self, node: &N) -> std::io::Result<bool> {
+        let span = node.span();
+        let Ok(text) = self.cm.span_to_snippet(span) else {
+            return Ok(false);
+        };
+
+        self.emit_leading_comments_of_span(span, false)?;
+
+        // Lines are re-indented by removing the indentation of the first line,
+        // and the writer indents them.
+        let file = self.cm.lookup_char_pos(span.lo).file;
+        let line_start = file.src[..(span.lo - file.start_pos).0 as usize]
+            .rfind(['\n', '\r'])
+            .map_or(0, |i| i + 1);
+        let first_line = &file.src[line_start..];
+        let indent = &first_line[..first_line.len() - first_line.trim_start().len()];
+
+        let literals = node.literal_spans();
+        let is_in_literal = |pos: BytePos| literals.iter().any(|s| s.lo < pos && pos < s.hi);
+
+        let mut start = 0;
+        let mut line_is_empty = false;
+        let mut chars = text.char_indices().peekable();
+        while let Some((i, c)) = chars.next() {
+            if c == '\n' || c == '\r' {
+                if is_in_literal(span.lo + BytePos(i as u32)) {
+                    continue;
+                }
+
+                if line_is_empty {
+                    self.wr.write_blank_line()?;
+                } else {
+                    self.write_original_words(span.lo, &text, start, i)?;
+                    self.wr.write_line()?;
+                }
+
+                if c == '\r' && chars.next_if(|&(_, c)| c == '\n').is_some() {
+                    start = i + 2;
+                } else {
+                    start = i + 1;
+                }
+                let rest = &text[start..];
+                let line_indent = &rest[..rest.len() - rest.trim_start_matches([' ', '\t']).len()];
+                start += common_prefix_len(line_indent, indent);
+                line_is_empty = rest
+                    .trim_start_matches([' ', '\t'])
+                    .starts_with(['\n', '\r']);
+            }
+        }
+        self.write_original_words(span.lo, &text, start, text.len())?;
+
+        if node.ends_with_expr() && !text.ends_with(';') {
+            self.wr.write_semi(None)?;
+        }
+
+        if self.comments.is_some() {
+            self.emit_trailing_comments_of_pos(span.hi, true, true)?;
+        }
+
+        self.wr.write_line()?;
+
+        Ok(true)
+    }
+
+    /// Writes `text[start..end]`, mapping each word to its original position.
+    fn write_original_words(
+        &mut self,
+        lo: BytePos,
+        text: &str,
+        start: usize,
+        end: usize,
+    ) -> Result {
+        let mut chunk_start = start;
+        let mut prev_is_whitespace = false;
+        for (i, c) in text[start..end].char_indices() {
+            let is_whitespace = c.is_whitespace();
+            if prev_is_whitespace && !is_whitespace {
+                self.write_original_chunk(lo, text, chunk_start, start + i)?;
+                chunk_start = start + i;
+            }
+            prev_is_whitespace = is_whitespace;
+        }
+        self.write_original_chunk(lo, text, chunk_start, end)
+    }
+
+    fn write_original_chunk(
+        &mut self,
+        lo: BytePos,
+        text: &str,
+        start: usize,
+        end: usize,
+    ) -> Result {
+        let chunk_lo = lo + BytePos(start as u32);
+        let chunk_hi = lo + BytePos(end as u32);
+
+        self.wr
+            .write_str_lit(Span::new(chunk_lo, chunk_hi), &text[start..end])
+    }
+}
+
+/// Returns the length of the common prefix of `a` and `b` in bytes.
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.bytes().zip(b.bytes()).take_while(|(a, b)| a == b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::{sync::Lrc, FileName, Globals, Mark, SourceMap, GLOBALS};
+    use swc_ecma_ast::{EsVersion, Ident, Module};
+    use swc_ecma_parser::{parse_file_as_module, Syntax};
+
+    use super::*;
+    use crate::{text_writer::JsWriter, Config, Node};
+
+    fn parse(cm: &Lrc<SourceMap>, src: &str) -> Program {
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+        let module = parse_file_as_module(
+            &fm,
+            Syntax::default(),
+            EsVersion::latest(),
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap();
+
+        Program::Module(module)
+    }
+
+    fn emit(cm: &Lrc<SourceMap>, original: &OriginalSource, program: &Program) -> String {
+        let mut buf = Vec::new();
+        {
+            let mut emitter = Emitter {
+                cfg: Config::default(),
+                comments: None,
+                cm: cm.clone(),
+                wr: JsWriter::new(cm.clone(), "\n", &mut buf, None),
+            };
+            with_original_source(original, program, || program.emit_with(&mut emitter)).unwrap();
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    fn body(program: &mut Program) -> &mut Module {
+        program.as_mut_module().unwrap()
+    }
+
+    #[test]
+    fn reuses_unchanged_statements() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(&cm, "let  a = 'x';\n\n\nfoo( a );\nbar(  a  );\n");
+        let original = OriginalSource::new(&program);
+
+        let stmt = body(&mut program).body[2].as_mut_stmt().unwrap();
+        let call = stmt.as_mut_expr().unwrap().expr.as_mut_call().unwrap();
+        call.args.clear();
+
+        assert_eq!(
+            emit(&cm, &original, &program),
+            "let  a = 'x';\n\nfoo( a );\nbar();\n"
+        );
+    }
+
+    #[test]
+    fn reuses_unchanged_children_of_changed_nodes() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(
+            &cm,
+            "class A {\n  foo( ) {  return 1 }\n  bar() {}\n}\nif (a) {  b( ) }\n",
+        );
+        let original = OriginalSource::new(&program);
+
+        let class = body(&mut program).body[0].as_mut_stmt().unwrap();
+        let class = &mut class.as_mut_decl().unwrap().as_mut_class().unwrap().class;
+        class.body.pop();
+
+        assert_eq!(
+            emit(&cm, &original, &program),
+            "class A {\n    foo( ) {  return 1 }\n}\nif (a) {  b( ) }\n"
+        );
+    }
+
+    #[test]
+    fn reindents_reused_text() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(
+            &cm,
+            "class A {\n  foo() {\n    return `a\n  b`;\n\n  }\n  bar() {}\n}\n",
+        );
+        let original = OriginalSource::new(&program);
+
+        let class = body(&mut program).body[0].as_mut_stmt().unwrap();
+        let class = &mut class.as_mut_decl().unwrap().as_mut_class().unwrap().class;
+        class.body.pop();
+
+        assert_eq!(
+            emit(&cm, &original, &program),
+            "class A {\n    foo() {\n      return `a\n  b`;\n\n    }\n}\n"
+        );
+    }
+
+    #[test]
+    fn terminates_reused_expressions() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(&cm, "a = b\nc = {}\nfunction f() {}\nd()\n");
+        let original = OriginalSource::new(&program);
+
+        // The printed statements start with `(`, which would continue the
+        // previous expression if it's not terminated.
+        let mut printed = parse(&cm, "(0, e)()\n");
+        let printed = body(&mut printed).body[0].clone();
+        let module = body(&mut program);
+        module.body.insert(3, printed.clone());
+        module.body.insert(2, printed.clone());
+        module.body.insert(1, printed);
+
+        assert_eq!(
+            emit(&cm, &original, &program),
+            "a = b;\n(0, e)();\nc = {};\n(0, e)();\nfunction f() {}\n(0, e)();\nd();\n"
+        );
+    }
+
+    #[test]
+    fn ignores_syntax_context() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(&cm, "foo( a );\n");
+        let original = OriginalSource::new(&program);
+
+        GLOBALS.set(&Globals::new(), || program.visit_mut_with(&mut SetCtxt));
+
+        assert_eq!(emit(&cm, &original, &program), "foo( a );\n");
+    }
+
+    #[test]
+    fn prints_modified_copies() {
+        let cm = Lrc::new(SourceMap::default());
+        let mut program = parse(&cm, "foo( a );\n");
+        let original = OriginalSource::new(&program);
+
+        let module = body(&mut program);
+        let mut stmt = module.body[0].clone();
+        stmt.as_mut_stmt()
+            .unwrap()
+            .as_mut_expr()
+            .unwrap()
+            .expr
+            .as_mut_call()
+            .unwrap()
+            .args
+            .clear();
+        module.body.insert(0, stmt);
+
+        assert_eq!(emit(&cm, &original, &program), "foo();\nfoo( a );\n");
+    }
+
+    struct SetCtxt;
+
+    impl VisitMut for SetCtxt {
+        fn visit_mut_ident(&mut self, n: &mut Ident) {
+            n.ctxt = SyntaxContext::empty().apply_mark(Mark::new());
+        }
+    }
+}
@@ -13,6 +13,10 @@ use crate::{
 #[node_impl]
 impl MacroNode for Stmt {
     fn emit(&mut self, emitter: &mut Macro) -> Result {
+        if emitter.emit_original_node(self)? {
+            return Ok(());
+        }
+
         match self {
             Stmt::Expr(ref e) => emit!(e),
             Stmt::Block(ref e) => {
@@ -80,6 +80,13 @@ pub trait WriteJs {
 
     fn write_line(&mut self) -> Result;
 
+    /// Writes an empty line, even if the writer is at the start of a line.
+    ///
+    /// This is used to preserve blank lines of the original source.
+    fn write_blank_line(&mut self) -> Result {
+        self.write_line()
+    }
+
     fn write_lit(&mut self, span: Span, s: &str) -> Result;
     fn write_comment(&mut self, s: &str) -> Result;
 
@@ -205,6 +212,11 @@ where
         (**self).write_line()
     }
 
+    #[inline]
+    fn write_blank_line(&mut self) -> Result {
+        (**self).write_blank_line()
+    }
+
     #[inline]
     fn write_lit(&mut self, span: Span, s: &str) -> Result {
         (**self).write_lit(span, s)
@@ -341,6 +353,11 @@ where
         (**self).write_line()
     }
 
+    #[inline]
+    fn write_blank_line(&mut self) -> Result {
+        (**self).write_blank_line()
+    }
+
     #[inline]
     fn write_lit(&mut self, span: Span, s: &str) -> Result {
         (**self).write_lit(span, s)
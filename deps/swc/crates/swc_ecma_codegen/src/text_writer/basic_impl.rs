@@ -259,6 +259,17 @@ impl<W: Write> WriteJs for JsWriter<'_, W> {
         Ok(())
     }
 
+    fn write_blank_line(&mut self) -> Result {
+        self.write_line()?;
+
+        self.raw_write(self.new_line)?;
+        if self.srcmap.is_some() {
+            self.line_count += 1;
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn write_lit(&mut self, span: Span, s: &str) -> Result {
         if !s.is_empty() {
@@ -1,12 +1,15 @@
 use std::fmt::Debug;
 
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use swc_config::merge::Merge;
 
 #[cfg(feature = "non_critical_lints")]
 use crate::rules::non_critical_lints::{
-    dot_notation::DotNotationConfig, eqeqeq::EqeqeqConfig, no_bitwise::NoBitwiseConfig,
+    dot_notation::DotNotationConfig, eqeqeq::EqeqeqConfig,
+    erasable_syntax_only::ErasableSyntaxOnlyConfig, no_bitwise::NoBitwiseConfig,
     no_console::NoConsoleConfig, no_empty_function::NoEmptyFunctionConfig,
+    no_explicit_any::NoExplicitAnyConfig, no_namespace::NoNamespaceConfig,
     no_param_reassign::NoParamReassignConfig, no_restricted_syntax::NoRestrictedSyntaxConfig,
     no_use_before_define::NoUseBeforeDefineConfig, prefer_const::PreferConstConfig,
     prefer_regex_literals::PreferRegexLiteralsConfig, quotes::QuotesConfig, radix::RadixConfig,
@@ -215,4 +218,48 @@ pub struct LintConfig {
     #[cfg(feature = "non_critical_lints")]
     #[serde(default, alias = "preferObjectSpread")]
     pub prefer_object_spread: RuleConfig<()>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "consistentTypeImports")]
+    pub consistent_type_imports: RuleConfig<()>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "noExplicitAny")]
+    pub no_explicit_any: RuleConfig<NoExplicitAnyConfig>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "noNonNullAssertion")]
+    pub no_non_null_assertion: RuleConfig<()>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "noNamespace")]
+    pub no_namespace: RuleConfig<NoNamespaceConfig>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "noEnum")]
+    pub no_enum: RuleConfig<()>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "preferAsConst")]
+    pub prefer_as_const: RuleConfig<()>,
+
+    #[cfg(feature = "non_critical_lints")]
+    #[serde(default, alias = "erasableSyntaxOnly")]
+    pub erasable_syntax_only: RuleConfig<ErasableSyntaxOnlyConfig>,
+
+    /// Wasm plugins which provide lint rules. They are run by hosts which
+    /// support plugins, and ignored by [crate::rules::all].
+    #[serde(default)]
+    pub plugins: Vec<LintPluginConfig>,
 }
+
+/// A Wasm plugin which provides lint rules.
+///
+/// The first element is a resolvable name of the plugin, and the second one
+/// configures its rules by name, like `{ "no-foo": ["error", { ... }] }`.
+/// Options of rules are passed to the plugin as they are.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintPluginConfig(
+    pub String,
+    #[serde(default)] pub FxHashMap<String, RuleConfig<serde_json::Value>>,
+);
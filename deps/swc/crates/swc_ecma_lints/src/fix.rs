@@ -0,0 +1,309 @@
+//! Automatic fixes of lint rules.
+//!
+//! Rules attach fixes to diagnostics as suggestions with
+//! [Applicability::MachineApplicable], and fixes are applied to the source
+//! code by [apply_fixes] or [fix_until_stable].
+
+use swc_common::{
+    errors::{Applicability, Diagnostic},
+    BytePos,
+};
+
+/// The maximum number of passes of [fix_until_stable].
+pub const MAX_FIX_PASSES: usize = 10;
+
+/// Replacement of `start..end` of the source code with `text`, in byte
+/// offsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+}
+
+/// Edits of a diagnostic, which are applied all together or not at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub edits: Vec<Edit>,
+}
+
+impl Fix {
+    fn start(&self) -> usize {
+        self.edits.iter().map(|edit| edit.start).min().unwrap_or(0)
+    }
+
+    fn overlaps(&self, edit: &Edit) -> bool {
+        self.edits.iter().any(|e| {
+            // Insertions at the same position are considered overlapping, as
+            // their order is unspecified.
+            (e.start < edit.end && edit.start < e.end) || e.start == edit.start
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct FixOutput {
+    pub code: String,
+    /// The number of applied fixes.
+    pub applied: usize,
+}
+
+/// Extracts machine-applicable fixes from `diagnostics` of a source file
+/// which starts at `start_pos`.
+pub fn fixes_from_diagnostics<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    start_pos: BytePos,
+) -> Vec<Fix> {
+    diagnostics
+        .into_iter()
+        .flat_map(|d| &d.suggestions)
+        .filter(|suggestion| suggestion.applicability == Applicability::MachineApplicable)
+        .filter_map(|suggestion| {
+            let substitution = suggestion.substitutions.first()?;
+
+            let edits = substitution
+                .parts
+                .iter()
+                .map(|part| {
+                    if part.span.is_dummy() || part.span.lo < start_pos {
+                        return None;
+                    }
+
+                    Some(Edit {
+                        start: (part.span.lo - start_pos).0 as usize,
+                        end: (part.span.hi - start_pos).0 as usize,
+                        text: part.snippet.clone(),
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+
+            (!edits.is_empty()).then_some(Fix { edits })
+        })
+        .collect()
+}
+
+/// Applies `fixes` to `src`.
+///
+/// Fixes are applied in the order of their positions, and a fix is skipped if
+/// it overlaps with an applied fix or is out of `src`. Skipped fixes may be
+/// applied by the next pass of [fix_until_stable].
+pub fn apply_fixes(src: &str, mut fixes: Vec<Fix>) -> FixOutput {
+    fixes.sort_by_key(|fix| fix.start());
+
+    let mut applied = Vec::<Fix>::new();
+
+    for fix in fixes {
+        let is_valid = fix.edits.iter().all(|edit| {
+            edit.start <= edit.end
+                && edit.end <= src.len()
+                && src.is_char_boundary(edit.start)
+                && src.is_char_boundary(edit.end)
+        });
+        if !is_valid {
+            continue;
+        }
+
+        let is_overlapping = fix.edits.iter().enumerate().any(|(idx, edit)| {
+            fix.edits[..idx]
+                .iter()
+                .any(|e| e.start < edit.end && edit.start < e.end)
+                || applied.iter().any(|applied| applied.overlaps(edit))
+        });
+        if is_overlapping || applied.contains(&fix) {
+            continue;
+        }
+
+        applied.push(fix);
+    }
+
+    let mut edits = applied
+        .iter()
+        .flat_map(|fix| &fix.edits)
+        .collect::<Vec<_>>();
+    edits.sort_by_key(|edit| edit.start);
+
+    let mut code = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        code.push_str(&src[pos..edit.start]);
+        code.push_str(&edit.text);
+        pos = edit.end;
+    }
+    code.push_str(&src[pos..]);
+
+    FixOutput {
+        code,
+        applied: applied.len(),
+    }
+}
+
+/// Lints and fixes `src` repeatedly until no fix is left, as fixes may
+/// overlap or produce code which can be fixed again.
+///
+/// `lint` returns fixes of the given code, or [None] if the code is invalid. If
+/// fixes produce invalid code, they are reverted and the last valid code is
+/// returned.
+pub fn fix_until_stable<E>(
+    src: String,
+    mut lint: impl FnMut(&str) -> Result<Option<Vec<Fix>>, E>,
+) -> Result<FixOutput, E> {
+    let mut code = src;
+    let mut applied = 0;
+    let mut previous = None;
+
+    for pass in 0..=MAX_FIX_PASSES {
+        let Some(fixes) = lint(&code)? else {
+            if let Some((previous_code, previous_applied)) = previous {
+                code = previous_code;
+                applied = previous_applied;
+            }
+            break;
+        };
+
+        if fixes.is_empty() || pass == MAX_FIX_PASSES {
+            break;
+        }
+
+        let output = apply_fixes(&code, fixes);
+        if output.applied == 0 || output.code == code {
+            break;
+        }
+
+        previous = Some((std::mem::replace(&mut code, output.code), applied));
+        applied += output.applied;
+    }
+
+    Ok(FixOutput { code, applied })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::{apply_fixes, fix_until_stable, Edit, Fix, MAX_FIX_PASSES};
+
+    fn fix(edits: &[(usize, usize, &str)]) -> Fix {
+        Fix {
+            edits: edits
+                .iter()
+                .map(|&(start, end, text)| Edit {
+                    start,
+                    end,
+                    text: text.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn applies_fixes_in_order_of_positions() {
+        let output = apply_fixes(
+            "var a = 1; var b = 2;",
+            vec![fix(&[(11, 14, "let")]), fix(&[(0, 3, "let")])],
+        );
+
+        assert_eq!(output.code, "let a = 1; let b = 2;");
+        assert_eq!(output.applied, 2);
+    }
+
+    #[test]
+    fn skips_overlapping_fixes() {
+        let output = apply_fixes(
+            "a == b",
+            vec![
+                fix(&[(2, 4, "===")]),
+                fix(&[(0, 6, "a === b")]),
+                // Insertions at the same position.
+                fix(&[(6, 6, ";")]),
+                fix(&[(6, 6, "\n")]),
+            ],
+        );
+
+        assert_eq!(output.code, "a === b;");
+        assert_eq!(output.applied, 2);
+    }
+
+    #[test]
+    fn applies_all_edits_of_fix_or_none() {
+        let output = apply_fixes(
+            "(a) + (b)",
+            vec![
+                fix(&[(1, 2, "x"), (7, 8, "y")]),
+                // `(b)` overlaps with the fix above, so `+` is not replaced
+                // either.
+                fix(&[(4, 5, "-"), (6, 9, "b")]),
+                // The edits overlap with each other.
+                fix(&[(3, 4, "!"), (3, 5, "?")]),
+            ],
+        );
+
+        assert_eq!(output.code, "(x) + (y)");
+        assert_eq!(output.applied, 1);
+    }
+
+    #[test]
+    fn skips_invalid_and_duplicate_fixes() {
+        let output = apply_fixes(
+            "'é'",
+            vec![
+                fix(&[(2, 3, "e")]),
+                fix(&[(3, 10, "")]),
+                fix(&[(2, 1, "")]),
+                fix(&[(0, 1, "\"")]),
+                fix(&[(0, 1, "\"")]),
+            ],
+        );
+
+        assert_eq!(output.code, "\"é'");
+        assert_eq!(output.applied, 1);
+    }
+
+    /// Replaces the first `aa` with `a`.
+    fn dedupe(code: &str) -> Result<Option<Vec<Fix>>, Infallible> {
+        Ok(Some(
+            code.find("aa")
+                .map(|idx| vec![fix(&[(idx, idx + 2, "a")])])
+                .unwrap_or_default(),
+        ))
+    }
+
+    #[test]
+    fn fixes_until_no_fix_is_left() {
+        let output = fix_until_stable("aaaa".to_string(), dedupe).unwrap();
+
+        assert_eq!(output.code, "a");
+        assert_eq!(output.applied, 3);
+    }
+
+    #[test]
+    fn stops_after_max_passes() {
+        let src = "a".repeat(MAX_FIX_PASSES + 5);
+
+        let output = fix_until_stable(src, dedupe).unwrap();
+
+        assert_eq!(output.code, "a".repeat(5));
+        assert_eq!(output.applied, MAX_FIX_PASSES);
+    }
+
+    #[test]
+    fn reverts_fixes_producing_invalid_code() {
+        let output = fix_until_stable("a = 1".to_string(), |code| {
+            Ok::<_, Infallible>(match code {
+                "a = 1" => Some(vec![fix(&[(0, 1, "b")])]),
+                "b = 1" => Some(vec![fix(&[(2, 3, "")])]),
+                _ => None,
+            })
+        })
+        .unwrap();
+
+        assert_eq!(output.code, "b = 1");
+        assert_eq!(output.applied, 1);
+    }
+
+    #[test]
+    fn propagates_lint_errors() {
+        let result = fix_until_stable("a".to_string(), |_| Err("error"));
+
+        assert_eq!(result.unwrap_err(), "error");
+    }
+}
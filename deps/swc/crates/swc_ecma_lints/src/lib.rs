@@ -4,5 +4,8 @@
 #![allow(dead_code)]
 
 pub mod config;
+pub mod fix;
+pub mod plugin;
 pub mod rule;
 pub mod rules;
+pub mod suppression;
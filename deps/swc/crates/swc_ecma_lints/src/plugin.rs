@@ -0,0 +1,282 @@
+//! Lint rules of Wasm plugins.
+//!
+//! A lint plugin is a transform plugin which is declared with
+//! `#[plugin_lint]` of `swc_core`. It receives the program as read-only and
+//! reports diagnostics to `HANDLER`, with names of its rules as codes and
+//! fixes as suggestions. Running plugins is up to hosts, which pass a runner to
+//! [plugin_rule].
+
+use std::{fmt, mem::take};
+
+use rustc_hash::FxHashMap;
+use serde_json::{json, Map, Value};
+use swc_common::errors::{DiagnosticBuilder, DiagnosticId, Handler, Level, HANDLER};
+use swc_ecma_ast::{Module, Program, Script};
+
+use crate::{
+    config::{LintPluginConfig, LintRuleReaction},
+    rule::{Capturing, Rule},
+};
+
+impl LintPluginConfig {
+    /// The configuration passed to the plugin, which contains options of its
+    /// enabled rules, like `{ "rules": { "no-foo": { ... } } }`.
+    pub fn plugin_config(&self) -> Value {
+        let rules = self
+            .1
+            .iter()
+            .filter(|(_, config)| !matches!(config.get_rule_reaction(), LintRuleReaction::Off))
+            .map(|(name, config)| (name.clone(), config.get_rule_config().clone()))
+            .collect::<Map<_, _>>();
+
+        json!({ "rules": rules })
+    }
+
+    fn reactions(&self) -> FxHashMap<String, LintRuleReaction> {
+        self.1
+            .iter()
+            .map(|(name, config)| (name.clone(), config.get_rule_reaction()))
+            .filter(|(_, reaction)| !matches!(reaction, LintRuleReaction::Off))
+            .collect()
+    }
+}
+
+/// Creates a rule which runs the plugins of `configs`, or [None] if no rule of
+/// them is enabled.
+///
+/// For each file, `prepare` receives a copy of the program, and its result is
+/// shared by the plugins. `run` is called for each plugin which has enabled
+/// rules, with its index in `configs`.
+///
+/// Levels of diagnostics of plugin rules are replaced by the configured ones,
+/// and diagnostics of rules which are not enabled are dropped. Diagnostics
+/// without codes of rules, like errors of running the plugin, are reported as
+/// they are.
+pub fn plugin_rule<T: 'static>(
+    configs: &[LintPluginConfig],
+    prepare: impl 'static + FnMut(Program) -> T + Send + Sync,
+    run: impl 'static + FnMut(usize, &T) + Send + Sync,
+) -> Option<Box<dyn Rule>> {
+    let plugins = configs
+        .iter()
+        .enumerate()
+        .map(|(index, config)| EnabledPlugin {
+            index,
+            name: config.0.clone(),
+            reactions: config.reactions(),
+        })
+        .filter(|plugin| !plugin.reactions.is_empty())
+        .collect::<Vec<_>>();
+    if plugins.is_empty() {
+        return None;
+    }
+
+    Some(Box::new(PluginRule {
+        plugins,
+        prepare: Box::new(prepare),
+        run: Box::new(run),
+    }))
+}
+
+#[derive(Debug)]
+struct EnabledPlugin {
+    /// The index in the configuration.
+    index: usize,
+    name: String,
+    reactions: FxHashMap<String, LintRuleReaction>,
+}
+
+type Prepare<T> = Box<dyn FnMut(Program) -> T + Send + Sync>;
+
+type Run<T> = Box<dyn FnMut(usize, &T) + Send + Sync>;
+
+/// Runs all plugins of a file, so the program is copied once.
+struct PluginRule<T> {
+    plugins: Vec<EnabledPlugin>,
+    prepare: Prepare<T>,
+    run: Run<T>,
+}
+
+impl<T> fmt::Debug for PluginRule<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PluginRule")
+            .field("plugins", &self.plugins)
+            .finish()
+    }
+}
+
+impl<T> PluginRule<T> {
+    fn lint(&mut self, program: Program) {
+        let input = (self.prepare)(program);
+
+        for plugin in &self.plugins {
+            let capturing = Capturing::default();
+
+            HANDLER.set(
+                &Handler::with_emitter(true, false, Box::new(capturing.clone())),
+                || (self.run)(plugin.index, &input),
+            );
+
+            let errors = take(&mut *capturing.errors.lock());
+
+            HANDLER.with(|handler| {
+                for mut error in errors {
+                    if let Some(DiagnosticId::Lint(rule)) = &error.code {
+                        error.level = match plugin.reactions.get(rule) {
+                            Some(LintRuleReaction::Error) => Level::Error,
+                            Some(LintRuleReaction::Warning) => Level::Warning,
+                            _ => continue,
+                        };
+                    }
+
+                    DiagnosticBuilder::new_diagnostic(handler, error).emit();
+                }
+            });
+        }
+    }
+}
+
+impl<T> Rule for PluginRule<T> {
+    fn lint_module(&mut self, program: &Module) {
+        self.lint(Program::Module(program.clone()));
+    }
+
+    fn lint_script(&mut self, program: &Script) {
+        self.lint(Program::Script(program.clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use serde_json::json;
+    use swc_common::errors::{DiagnosticId, Handler, Level, HANDLER};
+    use swc_ecma_ast::{Module, Program};
+
+    use super::plugin_rule;
+    use crate::{config::LintPluginConfig, rule::Capturing};
+
+    fn config(name: &str, rules: serde_json::Value) -> LintPluginConfig {
+        serde_json::from_value(json!([name, rules])).unwrap()
+    }
+
+    /// Runs plugins which report a warning for each rule in `rules`, and a
+    /// diagnostic without a code of a rule.
+    fn lint(
+        configs: &[LintPluginConfig],
+        rules: &'static [&'static str],
+    ) -> Vec<(usize, Level, String)> {
+        let rule = plugin_rule(
+            configs,
+            |program| matches!(program, Program::Module(..)),
+            move |index, is_module| {
+                assert!(is_module);
+
+                HANDLER.with(|handler| {
+                    for rule in rules {
+                        handler
+                            .struct_warn(&format!("{index}: {rule}"))
+                            .code(DiagnosticId::Lint(rule.to_string()))
+                            .emit();
+                    }
+                    handler.err_with_code(
+                        &format!("{index}: failed"),
+                        DiagnosticId::Error("plugin".into()),
+                    );
+                })
+            },
+        );
+
+        let capturing = Capturing::default();
+        HANDLER.set(
+            &Handler::with_emitter(true, false, Box::new(capturing.clone())),
+            || rule.unwrap().lint_module(&Module::default()),
+        );
+
+        let errors = capturing.errors.lock();
+        errors
+            .iter()
+            .map(|error| {
+                let message = error.message();
+                let index = message[..1].parse().unwrap();
+                (index, error.level, message[3..].to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn replaces_levels_of_plugin_rules() {
+        let configs = [
+            config("a", json!({ "no-foo": ["error"], "no-bar": ["warning"] })),
+            config("b", json!({ "no-foo": ["warning"], "no-bar": ["off"] })),
+        ];
+
+        assert_eq!(
+            lint(&configs, &["no-foo", "no-bar", "no-baz"]),
+            vec![
+                (0, Level::Error, "no-foo".into()),
+                (0, Level::Warning, "no-bar".into()),
+                (0, Level::Error, "failed".into()),
+                (1, Level::Warning, "no-foo".into()),
+                (1, Level::Error, "failed".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_plugins_without_enabled_rules() {
+        let configs = [
+            config("a", json!({ "no-foo": ["off"] })),
+            config("b", json!({ "no-foo": ["error"] })),
+        ];
+
+        assert_eq!(
+            lint(&configs, &["no-foo"]),
+            vec![
+                (1, Level::Error, "no-foo".into()),
+                (1, Level::Error, "failed".into()),
+            ]
+        );
+
+        assert!(plugin_rule(&configs[..1], |_| (), |_, _| {}).is_none());
+        assert!(plugin_rule(&[config("a", json!({}))], |_| (), |_, _| {}).is_none());
+    }
+
+    #[test]
+    fn prepares_program_once_for_all_plugins() {
+        let configs = [
+            config("a", json!({ "no-foo": ["error"] })),
+            config("b", json!({ "no-foo": ["error"] })),
+        ];
+        let prepared = Arc::new(AtomicUsize::new(0));
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let mut rule = plugin_rule(
+            &configs,
+            {
+                let prepared = prepared.clone();
+                move |_| prepared.fetch_add(1, Ordering::SeqCst)
+            },
+            {
+                let runs = runs.clone();
+                move |_, input| {
+                    assert_eq!(*input, 0);
+                    runs.fetch_add(1, Ordering::SeqCst);
+                }
+            },
+        )
+        .unwrap();
+
+        HANDLER.set(
+            &Handler::with_emitter(true, false, Box::new(Capturing::default())),
+            || rule.lint_module(&Module::default()),
+        );
+
+        assert_eq!(prepared.load(Ordering::SeqCst), 1);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+}
@@ -4,7 +4,7 @@ use auto_impl::auto_impl;
 use par_core::join;
 use parking_lot::Mutex;
 use swc_common::{
-    errors::{Diagnostic, DiagnosticBuilder, Emitter, Handler, HANDLER},
+    errors::{Diagnostic, DiagnosticBuilder, DiagnosticId, Emitter, Handler, HANDLER},
     GLOBALS,
 };
 use swc_ecma_ast::{Module, Script};
@@ -102,8 +102,8 @@ fn lint_rules<N: LintNode<R>, R: Rule>(rules: &mut Vec<R>, program: &N) {
 }
 
 #[derive(Default, Clone)]
-struct Capturing {
-    errors: Arc<Mutex<Vec<Diagnostic>>>,
+pub(crate) struct Capturing {
+    pub(crate) errors: Arc<Mutex<Vec<Diagnostic>>>,
 }
 
 impl Emitter for Capturing {
@@ -150,3 +150,48 @@ where
         program.visit_with(&mut self.0);
     }
 }
+
+/// Reports diagnostics of `rule` with `name` as their code, which identifies
+/// the rule in reporters and suppression comments.
+pub(crate) fn named_rule(name: &'static str, rule: Box<dyn Rule>) -> Box<dyn Rule> {
+    Box::new(NamedRule { name, rule })
+}
+
+#[derive(Debug)]
+struct NamedRule {
+    name: &'static str,
+    rule: Box<dyn Rule>,
+}
+
+impl NamedRule {
+    fn with_code(&mut self, op: impl FnOnce(&mut dyn Rule)) {
+        let capturing = Capturing::default();
+
+        HANDLER.set(
+            &Handler::with_emitter(true, false, Box::new(capturing.clone())),
+            || op(&mut *self.rule),
+        );
+
+        let errors = take(&mut *capturing.errors.lock());
+
+        HANDLER.with(|handler| {
+            for mut error in errors {
+                error
+                    .code
+                    .get_or_insert_with(|| DiagnosticId::Lint(self.name.into()));
+
+                DiagnosticBuilder::new_diagnostic(handler, error).emit();
+            }
+        });
+    }
+}
+
+impl Rule for NamedRule {
+    fn lint_module(&mut self, program: &Module) {
+        self.with_code(|rule| rule.lint_module(program));
+    }
+
+    fn lint_script(&mut self, program: &Script) {
+        self.with_code(|rule| rule.lint_script(program));
+    }
+}
@@ -0,0 +1,157 @@
+use rustc_hash::FxHashMap;
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    BytePos, Span, Spanned,
+};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const ALL_TYPES_MESSAGE: &str = "All imports in the declaration are only used as types";
+
+pub fn consistent_type_imports(config: &RuleConfig<()>) -> Option<Box<dyn Rule>> {
+    let rule_reaction = config.get_rule_reaction();
+
+    match rule_reaction {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(ConsistentTypeImports::new(rule_reaction))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct ConsistentTypeImports {
+    expected_reaction: LintRuleReaction,
+
+    /// Referenced bindings, and whether they are referenced as values.
+    references: FxHashMap<Id, bool>,
+    in_type: bool,
+}
+
+impl ConsistentTypeImports {
+    fn new(expected_reaction: LintRuleReaction) -> Self {
+        Self {
+            expected_reaction,
+            ..Default::default()
+        }
+    }
+
+    fn emit_report(&self, span: Span, message: &str, fix: Option<(BytePos, &str)>) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, message),
+                _ => return,
+            };
+
+            if let Some((pos, text)) = fix {
+                db.span_suggestion_with_applicability(
+                    Span::new(pos, pos),
+                    "Use a type-only import",
+                    text.into(),
+                    Applicability::MachineApplicable,
+                );
+            }
+
+            db.emit();
+        });
+    }
+
+    fn with_type(&mut self, in_type: bool, op: impl FnOnce(&mut Self)) {
+        let prev = self.in_type;
+        self.in_type |= in_type;
+
+        op(self);
+
+        self.in_type = prev;
+    }
+
+    /// Unused imports are not reported, and imports are reported if all
+    /// references of them are in types.
+    fn check_import_decl(&self, import_decl: &ImportDecl) {
+        if import_decl.type_only {
+            return;
+        }
+
+        let mut type_specifiers = Vec::new();
+        let mut has_value_specifier = false;
+        let mut has_inline_type = false;
+
+        for specifier in &import_decl.specifiers {
+            if specifier.is_type_only() {
+                has_inline_type = true;
+                continue;
+            }
+
+            match self.references.get(&specifier.local().to_id()) {
+                Some(false) => type_specifiers.push(specifier),
+                Some(true) => has_value_specifier = true,
+                None => {}
+            }
+        }
+
+        if type_specifiers.is_empty() {
+            return;
+        }
+
+        if !has_value_specifier {
+            // A type-only import can not have both a default import and other
+            // imports, or inline `type` modifiers.
+            let has_default = import_decl.specifiers.iter().any(|s| s.is_default());
+            let fix = (!has_inline_type && (!has_default || import_decl.specifiers.len() == 1))
+                .then_some((import_decl.span.lo + BytePos(6), " type"));
+
+            self.emit_report(import_decl.span, ALL_TYPES_MESSAGE, fix);
+            return;
+        }
+
+        for specifier in type_specifiers {
+            let message = format!("Import '{}' is only used as types", specifier.local().sym);
+            let fix = specifier
+                .is_named()
+                .then_some((specifier.span().lo, "type "));
+
+            self.emit_report(specifier.span(), &message, fix);
+        }
+    }
+}
+
+impl Visit for ConsistentTypeImports {
+    fn visit_module(&mut self, module: &Module) {
+        module.visit_children_with(self);
+
+        for item in &module.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) = item {
+                self.check_import_decl(import_decl);
+            }
+        }
+    }
+
+    fn visit_import_decl(&mut self, _: &ImportDecl) {}
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        *self.references.entry(ident.to_id()).or_default() |= !self.in_type;
+    }
+
+    fn visit_ts_type(&mut self, ty: &TsType) {
+        self.with_type(true, |v| ty.visit_children_with(v));
+    }
+
+    /// `implements` of classes and `extends` of interfaces.
+    fn visit_ts_expr_with_type_args(&mut self, expr: &TsExprWithTypeArgs) {
+        self.with_type(true, |v| expr.visit_children_with(v));
+    }
+
+    fn visit_named_export(&mut self, named_export: &NamedExport) {
+        self.with_type(named_export.type_only, |v| {
+            named_export.visit_children_with(v)
+        });
+    }
+
+    fn visit_export_named_specifier(&mut self, specifier: &ExportNamedSpecifier) {
+        self.with_type(specifier.is_type_only, |v| specifier.visit_children_with(v));
+    }
+}
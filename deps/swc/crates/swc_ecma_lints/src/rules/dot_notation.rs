@@ -2,7 +2,11 @@ use dashmap::DashMap;
 use regex::Regex;
 use rustc_hash::FxBuildHasher;
 use serde::{Deserialize, Serialize};
-use swc_common::{errors::HANDLER, sync::Lazy, Span};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    sync::Lazy,
+    BytePos, Span,
+};
 use swc_ecma_ast::*;
 use swc_ecma_visit::{noop_visit_type, Visit, VisitWith};
 
@@ -51,25 +55,34 @@ impl DotNotation {
         }
     }
 
-    fn emit_report(&self, span: Span, quote_type: QuotesType, prop: &str) {
+    fn emit_report(&self, span: Span, quote_type: QuotesType, prop: &str, fix: Option<String>) {
         let message = format!(
             "[{quote}{prop}{quote}] is better written in dot notation",
             prop = prop,
             quote = quote_type.get_char()
         );
 
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, &message).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, &message).emit();
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, &message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, &message),
+                _ => return,
+            };
+
+            if let Some(fix) = fix {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Use dot notation",
+                    fix,
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
 
-    fn check(&self, span: Span, quote_type: QuotesType, prop_name: &str) {
+    fn check(&self, span: Span, quote_type: QuotesType, prop_name: &str, fix: Option<String>) {
         if self.allow_keywords
             && (prop_name.is_reserved() || prop_name.is_reserved_in_strict_mode(self.is_module))
         {
@@ -92,23 +105,32 @@ impl DotNotation {
             }
         }
 
-        self.emit_report(span, quote_type, prop_name);
+        self.emit_report(span, quote_type, prop_name, fix);
     }
-}
 
-impl Visit for DotNotation {
-    noop_visit_type!();
-
-    fn visit_member_prop(&mut self, member: &MemberProp) {
+    /// `optional` is `true` if the property follows `?.`.
+    fn check_member_prop(&mut self, obj: &Expr, member: &MemberProp, optional: bool) {
         if let MemberProp::Computed(prop) = member {
             match &*prop.expr {
                 Expr::Lit(Lit::Str(lit_str)) => {
                     let quote_type = resolve_string_quote_type(lit_str).unwrap();
-
-                    self.check(prop.span, quote_type, &lit_str.value.to_string_lossy());
-                }
-                Expr::Member(member) => {
-                    member.visit_children_with(self);
+                    let prop_name = lit_str.value.to_string_lossy();
+
+                    // Comments or whitespaces in brackets are not supported, and
+                    // `1.foo` is not a member expression.
+                    let fix = (Ident::verify_symbol(&prop_name).is_ok()
+                        && prop.span.hi - prop.span.lo
+                            == lit_str.span.hi - lit_str.span.lo + BytePos(2)
+                        && !matches!(obj, Expr::Lit(Lit::Num(..))))
+                    .then(|| {
+                        if optional {
+                            prop_name.to_string()
+                        } else {
+                            format!(".{prop_name}")
+                        }
+                    });
+
+                    self.check(prop.span, quote_type, &prop_name, fix);
                 }
                 _ => {
                     prop.visit_with(self);
@@ -117,3 +139,24 @@ impl Visit for DotNotation {
         }
     }
 }
+
+impl Visit for DotNotation {
+    noop_visit_type!();
+
+    fn visit_member_expr(&mut self, member: &MemberExpr) {
+        member.obj.visit_with(self);
+
+        self.check_member_prop(&member.obj, &member.prop, false);
+    }
+
+    fn visit_opt_chain_expr(&mut self, opt_chain: &OptChainExpr) {
+        match &*opt_chain.base {
+            OptChainBase::Member(member) => {
+                member.obj.visit_with(self);
+
+                self.check_member_prop(&member.obj, &member.prop, opt_chain.optional);
+            }
+            _ => opt_chain.visit_children_with(self),
+        }
+    }
+}
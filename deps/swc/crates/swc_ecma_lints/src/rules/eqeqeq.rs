@@ -1,5 +1,13 @@
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
 use serde::{Deserialize, Serialize};
-use swc_common::{errors::HANDLER, Span};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    BytePos, SourceMap, SourceMapper, Span, Spanned,
+};
 use swc_ecma_ast::*;
 use swc_ecma_visit::{noop_visit_type, Visit, VisitWith};
 
@@ -22,65 +30,119 @@ pub struct EqeqeqConfig {
     mode: EqEqEqMode,
 }
 
-pub fn eqeqeq(config: &RuleConfig<EqeqeqConfig>) -> Option<Box<dyn Rule>> {
+pub fn eqeqeq(
+    source_map: &Arc<SourceMap>,
+    config: &RuleConfig<EqeqeqConfig>,
+) -> Option<Box<dyn Rule>> {
     match config.get_rule_reaction() {
         LintRuleReaction::Off => None,
-        _ => Some(visitor_rule(Eqeqeq::new(config))),
+        _ => Some(visitor_rule(Eqeqeq::new(source_map, config))),
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 struct Eqeqeq {
+    source_map: Arc<SourceMap>,
+
     expected_reaction: LintRuleReaction,
     mode: EqEqEqMode,
 }
 
+impl Debug for Eqeqeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Eqeqeq")
+            .field("expected_reaction", &self.expected_reaction)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
 impl Eqeqeq {
-    fn new(config: &RuleConfig<EqeqeqConfig>) -> Self {
+    fn new(source_map: &Arc<SourceMap>, config: &RuleConfig<EqeqeqConfig>) -> Self {
         Self {
+            source_map: source_map.clone(),
             expected_reaction: config.get_rule_reaction(),
             mode: config.get_rule_config().mode,
         }
     }
 
-    fn emit_report(&self, span: Span, actual: &str, expected: &str) {
+    fn emit_report(&self, span: Span, actual: &str, expected: &str, op_span: Option<Span>) {
         let message = format!("Use '{expected}' instead of '{actual}'");
 
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, &message).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, &message).emit();
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, &message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, &message),
+                _ => return,
+            };
+
+            if let Some(op_span) = op_span {
+                db.span_suggestion_with_applicability(
+                    op_span,
+                    &format!("Replace '{actual}' with '{expected}'"),
+                    expected.to_string(),
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
 
-    fn check(&self, span: Span, bin_op: &BinaryOp) {
-        match bin_op {
-            op!("==") => {
-                if let EqEqEqMode::Always = self.mode {
-                    self.emit_report(span, "==", "===");
-                }
-            }
-            op!("!=") => {
-                if let EqEqEqMode::Always = self.mode {
-                    self.emit_report(span, "!=", "!==");
-                }
+    /// Returns the span of the operator if replacing it doesn't change the
+    /// result, which is the case if both operands are of the same type.
+    fn fixable_op_span(&self, bin_expr: &BinExpr, op: &str) -> Option<Span> {
+        let is_typeof = |expr: &Expr| {
+            matches!(
+                expr,
+                Expr::Unary(UnaryExpr {
+                    op: op!("typeof"),
+                    ..
+                })
+            )
+        };
+        let is_same_type = match (&*bin_expr.left, &*bin_expr.right) {
+            (Expr::Lit(Lit::Str(..)), right) => {
+                is_typeof(right) || matches!(right, Expr::Lit(Lit::Str(..)))
             }
-            op!("===") => {
-                if let EqEqEqMode::Never = self.mode {
-                    self.emit_report(span, "===", "==");
-                }
-            }
-            op!("!==") => {
-                if let EqEqEqMode::Never = self.mode {
-                    self.emit_report(span, "!==", "!=");
-                }
-            }
-            _ => {}
+            (left, Expr::Lit(Lit::Str(..))) => is_typeof(left),
+            (Expr::Lit(Lit::Num(..)), Expr::Lit(Lit::Num(..)))
+            | (Expr::Lit(Lit::Bool(..)), Expr::Lit(Lit::Bool(..)))
+            | (Expr::Lit(Lit::Null(..)), Expr::Lit(Lit::Null(..))) => true,
+            _ => false,
+        };
+
+        if !is_same_type {
+            return None;
+        }
+
+        // Comments between operands are not supported.
+        let gap = Span::new(bin_expr.left.span_hi(), bin_expr.right.span_lo());
+        let snippet = self.source_map.span_to_snippet(gap).ok()?;
+        if snippet.trim() != op {
+            return None;
         }
+
+        let lo = gap.lo + BytePos((snippet.len() - snippet.trim_start().len()) as u32);
+
+        Some(Span::new(lo, lo + BytePos(op.len() as u32)))
+    }
+
+    fn check(&self, bin_expr: &BinExpr) {
+        let (actual, expected) = match (bin_expr.op, self.mode) {
+            (op!("=="), EqEqEqMode::Always) => ("==", "==="),
+            (op!("!="), EqEqEqMode::Always) => ("!=", "!=="),
+            (op!("==="), EqEqEqMode::Never) => ("===", "=="),
+            (op!("!=="), EqEqEqMode::Never) => ("!==", "!="),
+            _ => return,
+        };
+
+        self.emit_report(
+            bin_expr.span,
+            actual,
+            expected,
+            self.fixable_op_span(bin_expr, actual),
+        );
     }
 }
 
@@ -88,7 +150,7 @@ impl Visit for Eqeqeq {
     noop_visit_type!();
 
     fn visit_bin_expr(&mut self, bin_expr: &BinExpr) {
-        self.check(bin_expr.span, &bin_expr.op);
+        self.check(bin_expr);
 
         bin_expr.visit_children_with(self);
     }
@@ -0,0 +1,336 @@
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    SourceMap, SourceMapper, Span, Spanned,
+};
+use swc_ecma_ast::*;
+use swc_ecma_utils::assertion_chain_would_change_binary_grouping;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErasableSyntaxOnlyConfig {
+    /// Also reports `module Foo {}`, like the `deprecatedTsModuleAsError`
+    /// option of `swc_ts_fast_strip`.
+    deprecated_ts_module_as_error: Option<bool>,
+}
+
+/// Reports TypeScript syntax which can not be erased, like type stripping of
+/// Node.js and the strip-only mode of `swc_ts_fast_strip`.
+pub fn erasable_syntax_only(
+    source_map: &Arc<SourceMap>,
+    config: &RuleConfig<ErasableSyntaxOnlyConfig>,
+) -> Option<Box<dyn Rule>> {
+    let rule_reaction = config.get_rule_reaction();
+
+    match rule_reaction {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(ErasableSyntaxOnly::new(
+            source_map.clone(),
+            rule_reaction,
+            config.get_rule_config(),
+        ))),
+    }
+}
+
+#[derive(Default)]
+struct ErasableSyntaxOnly {
+    source_map: Arc<SourceMap>,
+
+    expected_reaction: LintRuleReaction,
+
+    deprecated_ts_module_as_error: bool,
+
+    /// The operator of the binary expression whose left operand is the type
+    /// assertion being visited.
+    next_binary_op: Option<BinaryOp>,
+}
+
+impl Debug for ErasableSyntaxOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ErasableSyntaxOnly")
+            .field("expected_reaction", &self.expected_reaction)
+            .field(
+                "deprecated_ts_module_as_error",
+                &self.deprecated_ts_module_as_error,
+            )
+            .finish()
+    }
+}
+
+/// Declarations which are removed as a whole, so their contents don't need
+/// to be erasable.
+fn is_erased_decl(decl: &Decl) -> bool {
+    match decl {
+        Decl::TsInterface(..) | Decl::TsTypeAlias(..) => true,
+        Decl::TsModule(module) => {
+            module.declare || module.id.is_str() || is_uninstantiated_module(module)
+        }
+        Decl::TsEnum(r#enum) => r#enum.declare,
+        Decl::Var(var) => var.declare,
+        Decl::Fn(FnDecl { declare: true, .. }) | Decl::Class(ClassDecl { declare: true, .. }) => {
+            true
+        }
+        Decl::Fn(FnDecl { function, .. }) => function.body.is_none(),
+        _ => false,
+    }
+}
+
+fn is_erased_default_decl(decl: &DefaultDecl) -> bool {
+    match decl {
+        DefaultDecl::Fn(r#fn) => r#fn.function.body.is_none(),
+        DefaultDecl::TsInterfaceDecl(..) => true,
+        _ => false,
+    }
+}
+
+/// Namespaces which only contain types.
+fn is_uninstantiated_module(module: &TsModuleDecl) -> bool {
+    fn is_uninstantiated_decl(decl: &Decl) -> bool {
+        match decl {
+            Decl::TsInterface(..) | Decl::TsTypeAlias(..) => true,
+            Decl::TsModule(module) => is_uninstantiated_module(module),
+            _ => false,
+        }
+    }
+
+    fn is_uninstantiated_body(body: &TsNamespaceBody) -> bool {
+        match body {
+            TsNamespaceBody::TsModuleBlock(block) => block.body.iter().all(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(decl))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+                    is_uninstantiated_decl(decl)
+                }
+                _ => false,
+            }),
+            TsNamespaceBody::TsNamespaceDecl(decl) => is_uninstantiated_body(&decl.body),
+            #[cfg(swc_ast_unknown)]
+            _ => false,
+        }
+    }
+
+    matches!(&module.body, Some(body) if is_uninstantiated_body(body))
+}
+
+impl ErasableSyntaxOnly {
+    fn new(
+        source_map: Arc<SourceMap>,
+        expected_reaction: LintRuleReaction,
+        config: &ErasableSyntaxOnlyConfig,
+    ) -> Self {
+        Self {
+            source_map,
+            expected_reaction,
+            deprecated_ts_module_as_error: config.deprecated_ts_module_as_error.unwrap_or_default(),
+            next_binary_op: None,
+        }
+    }
+
+    fn emit_report(&self, span: Span, message: &str, fix: Option<String>) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, message),
+                _ => return,
+            };
+
+            if let Some(fix) = fix {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Use the 'as' syntax",
+                    fix,
+                    Applicability::MachineApplicable,
+                );
+            }
+
+            db.emit();
+        });
+    }
+
+    /// Reports `module Foo {}` and the ones nested in it, even if they are
+    /// erased, as `swc_ts_fast_strip` does.
+    fn check_module_keyword(&self, module_decl: &TsModuleDecl) {
+        if !module_decl.global && !module_decl.id.is_str() {
+            let uses_module_keyword =
+                self.source_map
+                    .span_to_snippet(module_decl.span)
+                    .is_ok_and(|snippet| {
+                        let snippet = snippet.strip_prefix("declare").unwrap_or(&snippet);
+
+                        snippet.trim_start().starts_with("module")
+                    });
+
+            if uses_module_keyword {
+                self.emit_report(
+                    module_decl.span,
+                    "`module` keyword is not supported. Use `namespace` instead.",
+                    None,
+                );
+            }
+        }
+
+        let mut body = module_decl.body.as_ref();
+
+        while let Some(TsNamespaceBody::TsNamespaceDecl(namespace_decl)) = body {
+            body = Some(&namespace_decl.body);
+        }
+
+        if let Some(TsNamespaceBody::TsModuleBlock(block)) = body {
+            for item in &block.body {
+                if let ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(module_decl)))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::TsModule(module_decl),
+                    ..
+                })) = item
+                {
+                    self.check_module_keyword(module_decl);
+                }
+            }
+        }
+    }
+
+    fn check_assertion_grouping(&self, span: Span, expr: &Expr, next_op: Option<BinaryOp>) {
+        if next_op.is_some_and(|op| assertion_chain_would_change_binary_grouping(expr, op)) {
+            self.emit_report(
+                span,
+                "Type assertions that would change binary expression grouping are not erasable \
+                 syntax. Wrap the expression in parentheses",
+                None,
+            );
+        }
+    }
+
+    /// `<T>expr` is fixed to `(expr as T)`. `expr` of a type assertion is a
+    /// unary expression, so it doesn't need parentheses.
+    fn build_type_assertion_fix(&self, type_assertion: &TsTypeAssertion) -> Option<String> {
+        let expr = self
+            .source_map
+            .span_to_snippet(type_assertion.expr.span())
+            .ok()?;
+        let ty = self
+            .source_map
+            .span_to_snippet(type_assertion.type_ann.span())
+            .ok()?;
+
+        Some(format!("({expr} as {ty})"))
+    }
+}
+
+impl Visit for ErasableSyntaxOnly {
+    fn visit_decl(&mut self, decl: &Decl) {
+        if let Decl::TsModule(module_decl) = decl {
+            if self.deprecated_ts_module_as_error {
+                self.check_module_keyword(module_decl);
+            }
+        }
+
+        if !is_erased_decl(decl) {
+            decl.visit_children_with(self);
+        }
+    }
+
+    fn visit_export_default_decl(&mut self, export_default_decl: &ExportDefaultDecl) {
+        if !is_erased_default_decl(&export_default_decl.decl) {
+            export_default_decl.visit_children_with(self);
+        }
+    }
+
+    fn visit_ts_enum_decl(&mut self, enum_decl: &TsEnumDecl) {
+        self.emit_report(
+            enum_decl.span,
+            "TypeScript enum is not erasable syntax",
+            None,
+        );
+    }
+
+    fn visit_ts_module_decl(&mut self, module_decl: &TsModuleDecl) {
+        self.emit_report(
+            module_decl.span,
+            "TypeScript namespace declaration is not erasable syntax",
+            None,
+        );
+    }
+
+    fn visit_ts_param_prop(&mut self, param_prop: &TsParamProp) {
+        self.emit_report(
+            param_prop.span,
+            "TypeScript parameter property is not erasable syntax",
+            None,
+        );
+
+        param_prop.visit_children_with(self);
+    }
+
+    fn visit_ts_import_equals_decl(&mut self, import_equals_decl: &TsImportEqualsDecl) {
+        if !import_equals_decl.is_type_only {
+            self.emit_report(
+                import_equals_decl.span,
+                "TypeScript import equals declaration is not erasable syntax",
+                None,
+            );
+        }
+    }
+
+    fn visit_ts_export_assignment(&mut self, export_assignment: &TsExportAssignment) {
+        self.emit_report(
+            export_assignment.span,
+            "TypeScript export assignment is not erasable syntax",
+            None,
+        );
+    }
+
+    fn visit_ts_type_assertion(&mut self, type_assertion: &TsTypeAssertion) {
+        self.emit_report(
+            type_assertion.span,
+            "The angle-bracket syntax for type assertions, `<T>expr`, is not erasable syntax. \
+             Use the 'as' syntax: `expr as T`",
+            self.build_type_assertion_fix(type_assertion),
+        );
+
+        type_assertion.visit_children_with(self);
+    }
+
+    fn visit_bin_expr(&mut self, bin_expr: &BinExpr) {
+        if matches!(
+            &*bin_expr.left,
+            Expr::TsAs(..) | Expr::TsSatisfies(..) | Expr::TsConstAssertion(..)
+        ) {
+            self.next_binary_op = Some(bin_expr.op);
+        }
+
+        bin_expr.left.visit_with(self);
+        self.next_binary_op = None;
+        bin_expr.right.visit_with(self);
+    }
+
+    fn visit_ts_as_expr(&mut self, as_expr: &TsAsExpr) {
+        let next_op = self.next_binary_op.take();
+        self.check_assertion_grouping(as_expr.span, &as_expr.expr, next_op);
+
+        as_expr.visit_children_with(self);
+    }
+
+    fn visit_ts_satisfies_expr(&mut self, satisfies_expr: &TsSatisfiesExpr) {
+        let next_op = self.next_binary_op.take();
+        self.check_assertion_grouping(satisfies_expr.span, &satisfies_expr.expr, next_op);
+
+        satisfies_expr.visit_children_with(self);
+    }
+
+    fn visit_ts_const_assertion(&mut self, const_assertion: &TsConstAssertion) {
+        let next_op = self.next_binary_op.take();
+        self.check_assertion_grouping(const_assertion.span, &const_assertion.expr, next_op);
+
+        const_assertion.visit_children_with(self);
+    }
+}
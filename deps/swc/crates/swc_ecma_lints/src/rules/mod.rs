@@ -11,11 +11,13 @@ mod utils;
 #[cfg(feature = "non_critical_lints")]
 #[path = ""]
 pub(crate) mod non_critical_lints {
+    pub mod consistent_type_imports;
     pub mod constructor_super;
     pub mod default_case_last;
     pub mod default_param_last;
     pub mod dot_notation;
     pub mod eqeqeq;
+    pub mod erasable_syntax_only;
     pub mod no_alert;
     pub mod no_await_in_loop;
     pub mod no_bitwise;
@@ -25,10 +27,14 @@ pub(crate) mod non_critical_lints {
     pub mod no_debugger;
     pub mod no_empty_function;
     pub mod no_empty_pattern;
+    pub mod no_enum;
+    pub mod no_explicit_any;
     pub mod no_loop_func;
+    pub mod no_namespace;
     pub mod no_new;
     pub mod no_new_object;
     pub mod no_new_symbol;
+    pub mod no_non_null_assertion;
     pub mod no_obj_calls;
     pub mod no_param_reassign;
     pub mod no_prototype_builtins;
@@ -37,6 +43,7 @@ pub(crate) mod non_critical_lints {
     pub mod no_throw_literal;
     pub mod no_use_before_define;
     pub mod no_var;
+    pub mod prefer_as_const;
     pub mod prefer_const;
     pub mod prefer_object_spread;
     pub mod prefer_regex_literals;
@@ -60,6 +67,12 @@ pub struct LintParams<'a> {
     pub source_map: Arc<SourceMap>,
 }
 
+/// Names `rule`, which is used as the code of its diagnostics.
+#[cfg(feature = "non_critical_lints")]
+fn named(name: &'static str, rule: Option<Box<dyn Rule>>) -> Option<Box<dyn Rule>> {
+    rule.map(|rule| crate::rule::named_rule(name, rule))
+}
+
 pub fn all(#[allow(unused)] lint_params: LintParams) -> Vec<Box<dyn Rule>> {
     let rules = vec![critical_rules::critical_rules()];
 
@@ -76,134 +89,216 @@ pub fn all(#[allow(unused)] lint_params: LintParams) -> Vec<Box<dyn Rule>> {
             source_map,
         } = lint_params;
 
-        rules.extend(no_use_before_define::no_use_before_define(
-            &lint_params.lint_config.no_use_before_define,
+        rules.extend(named(
+            "no-use-before-define",
+            no_use_before_define::no_use_before_define(
+                &lint_params.lint_config.no_use_before_define,
+            ),
         ));
 
-        rules.extend(no_console::no_console(
-            &lint_config.no_console,
-            unresolved_ctxt,
+        rules.extend(named(
+            "no-console",
+            no_console::no_console(&lint_config.no_console, unresolved_ctxt),
         ));
 
-        rules.extend(no_alert::no_alert(
-            &lint_config.no_alert,
-            unresolved_ctxt,
-            es_version,
+        rules.extend(named(
+            "no-alert",
+            no_alert::no_alert(&lint_config.no_alert, unresolved_ctxt, es_version),
         ));
 
-        rules.extend(no_debugger::no_debugger(&lint_config.no_debugger));
+        rules.extend(named(
+            "no-debugger",
+            no_debugger::no_debugger(&lint_config.no_debugger),
+        ));
 
-        rules.extend(quotes::quotes(&lint_config.quotes));
+        rules.extend(named("quotes", quotes::quotes(&lint_config.quotes)));
 
-        rules.extend(prefer_regex_literals::prefer_regex_literals(
-            &lint_config.prefer_regex_literals,
-            unresolved_ctxt,
-            es_version,
+        rules.extend(named(
+            "prefer-regex-literals",
+            prefer_regex_literals::prefer_regex_literals(
+                &lint_config.prefer_regex_literals,
+                unresolved_ctxt,
+                es_version,
+            ),
         ));
 
-        rules.extend(dot_notation::dot_notation(
-            program,
-            &lint_config.dot_notation,
+        rules.extend(named(
+            "dot-notation",
+            dot_notation::dot_notation(program, &lint_config.dot_notation),
         ));
 
-        rules.extend(no_empty_function::no_empty_function(
-            &source_map,
-            &lint_config.no_empty_function,
+        rules.extend(named(
+            "no-empty-function",
+            no_empty_function::no_empty_function(&source_map, &lint_config.no_empty_function),
         ));
 
-        rules.extend(no_empty_pattern::no_empty_pattern(
-            &lint_config.no_empty_pattern,
+        rules.extend(named(
+            "no-empty-pattern",
+            no_empty_pattern::no_empty_pattern(&lint_config.no_empty_pattern),
         ));
 
-        rules.extend(eqeqeq::eqeqeq(&lint_config.eqeqeq));
+        rules.extend(named(
+            "eqeqeq",
+            eqeqeq::eqeqeq(&source_map, &lint_config.eqeqeq),
+        ));
 
-        rules.extend(no_loop_func::no_loop_func(&lint_config.no_loop_func));
+        rules.extend(named(
+            "no-loop-func",
+            no_loop_func::no_loop_func(&lint_config.no_loop_func),
+        ));
 
-        rules.extend(no_new::no_new(&lint_config.no_new));
+        rules.extend(named("no-new", no_new::no_new(&lint_config.no_new)));
 
-        rules.extend(no_restricted_syntax::no_restricted_syntax(
-            &lint_config.no_restricted_syntax,
+        rules.extend(named(
+            "no-restricted-syntax",
+            no_restricted_syntax::no_restricted_syntax(&lint_config.no_restricted_syntax),
         ));
 
-        rules.extend(radix::radix(unresolved_ctxt, &lint_config.radix));
+        rules.extend(named(
+            "radix",
+            radix::radix(unresolved_ctxt, &lint_config.radix),
+        ));
 
-        rules.extend(no_bitwise::no_bitwise(&lint_config.no_bitwise));
+        rules.extend(named(
+            "no-bitwise",
+            no_bitwise::no_bitwise(&lint_config.no_bitwise),
+        ));
 
-        rules.extend(default_param_last::default_param_last(
-            &lint_config.default_param_last,
+        rules.extend(named(
+            "default-param-last",
+            default_param_last::default_param_last(&lint_config.default_param_last),
         ));
 
-        rules.extend(yoda::yoda(&lint_config.yoda));
+        rules.extend(named("yoda", yoda::yoda(&lint_config.yoda)));
 
-        rules.extend(no_new_symbol::no_new_symbol(
-            unresolved_ctxt,
-            &lint_config.no_new_symbol,
+        rules.extend(named(
+            "no-new-symbol",
+            no_new_symbol::no_new_symbol(unresolved_ctxt, &lint_config.no_new_symbol),
         ));
 
-        rules.extend(use_is_nan::use_is_nan(
-            unresolved_ctxt,
-            &lint_config.use_isnan,
+        rules.extend(named(
+            "use-isnan",
+            use_is_nan::use_is_nan(unresolved_ctxt, &lint_config.use_isnan),
         ));
 
-        rules.extend(valid_typeof::valid_typeof(&lint_config.valid_typeof));
+        rules.extend(named(
+            "valid-typeof",
+            valid_typeof::valid_typeof(&lint_config.valid_typeof),
+        ));
 
-        rules.extend(no_param_reassign::no_param_reassign(
-            &lint_config.no_param_reassign,
+        rules.extend(named(
+            "no-param-reassign",
+            no_param_reassign::no_param_reassign(&lint_config.no_param_reassign),
         ));
 
-        rules.extend(symbol_description::symbol_description(
-            unresolved_ctxt,
-            &lint_config.symbol_description,
+        rules.extend(named(
+            "symbol-description",
+            symbol_description::symbol_description(
+                unresolved_ctxt,
+                &lint_config.symbol_description,
+            ),
         ));
 
-        rules.extend(no_obj_calls::no_obj_calls(
-            unresolved_ctxt,
-            &lint_config.no_obj_calls,
+        rules.extend(named(
+            "no-obj-calls",
+            no_obj_calls::no_obj_calls(unresolved_ctxt, &lint_config.no_obj_calls),
         ));
 
-        rules.extend(no_throw_literal::no_throw_literal(
-            &lint_config.no_throw_literal,
+        rules.extend(named(
+            "no-throw-literal",
+            no_throw_literal::no_throw_literal(&lint_config.no_throw_literal),
         ));
 
-        rules.extend(no_var::no_var(&lint_config.no_var));
+        rules.extend(named("no-var", no_var::no_var(&lint_config.no_var)));
 
-        rules.extend(prefer_const::prefer_const(&lint_config.prefer_const));
+        rules.extend(named(
+            "prefer-const",
+            prefer_const::prefer_const(&lint_config.prefer_const),
+        ));
 
-        rules.extend(no_compare_neg_zero::no_compare_neg_zero(
-            &lint_config.no_compare_neg_zero,
+        rules.extend(named(
+            "no-compare-neg-zero",
+            no_compare_neg_zero::no_compare_neg_zero(&lint_config.no_compare_neg_zero),
         ));
 
-        rules.extend(constructor_super::constructor_super(
-            &lint_config.constructor_super,
+        rules.extend(named(
+            "constructor-super",
+            constructor_super::constructor_super(&lint_config.constructor_super),
         ));
 
-        rules.extend(no_sparse_arrays::no_sparse_arrays(
-            &lint_config.no_sparse_arrays,
+        rules.extend(named(
+            "no-sparse-arrays",
+            no_sparse_arrays::no_sparse_arrays(&lint_config.no_sparse_arrays),
         ));
 
-        rules.extend(default_case_last::default_case_last(
-            &lint_config.default_case_last,
+        rules.extend(named(
+            "default-case-last",
+            default_case_last::default_case_last(&lint_config.default_case_last),
         ));
 
-        rules.extend(no_await_in_loop::no_await_in_loop(
-            &lint_config.no_await_in_loop,
+        rules.extend(named(
+            "no-await-in-loop",
+            no_await_in_loop::no_await_in_loop(&lint_config.no_await_in_loop),
         ));
 
-        rules.extend(no_cond_assign::no_cond_assign(&lint_config.no_cond_assign));
+        rules.extend(named(
+            "no-cond-assign",
+            no_cond_assign::no_cond_assign(&lint_config.no_cond_assign),
+        ));
 
-        rules.extend(no_prototype_builtins::no_prototype_builtins(
-            &lint_config.no_prototype_builtins,
+        rules.extend(named(
+            "no-prototype-builtins",
+            no_prototype_builtins::no_prototype_builtins(&lint_config.no_prototype_builtins),
         ));
 
-        rules.extend(no_new_object::no_new_object(
-            unresolved_ctxt,
-            &lint_config.no_new_object,
+        rules.extend(named(
+            "no-new-object",
+            no_new_object::no_new_object(unresolved_ctxt, &lint_config.no_new_object),
         ));
 
-        rules.extend(prefer_object_spread::prefer_object_spread(
-            &lint_config.prefer_object_spread,
-            unresolved_ctxt,
-            es_version,
+        rules.extend(named(
+            "prefer-object-spread",
+            prefer_object_spread::prefer_object_spread(
+                &source_map,
+                &lint_config.prefer_object_spread,
+                unresolved_ctxt,
+                es_version,
+            ),
+        ));
+
+        rules.extend(named(
+            "consistent-type-imports",
+            consistent_type_imports::consistent_type_imports(&lint_config.consistent_type_imports),
+        ));
+
+        rules.extend(named(
+            "no-explicit-any",
+            no_explicit_any::no_explicit_any(&lint_config.no_explicit_any),
+        ));
+
+        rules.extend(named(
+            "no-non-null-assertion",
+            no_non_null_assertion::no_non_null_assertion(&lint_config.no_non_null_assertion),
+        ));
+
+        rules.extend(named(
+            "no-namespace",
+            no_namespace::no_namespace(&lint_config.no_namespace),
+        ));
+
+        rules.extend(named("no-enum", no_enum::no_enum(&lint_config.no_enum)));
+
+        rules.extend(named(
+            "prefer-as-const",
+            prefer_as_const::prefer_as_const(&lint_config.prefer_as_const),
+        ));
+
+        rules.extend(named(
+            "erasable-syntax-only",
+            erasable_syntax_only::erasable_syntax_only(
+                &source_map,
+                &lint_config.erasable_syntax_only,
+            ),
         ));
 
         rules
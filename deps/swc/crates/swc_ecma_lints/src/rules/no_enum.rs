@@ -0,0 +1,51 @@
+use swc_common::{errors::HANDLER, Span};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const MESSAGE: &str = "Unexpected enum, use a union of literal types or an object with 'as const' \
+                       instead";
+
+pub fn no_enum(config: &RuleConfig<()>) -> Option<Box<dyn Rule>> {
+    let rule_reaction = config.get_rule_reaction();
+
+    match rule_reaction {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(NoEnum::new(rule_reaction))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct NoEnum {
+    expected_reaction: LintRuleReaction,
+}
+
+impl NoEnum {
+    fn new(expected_reaction: LintRuleReaction) -> Self {
+        Self { expected_reaction }
+    }
+
+    fn emit_report(&self, span: Span) {
+        HANDLER.with(|handler| match self.expected_reaction {
+            LintRuleReaction::Error => {
+                handler.struct_span_err(span, MESSAGE).emit();
+            }
+            LintRuleReaction::Warning => {
+                handler.struct_span_warn(span, MESSAGE).emit();
+            }
+            _ => {}
+        });
+    }
+}
+
+impl Visit for NoEnum {
+    fn visit_ts_enum_decl(&mut self, enum_decl: &TsEnumDecl) {
+        self.emit_report(enum_decl.span);
+
+        enum_decl.visit_children_with(self);
+    }
+}
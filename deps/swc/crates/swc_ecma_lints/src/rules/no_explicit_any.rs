@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    Span,
+};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const MESSAGE: &str = "Unexpected any. Specify a different type";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoExplicitAnyConfig {
+    /// Fix `any` to `unknown`.
+    fix_to_unknown: Option<bool>,
+}
+
+pub fn no_explicit_any(config: &RuleConfig<NoExplicitAnyConfig>) -> Option<Box<dyn Rule>> {
+    match config.get_rule_reaction() {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(NoExplicitAny::new(config))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct NoExplicitAny {
+    expected_reaction: LintRuleReaction,
+    fix_to_unknown: bool,
+}
+
+impl NoExplicitAny {
+    fn new(config: &RuleConfig<NoExplicitAnyConfig>) -> Self {
+        Self {
+            expected_reaction: config.get_rule_reaction(),
+            fix_to_unknown: config.get_rule_config().fix_to_unknown.unwrap_or(false),
+        }
+    }
+
+    fn emit_report(&self, span: Span) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, MESSAGE),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, MESSAGE),
+                _ => return,
+            };
+
+            if self.fix_to_unknown {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Use 'unknown'",
+                    "unknown".into(),
+                    Applicability::MachineApplicable,
+                );
+            }
+
+            db.emit();
+        });
+    }
+}
+
+impl Visit for NoExplicitAny {
+    fn visit_ts_keyword_type(&mut self, keyword_type: &TsKeywordType) {
+        if let TsKeywordTypeKind::TsAnyKeyword = keyword_type.kind {
+            self.emit_report(keyword_type.span);
+        }
+
+        keyword_type.visit_children_with(self);
+    }
+}
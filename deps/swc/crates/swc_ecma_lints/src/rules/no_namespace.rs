@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use swc_common::{errors::HANDLER, Span};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const MESSAGE: &str = "ES2015 module syntax is preferred over namespaces";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NoNamespaceConfig {
+    /// Allow namespaces with `declare`, and namespaces in them.
+    allow_declarations: Option<bool>,
+}
+
+pub fn no_namespace(config: &RuleConfig<NoNamespaceConfig>) -> Option<Box<dyn Rule>> {
+    match config.get_rule_reaction() {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(NoNamespace::new(config))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct NoNamespace {
+    expected_reaction: LintRuleReaction,
+    allow_declarations: bool,
+
+    in_declare: bool,
+}
+
+impl NoNamespace {
+    fn new(config: &RuleConfig<NoNamespaceConfig>) -> Self {
+        Self {
+            expected_reaction: config.get_rule_reaction(),
+            allow_declarations: config.get_rule_config().allow_declarations.unwrap_or(false),
+            in_declare: false,
+        }
+    }
+
+    fn emit_report(&self, span: Span) {
+        HANDLER.with(|handler| match self.expected_reaction {
+            LintRuleReaction::Error => {
+                handler.struct_span_err(span, MESSAGE).emit();
+            }
+            LintRuleReaction::Warning => {
+                handler.struct_span_warn(span, MESSAGE).emit();
+            }
+            _ => {}
+        });
+    }
+}
+
+impl Visit for NoNamespace {
+    fn visit_ts_module_decl(&mut self, module_decl: &TsModuleDecl) {
+        let in_declare = self.in_declare;
+        self.in_declare |= module_decl.declare;
+
+        // `declare global` and `declare module 'foo'` are not namespaces.
+        let is_namespace = !module_decl.global && module_decl.id.is_ident();
+
+        if is_namespace && !(self.allow_declarations && self.in_declare) {
+            self.emit_report(module_decl.span);
+        }
+
+        module_decl.visit_children_with(self);
+
+        self.in_declare = in_declare;
+    }
+}
@@ -0,0 +1,50 @@
+use swc_common::{errors::HANDLER, Span};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const MESSAGE: &str = "Forbidden non-null assertion";
+
+pub fn no_non_null_assertion(config: &RuleConfig<()>) -> Option<Box<dyn Rule>> {
+    let rule_reaction = config.get_rule_reaction();
+
+    match rule_reaction {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(NoNonNullAssertion::new(rule_reaction))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct NoNonNullAssertion {
+    expected_reaction: LintRuleReaction,
+}
+
+impl NoNonNullAssertion {
+    fn new(expected_reaction: LintRuleReaction) -> Self {
+        Self { expected_reaction }
+    }
+
+    fn emit_report(&self, span: Span) {
+        HANDLER.with(|handler| match self.expected_reaction {
+            LintRuleReaction::Error => {
+                handler.struct_span_err(span, MESSAGE).emit();
+            }
+            LintRuleReaction::Warning => {
+                handler.struct_span_warn(span, MESSAGE).emit();
+            }
+            _ => {}
+        });
+    }
+}
+
+impl Visit for NoNonNullAssertion {
+    fn visit_ts_non_null_expr(&mut self, non_null_expr: &TsNonNullExpr) {
+        self.emit_report(non_null_expr.span);
+
+        non_null_expr.visit_children_with(self);
+    }
+}
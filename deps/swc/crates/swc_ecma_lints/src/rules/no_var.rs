@@ -1,4 +1,8 @@
-use swc_common::{errors::HANDLER, Span};
+use rustc_hash::FxHashMap;
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    BytePos, Span, DUMMY_SP,
+};
 use swc_ecma_ast::*;
 use swc_ecma_visit::{Visit, VisitWith};
 
@@ -18,33 +22,250 @@ pub fn no_var(config: &RuleConfig<()>) -> Option<Box<dyn Rule>> {
     }
 }
 
+/// Declarations and references of variables, used to check if `var` can be
+/// replaced with `let`.
+#[derive(Debug, Default)]
+struct Bindings {
+    decl_counts: FxHashMap<Id, usize>,
+    references: FxHashMap<Id, Vec<Span>>,
+}
+
+impl Visit for Bindings {
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        *self.decl_counts.entry(binding.to_id()).or_default() += 1;
+
+        binding.visit_children_with(self);
+    }
+
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        *self.decl_counts.entry(fn_decl.ident.to_id()).or_default() += 1;
+
+        fn_decl.visit_children_with(self);
+    }
+
+    fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
+        *self
+            .decl_counts
+            .entry(class_decl.ident.to_id())
+            .or_default() += 1;
+
+        class_decl.visit_children_with(self);
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.references
+            .entry(ident.to_id())
+            .or_default()
+            .push(ident.span);
+    }
+}
+
 #[derive(Debug, Default)]
 struct NoVar {
     expected_reaction: LintRuleReaction,
+
+    bindings: Bindings,
+    is_module: bool,
+    fn_depth: usize,
+    loop_depth: usize,
+    in_switch_case: bool,
+    /// The span of the innermost block or program.
+    scope_span: Span,
+    /// The span of a `var` declaration which is an item of a statement list.
+    stmt_decl_span: Span,
 }
 
 impl NoVar {
     fn new(expected_reaction: LintRuleReaction) -> Self {
-        Self { expected_reaction }
+        Self {
+            expected_reaction,
+            ..Default::default()
+        }
     }
 
-    fn emit_error(&self, span: Span) {
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, MESSAGE).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, MESSAGE).emit();
+    fn emit_error(&self, span: Span, fix: Option<Span>) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, MESSAGE),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, MESSAGE),
+                _ => return,
+            };
+
+            if let Some(keyword_span) = fix {
+                db.span_suggestion_with_applicability(
+                    keyword_span,
+                    "Use 'let'",
+                    "let".into(),
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
+
+    /// `var` can be replaced with `let` if the variables are not redeclared,
+    /// and are only referenced after the declaration in the same block.
+    ///
+    /// Declarations in loops are not fixed, as `let` creates a binding for
+    /// each iteration, and neither are top-level declarations of scripts, which
+    /// create properties of the global object.
+    fn is_fixable(&self, var_decl: &VarDecl) -> bool {
+        if var_decl.span != self.stmt_decl_span
+            || var_decl.declare
+            || self.loop_depth > 0
+            || self.in_switch_case
+            || (!self.is_module && self.fn_depth == 0)
+        {
+            return false;
+        }
+
+        var_decl.decls.iter().all(|decl| {
+            let Pat::Ident(binding) = &decl.name else {
+                return false;
+            };
+            let id = binding.to_id();
+
+            self.bindings.decl_counts.get(&id) == Some(&1)
+                && self
+                    .bindings
+                    .references
+                    .get(&id)
+                    .into_iter()
+                    .flatten()
+                    .all(|span| {
+                        *span == binding.span
+                            || (span.lo >= var_decl.span.hi && span.hi <= self.scope_span.hi)
+                    })
+        })
+    }
+
+    fn visit_stmt_list_item(&mut self, stmt: &Stmt) {
+        self.stmt_decl_span = match stmt {
+            Stmt::Decl(Decl::Var(var_decl)) => var_decl.span,
+            _ => DUMMY_SP,
+        };
+    }
+
+    fn with_fn_scope(&mut self, op: impl FnOnce(&mut Self)) {
+        let loop_depth = self.loop_depth;
+        let in_switch_case = self.in_switch_case;
+
+        self.fn_depth += 1;
+        self.loop_depth = 0;
+        self.in_switch_case = false;
+
+        op(self);
+
+        self.fn_depth -= 1;
+        self.loop_depth = loop_depth;
+        self.in_switch_case = in_switch_case;
+    }
+
+    fn with_loop(&mut self, op: impl FnOnce(&mut Self)) {
+        self.loop_depth += 1;
+
+        op(self);
+
+        self.loop_depth -= 1;
+    }
 }
 
 impl Visit for NoVar {
+    fn visit_module(&mut self, module: &Module) {
+        module.visit_with(&mut self.bindings);
+
+        self.is_module = true;
+        self.scope_span = module.span;
+
+        module.visit_children_with(self);
+    }
+
+    fn visit_script(&mut self, script: &Script) {
+        script.visit_with(&mut self.bindings);
+
+        self.scope_span = script.span;
+
+        script.visit_children_with(self);
+    }
+
+    fn visit_module_item(&mut self, item: &ModuleItem) {
+        if let ModuleItem::Stmt(stmt) = item {
+            self.visit_stmt_list_item(stmt);
+        }
+
+        item.visit_children_with(self);
+    }
+
+    fn visit_stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.visit_stmt_list_item(stmt);
+
+            stmt.visit_with(self);
+        }
+    }
+
+    fn visit_block_stmt(&mut self, block: &BlockStmt) {
+        let scope_span = self.scope_span;
+        let in_switch_case = self.in_switch_case;
+
+        self.scope_span = block.span;
+        self.in_switch_case = false;
+
+        block.visit_children_with(self);
+
+        self.scope_span = scope_span;
+        self.in_switch_case = in_switch_case;
+    }
+
+    fn visit_switch_case(&mut self, switch_case: &SwitchCase) {
+        let in_switch_case = self.in_switch_case;
+        self.in_switch_case = true;
+
+        switch_case.visit_children_with(self);
+
+        self.in_switch_case = in_switch_case;
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        self.with_fn_scope(|v| function.visit_children_with(v));
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        self.with_fn_scope(|v| arrow.visit_children_with(v));
+    }
+
+    fn visit_static_block(&mut self, static_block: &StaticBlock) {
+        self.with_fn_scope(|v| static_block.visit_children_with(v));
+    }
+
+    fn visit_for_stmt(&mut self, for_stmt: &ForStmt) {
+        self.with_loop(|v| for_stmt.visit_children_with(v));
+    }
+
+    fn visit_for_in_stmt(&mut self, for_in_stmt: &ForInStmt) {
+        self.with_loop(|v| for_in_stmt.visit_children_with(v));
+    }
+
+    fn visit_for_of_stmt(&mut self, for_of_stmt: &ForOfStmt) {
+        self.with_loop(|v| for_of_stmt.visit_children_with(v));
+    }
+
+    fn visit_while_stmt(&mut self, while_stmt: &WhileStmt) {
+        self.with_loop(|v| while_stmt.visit_children_with(v));
+    }
+
+    fn visit_do_while_stmt(&mut self, do_while_stmt: &DoWhileStmt) {
+        self.with_loop(|v| do_while_stmt.visit_children_with(v));
+    }
+
     fn visit_var_decl(&mut self, var_decl: &VarDecl) {
         if let VarDeclKind::Var = var_decl.kind {
-            self.emit_error(var_decl.span);
+            let fix = self
+                .is_fixable(var_decl)
+                .then(|| Span::new(var_decl.span.lo, var_decl.span.lo + BytePos(3)));
+
+            self.emit_error(var_decl.span, fix);
         }
 
         var_decl.visit_children_with(self);
@@ -0,0 +1,110 @@
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    Span, Spanned,
+};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    config::{LintRuleReaction, RuleConfig},
+    rule::{visitor_rule, Rule},
+};
+
+const ASSERTION_MESSAGE: &str = "Expected a `const` assertion instead of a literal type assertion";
+const ANNOTATION_MESSAGE: &str =
+    "Expected a `const` assertion instead of a literal type annotation";
+
+pub fn prefer_as_const(config: &RuleConfig<()>) -> Option<Box<dyn Rule>> {
+    let rule_reaction = config.get_rule_reaction();
+
+    match rule_reaction {
+        LintRuleReaction::Off => None,
+        _ => Some(visitor_rule(PreferAsConst::new(rule_reaction))),
+    }
+}
+
+#[derive(Debug, Default)]
+struct PreferAsConst {
+    expected_reaction: LintRuleReaction,
+}
+
+/// Returns `true` if `ty` is the literal type of `expr`, like `'a'` of `'a'`.
+fn is_same_literal(expr: &Expr, ty: &TsType) -> bool {
+    let (Expr::Lit(lit), TsType::TsLitType(TsLitType { lit: ty_lit, .. })) = (expr, ty) else {
+        return false;
+    };
+
+    match (lit, ty_lit) {
+        (Lit::Str(value), TsLit::Str(ty)) => value.value == ty.value,
+        (Lit::Num(value), TsLit::Number(ty)) => value.value == ty.value,
+        _ => false,
+    }
+}
+
+impl PreferAsConst {
+    fn new(expected_reaction: LintRuleReaction) -> Self {
+        Self { expected_reaction }
+    }
+
+    /// Type assertions are fixed by replacing the type with `const`.
+    fn emit_report(&self, span: Span, message: &str, fixable: bool) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, message),
+                _ => return,
+            };
+
+            if fixable {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Use 'const'",
+                    "const".into(),
+                    Applicability::MachineApplicable,
+                );
+            }
+
+            db.emit();
+        });
+    }
+
+    fn check_annotation(&self, type_ann: Option<&TsTypeAnn>, value: Option<&Expr>) {
+        if let (Some(type_ann), Some(value)) = (type_ann, value) {
+            if is_same_literal(value, &type_ann.type_ann) {
+                self.emit_report(type_ann.type_ann.span(), ANNOTATION_MESSAGE, false);
+            }
+        }
+    }
+}
+
+impl Visit for PreferAsConst {
+    fn visit_ts_as_expr(&mut self, as_expr: &TsAsExpr) {
+        if is_same_literal(&as_expr.expr, &as_expr.type_ann) {
+            self.emit_report(as_expr.type_ann.span(), ASSERTION_MESSAGE, true);
+        }
+
+        as_expr.visit_children_with(self);
+    }
+
+    fn visit_ts_type_assertion(&mut self, type_assertion: &TsTypeAssertion) {
+        if is_same_literal(&type_assertion.expr, &type_assertion.type_ann) {
+            self.emit_report(type_assertion.type_ann.span(), ASSERTION_MESSAGE, true);
+        }
+
+        type_assertion.visit_children_with(self);
+    }
+
+    fn visit_var_declarator(&mut self, declarator: &VarDeclarator) {
+        if let Pat::Ident(BindingIdent { type_ann, .. }) = &declarator.name {
+            self.check_annotation(type_ann.as_deref(), declarator.init.as_deref());
+        }
+
+        declarator.visit_children_with(self);
+    }
+
+    fn visit_class_prop(&mut self, class_prop: &ClassProp) {
+        self.check_annotation(class_prop.type_ann.as_deref(), class_prop.value.as_deref());
+
+        class_prop.visit_children_with(self);
+    }
+}
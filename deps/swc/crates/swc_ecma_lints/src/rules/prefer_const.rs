@@ -1,7 +1,11 @@
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
-use swc_common::{errors::HANDLER, Span};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    BytePos, Span,
+};
 use swc_ecma_ast::*;
+use swc_ecma_utils::find_pat_ids;
 use swc_ecma_visit::{Visit, VisitWith};
 
 use crate::{
@@ -52,10 +56,19 @@ struct VariableMeta {
     used_before_initialize: bool,
 }
 
+/// A `let` declaration, which can be fixed if all of its variables are
+/// reported.
+#[derive(Debug)]
+struct LetDecl {
+    keyword_span: Span,
+    ids: Vec<Id>,
+}
+
 #[derive(Debug, Default)]
 struct PreferConst {
     expected_reaction: LintRuleReaction,
     vars_meta: FxHashMap<Id, VariableMeta>,
+    let_decls: Vec<LetDecl>,
     scope_vars_idx: usize,
     block_depth: usize,
     cycle_head_depth: usize,
@@ -68,6 +81,7 @@ impl PreferConst {
         Self {
             expected_reaction,
             vars_meta: Default::default(),
+            let_decls: Default::default(),
             scope_vars_idx: 0,
             block_depth: 0,
             cycle_head_depth: 0,
@@ -76,17 +90,26 @@ impl PreferConst {
         }
     }
 
-    fn emit_report(&self, span: Span, var_name: &str) {
+    fn emit_report(&self, span: Span, var_name: &str, fix: Option<Span>) {
         let message = format!("'{var_name}' is never reassigned. Use 'const' insted");
 
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, &message).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, &message).emit();
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, &message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, &message),
+                _ => return,
+            };
+
+            if let Some(keyword_span) = fix {
+                db.span_suggestion_with_applicability(
+                    keyword_span,
+                    "Use 'const'",
+                    "const".into(),
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
 
@@ -187,7 +210,25 @@ impl PreferConst {
         }
     }
 
+    /// Variables which can be declared with `const` without other changes.
+    fn is_fixable(&self, id: &Id) -> bool {
+        self.vars_meta.get(id).is_some_and(|var_meta| {
+            (var_meta.initialized || var_meta.declared_into_cycle_head)
+                && !var_meta.postinitialized
+                && !var_meta.destructuring_assign
+        })
+    }
+
     fn emit_ordered(&self) {
+        // The fix of a declaration is attached to the report of its first
+        // variable.
+        let fixes = self
+            .let_decls
+            .iter()
+            .filter(|decl| decl.ids.iter().all(|id| self.is_fixable(id)))
+            .filter_map(|decl| Some((decl.ids.first()?, decl.keyword_span)))
+            .collect::<FxHashMap<_, _>>();
+
         let mut vars = self.vars_meta.iter().collect::<Vec<_>>();
 
         vars.sort_by_key(|(_, a)| a.order);
@@ -204,7 +245,7 @@ impl PreferConst {
                 || var_meta.destructuring_assign
                 || var_meta.declared_into_cycle_head
             {
-                self.emit_report(var_meta.span, &id.0);
+                self.emit_report(var_meta.span, &id.0, fixes.get(id).copied());
             }
         });
     }
@@ -227,7 +268,14 @@ impl Visit for PreferConst {
         if let VarDeclKind::Let = var_decl.kind {
             var_decl.decls.iter().for_each(|var_decl| {
                 self.collect_decl_pat(var_decl.init.is_some(), &var_decl.name);
-            })
+            });
+
+            if !var_decl.declare {
+                self.let_decls.push(LetDecl {
+                    keyword_span: Span::new(var_decl.span.lo, var_decl.span.lo + BytePos(3)),
+                    ids: find_pat_ids(&var_decl.decls),
+                });
+            }
         }
 
         var_decl.visit_children_with(self);
@@ -1,4 +1,12 @@
-use swc_common::{errors::HANDLER, Span, SyntaxContext};
+use std::{
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    BytePos, SourceMap, SourceMapper, Span, Spanned, SyntaxContext,
+};
 use swc_ecma_ast::*;
 use swc_ecma_utils::ExprExt;
 use swc_ecma_visit::{noop_visit_type, Visit, VisitWith};
@@ -15,6 +23,7 @@ const USE_LITERAL_MESSAGE: &str =
     r#""Use an object literal instead of `Object.assign`. eg: `{ foo: bar }`""#;
 
 pub fn prefer_object_spread(
+    source_map: &Arc<SourceMap>,
     config: &RuleConfig<()>,
     unresolved_ctxt: SyntaxContext,
     es_version: EsVersion,
@@ -28,16 +37,31 @@ pub fn prefer_object_spread(
     match rule_reaction {
         LintRuleReaction::Off => None,
         _ => Some(visitor_rule(PreferObjectSpread::new(
+            source_map.clone(),
             rule_reaction,
             unresolved_ctxt,
         ))),
     }
 }
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 struct PreferObjectSpread {
+    source_map: Arc<SourceMap>,
+
     expected_reaction: LintRuleReaction,
     unresolved_ctxt: SyntaxContext,
+    /// The start of the expression of an expression statement or the body of
+    /// an arrow function, where an object literal should be parenthesized.
+    paren_required_pos: Option<BytePos>,
+}
+
+impl Debug for PreferObjectSpread {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreferObjectSpread")
+            .field("expected_reaction", &self.expected_reaction)
+            .field("unresolved_ctxt", &self.unresolved_ctxt)
+            .finish()
+    }
 }
 
 #[derive(Debug)]
@@ -51,25 +75,108 @@ enum ArgType {
 }
 
 impl PreferObjectSpread {
-    fn new(expected_reaction: LintRuleReaction, unresolved_ctxt: SyntaxContext) -> Self {
+    fn new(
+        source_map: Arc<SourceMap>,
+        expected_reaction: LintRuleReaction,
+        unresolved_ctxt: SyntaxContext,
+    ) -> Self {
         Self {
+            source_map,
             expected_reaction,
             unresolved_ctxt,
+            paren_required_pos: None,
         }
     }
 
-    fn emit_report(&self, span: Span, message: &str) {
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, message).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, message).emit();
+    fn emit_report(&self, span: Span, message: &str, fix: Option<String>) {
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, message),
+                _ => return,
+            };
+
+            if let Some(fix) = fix {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Replace `Object.assign` with an object literal",
+                    fix,
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
 
+    fn snippet(&self, lo: BytePos, hi: BytePos) -> Option<String> {
+        self.source_map.span_to_snippet(Span::new(lo, hi)).ok()
+    }
+
+    /// Builds an object literal which is equivalent to `call_expr`.
+    ///
+    /// Returns [None] if there are comments between arguments, which would be
+    /// lost.
+    fn build_fix(&self, call_expr: &CallExpr) -> Option<String> {
+        let mut pos = call_expr.callee.span_hi();
+        let mut props = Vec::new();
+
+        for (idx, arg) in call_expr.args.iter().enumerate() {
+            let separator = self.snippet(pos, arg.span_lo())?;
+            if separator.trim() != if idx == 0 { "(" } else { "," } {
+                return None;
+            }
+            pos = arg.span_hi();
+
+            match &*arg.expr {
+                Expr::Object(obj) => {
+                    if obj.props.is_empty() {
+                        continue;
+                    }
+
+                    let src = self.snippet(obj.span.lo, obj.span.hi)?;
+                    let inner = src.strip_prefix('{')?.strip_suffix('}')?.trim();
+
+                    props.push(
+                        inner
+                            .strip_suffix(',')
+                            .unwrap_or(inner)
+                            .trim_end()
+                            .to_string(),
+                    );
+                }
+                expr => {
+                    let src = self.snippet(arg.span_lo(), arg.span_hi())?;
+
+                    if matches!(expr, Expr::Seq(..)) {
+                        props.push(format!("...({src})"));
+                    } else {
+                        props.push(format!("...{src}"));
+                    }
+                }
+            }
+        }
+
+        // A trailing comma is allowed.
+        let rest = self.snippet(pos, call_expr.span.hi)?;
+        let rest = rest.trim();
+        if rest.strip_prefix(',').unwrap_or(rest).trim_start() != ")" {
+            return None;
+        }
+
+        let object = if props.is_empty() {
+            "{}".to_string()
+        } else {
+            format!("{{ {} }}", props.join(", "))
+        };
+
+        if self.paren_required_pos == Some(call_expr.span.lo) {
+            Some(format!("({object})"))
+        } else {
+            Some(object)
+        }
+    }
+
     fn recognize_expr_arg(expr: &Expr) -> ArgType {
         match expr {
             Expr::Object(obj) => {
@@ -157,7 +264,11 @@ impl PreferObjectSpread {
             (1, ArgType::EmptyLiteralObject)
             | (1, ArgType::LiteralObjectWithFields)
             | (1, ArgType::LiteralObjectWithGetterOrSetter) => {
-                self.emit_report(call_expr.span, USE_LITERAL_MESSAGE);
+                self.emit_report(
+                    call_expr.span,
+                    USE_LITERAL_MESSAGE,
+                    self.build_fix(call_expr),
+                );
             }
             (_, ArgType::EmptyLiteralObject) | (_, ArgType::LiteralObjectWithFields) => {
                 let has_spread_or_getter_setter = call_expr.args[1..].iter().any(|prop| {
@@ -171,7 +282,11 @@ impl PreferObjectSpread {
                     return;
                 }
 
-                self.emit_report(call_expr.span, USE_SPREAD_MESSAGE);
+                self.emit_report(
+                    call_expr.span,
+                    USE_SPREAD_MESSAGE,
+                    self.build_fix(call_expr),
+                );
             }
             _ => {}
         }
@@ -186,4 +301,18 @@ impl Visit for PreferObjectSpread {
 
         call_expr.visit_children_with(self);
     }
+
+    fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
+        self.paren_required_pos = Some(expr_stmt.expr.span_lo());
+
+        expr_stmt.visit_children_with(self);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow_expr: &ArrowExpr) {
+        if let BlockStmtOrExpr::Expr(body) = &*arrow_expr.body {
+            self.paren_required_pos = Some(body.span_lo());
+        }
+
+        arrow_expr.visit_children_with(self);
+    }
 }
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use swc_common::{errors::HANDLER, Span};
+use swc_common::{
+    errors::{Applicability, HANDLER},
+    Span,
+};
 use swc_ecma_ast::*;
 use swc_ecma_visit::{noop_visit_type, Visit, VisitWith};
 
@@ -50,24 +53,108 @@ impl Quotes {
         }
     }
 
-    fn emit_report(&self, span: Span) {
+    fn emit_report(&self, span: Span, fix: Option<String>) {
         let message = match &self.prefer {
             QuotesType::Backtick => MUST_USE_BACKTICK_QUOTES_MESSAGE,
             QuotesType::Single => MUST_USE_SINGLE_QUOTES_MESSAGE,
             QuotesType::Double => MUST_USE_DOUBLE_QUOTES_MESSAGE,
         };
 
-        HANDLER.with(|handler| match self.expected_reaction {
-            LintRuleReaction::Error => {
-                handler.struct_span_err(span, message).emit();
-            }
-            LintRuleReaction::Warning => {
-                handler.struct_span_warn(span, message).emit();
+        HANDLER.with(|handler| {
+            let mut db = match self.expected_reaction {
+                LintRuleReaction::Error => handler.struct_span_err(span, message),
+                LintRuleReaction::Warning => handler.struct_span_warn(span, message),
+                _ => return,
+            };
+
+            if let Some(fix) = fix {
+                db.span_suggestion_with_applicability(
+                    span,
+                    "Change the quotes",
+                    fix,
+                    Applicability::MachineApplicable,
+                );
             }
-            _ => {}
+
+            db.emit();
         });
     }
 
+    /// Converts `raw` of a string literal to use the preferred quotes, keeping
+    /// escape sequences.
+    fn requote_str(&self, raw: &str) -> Option<String> {
+        let mut chars = raw.chars();
+        let old_quote = chars.next()?;
+        let new_quote = self.prefer.get_char();
+
+        let inner = chars.as_str().strip_suffix(old_quote)?;
+        let mut result = String::with_capacity(raw.len() + 2);
+        result.push(new_quote);
+
+        let mut chars = inner.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '\\' => {
+                    let escaped = chars.next()?;
+
+                    // Octal escapes are not allowed in template literals.
+                    if new_quote == '`'
+                        && (matches!(escaped, '1'..='9')
+                            || (escaped == '0' && chars.peek().is_some_and(char::is_ascii_digit)))
+                    {
+                        return None;
+                    }
+
+                    if escaped != old_quote || escaped == new_quote {
+                        result.push('\\');
+                    }
+                    result.push(escaped);
+                }
+                _ if ch == new_quote => {
+                    result.push('\\');
+                    result.push(ch);
+                }
+                '$' if new_quote == '`' && chars.peek() == Some(&'{') => {
+                    result.push_str("\\$");
+                }
+                _ => result.push(ch),
+            }
+        }
+
+        result.push(new_quote);
+
+        Some(result)
+    }
+
+    /// Converts a template literal without expressions to a string literal with
+    /// the preferred quotes.
+    fn tpl_to_str(&self, tpl: &Tpl) -> Option<String> {
+        let cooked = tpl.quasis.first()?.cooked.as_ref()?.as_str()?;
+        let quote = self.prefer.get_char();
+
+        let mut result = String::with_capacity(cooked.len() + 2);
+        result.push(quote);
+
+        for ch in cooked.chars() {
+            match ch {
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\u{2028}' => result.push_str("\\u2028"),
+                '\u{2029}' => result.push_str("\\u2029"),
+                _ if ch == quote => {
+                    result.push('\\');
+                    result.push(ch);
+                }
+                _ => result.push(ch),
+            }
+        }
+
+        result.push(quote);
+
+        Some(result)
+    }
+
     fn is_mirroring_escape(&self, value: &str) -> bool {
         let quote = self.prefer.get_char();
 
@@ -80,25 +167,18 @@ impl Quotes {
         false
     }
 
-    fn check_str(&self, is_method_key_check: bool, lit_str: &Str) {
+    /// `fixable` is `false` if changing the quotes may change the semantics,
+    /// like directives.
+    fn check_str(&self, is_method_key_check: bool, lit_str: &Str, fixable: bool) {
         let found_quote_type = resolve_string_quote_type(lit_str).unwrap();
 
         let Str { span, value, .. } = lit_str;
 
         match (&self.prefer, &found_quote_type) {
-            (QuotesType::Double, QuotesType::Single) => {
-                if self.avoid_escape && self.is_mirroring_escape(&value.to_string_lossy()) {
-                    return;
-                }
-
-                self.emit_report(*span);
-            }
-            (QuotesType::Single, QuotesType::Double) => {
+            (QuotesType::Double, QuotesType::Single) | (QuotesType::Single, QuotesType::Double) => {
                 if self.avoid_escape && self.is_mirroring_escape(&value.to_string_lossy()) {
                     return;
                 }
-
-                self.emit_report(*span);
             }
             (QuotesType::Backtick, _) => {
                 if is_method_key_check {
@@ -108,11 +188,17 @@ impl Quotes {
                 if self.avoid_escape && self.is_mirroring_escape(&value.to_string_lossy()) {
                     return;
                 }
-
-                self.emit_report(*span);
             }
-            _ => {}
+            _ => return,
         }
+
+        let fix = if fixable {
+            lit_str.raw.as_deref().and_then(|raw| self.requote_str(raw))
+        } else {
+            None
+        };
+
+        self.emit_report(*span, fix);
     }
 
     fn check_tpl_str(&self, tpl_str: &Tpl) {
@@ -130,7 +216,7 @@ impl Quotes {
             return;
         }
 
-        self.emit_report(*span);
+        self.emit_report(*span, self.tpl_to_str(tpl_str));
     }
 }
 
@@ -143,7 +229,7 @@ impl Visit for Quotes {
                 self.check_tpl_str(tpl_str);
             }
             Expr::Lit(Lit::Str(lit_str)) => {
-                self.check_str(false, lit_str);
+                self.check_str(false, lit_str, true);
             }
             _ => {}
         }
@@ -152,12 +238,17 @@ impl Visit for Quotes {
     }
 
     fn visit_expr_stmt(&mut self, expr_stmt: &ExprStmt) {
-        if let Expr::Lit(Lit::Str(Str { value, .. })) = expr_stmt.expr.as_ref() {
-            let value = value.to_string_lossy();
+        if let Expr::Lit(Lit::Str(lit_str)) = expr_stmt.expr.as_ref() {
+            let value = lit_str.value.to_string_lossy();
 
             if DIRECTIVES.contains(&&*value) {
                 return;
             }
+
+            // A template literal is not a directive.
+            self.check_str(false, lit_str, !matches!(self.prefer, QuotesType::Backtick));
+
+            return;
         }
 
         expr_stmt.visit_children_with(self);
@@ -165,7 +256,7 @@ impl Visit for Quotes {
 
     fn visit_class_method(&mut self, class_method: &ClassMethod) {
         if let Some(lit_str) = class_method.key.as_str() {
-            self.check_str(true, lit_str);
+            self.check_str(true, lit_str, true);
         }
 
         class_method.visit_children_with(self);
@@ -0,0 +1,421 @@
+//! Suppression of lint diagnostics with comments.
+//!
+//! - `// swc-lint-disable-next-line no-var, eqeqeq` suppresses diagnostics
+//!   which start on the next line.
+//! - `/* swc-lint-disable no-var */` suppresses diagnostics until
+//!   `/* swc-lint-enable no-var */`, or until the end of the file if the rule is
+//!   not enabled again. `/* swc-lint-enable no-var */` after
+//!   `/* swc-lint-disable */` enables only `no-var` again.
+//! - `/* swc-lint-disable-file no-var */` suppresses diagnostics of the whole
+//!   file, wherever it's placed.
+//!
+//! Directives without rule names suppress diagnostics of all rules, and text
+//! after `--` is a description. Rules are identified by the codes of their
+//! diagnostics, which are the names of rules in [LintConfig], like `no-var`.
+//! Diagnostics without codes are never suppressed.
+//!
+//! [LintConfig]: crate::config::LintConfig
+
+use std::{fmt, mem::take, sync::Arc};
+
+use swc_common::{
+    comments::Comment,
+    errors::{Diagnostic, DiagnosticBuilder, DiagnosticId, Handler, Level, HANDLER},
+    BytePos, SourceMap, Span,
+};
+use swc_ecma_ast::{Module, Script};
+
+use crate::rule::{Capturing, Rule};
+
+/// The code of diagnostics of unused directives.
+pub const UNUSED_SUPPRESSION: &str = "unused-suppression";
+
+const DISABLE_NEXT_LINE: &str = "swc-lint-disable-next-line";
+const DISABLE_FILE: &str = "swc-lint-disable-file";
+const DISABLE: &str = "swc-lint-disable";
+const ENABLE: &str = "swc-lint-enable";
+
+#[derive(Debug, Clone, Copy)]
+enum Scope {
+    /// A 1-based line number.
+    Line(usize),
+    Range(BytePos, BytePos),
+    File,
+}
+
+#[derive(Debug)]
+struct Suppression {
+    /// [None] for all rules.
+    rule: Option<String>,
+    scope: Scope,
+    /// Rules enabled again by `swc-lint-enable` within the range of a
+    /// suppression of all rules, and the positions they are enabled from.
+    enabled: Vec<(String, BytePos)>,
+    /// The span of the directive comment.
+    span: Span,
+    used: bool,
+}
+
+impl Suppression {
+    fn matches(&self, rule: &str, pos: BytePos, line: impl FnOnce() -> usize) -> bool {
+        if self.rule.as_deref().is_some_and(|r| r != rule) {
+            return false;
+        }
+        if self
+            .enabled
+            .iter()
+            .any(|(enabled, from)| enabled == rule && *from <= pos)
+        {
+            return false;
+        }
+
+        match self.scope {
+            Scope::Line(l) => l == line(),
+            Scope::Range(start, end) => start <= pos && pos < end,
+            Scope::File => true,
+        }
+    }
+}
+
+/// Parses `text` of a comment as `(directive, rules)`.
+fn parse_directive(text: &str) -> Option<(&'static str, Vec<&str>)> {
+    let text = text.trim_start_matches(['*', ' ', '\t', '\r', '\n']);
+
+    // Longer directives first, as they start with shorter ones.
+    let (directive, rest) = [DISABLE_NEXT_LINE, DISABLE_FILE, DISABLE, ENABLE]
+        .into_iter()
+        .find_map(|directive| {
+            let rest = text.strip_prefix(directive)?;
+
+            (rest.is_empty() || rest.starts_with(char::is_whitespace)).then_some((directive, rest))
+        })?;
+
+    let rules = rest
+        .split_once("--")
+        .map_or(rest, |(rules, _description)| rules)
+        // `*` of continuation lines of block comments.
+        .split(|c: char| c == ',' || c == '*' || c.is_whitespace())
+        .filter(|rule| !rule.is_empty())
+        .collect();
+
+    Some((directive, rules))
+}
+
+/// Suppression directives of a file.
+pub struct Suppressions {
+    source_map: Arc<SourceMap>,
+    suppressions: Vec<Suppression>,
+    report_unused: bool,
+}
+
+impl fmt::Debug for Suppressions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Suppressions")
+            .field("suppressions", &self.suppressions)
+            .field("report_unused", &self.report_unused)
+            .finish()
+    }
+}
+
+impl Suppressions {
+    /// Collects directives from `comments` of a file.
+    pub fn new<'a>(
+        source_map: Arc<SourceMap>,
+        comments: impl IntoIterator<Item = &'a Comment>,
+    ) -> Self {
+        let mut comments = comments.into_iter().collect::<Vec<_>>();
+        comments.sort_by_key(|comment| comment.span.lo);
+
+        let mut suppressions = Vec::new();
+        // Indices of suppressions of `swc-lint-disable` which are not enabled yet.
+        let mut disabled = Vec::<usize>::new();
+
+        for comment in comments {
+            let Some((directive, rules)) = parse_directive(&comment.text) else {
+                continue;
+            };
+
+            let scope = match directive {
+                DISABLE_NEXT_LINE => {
+                    Scope::Line(source_map.lookup_char_pos(comment.span.hi).line + 1)
+                }
+                DISABLE_FILE => Scope::File,
+                DISABLE => Scope::Range(comment.span.hi, BytePos(u32::MAX)),
+                _ => {
+                    disabled.retain(|&idx| {
+                        let suppression: &mut Suppression = &mut suppressions[idx];
+
+                        // A suppression of all rules keeps suppressing the rules which
+                        // are not enabled again.
+                        if suppression.rule.is_none() && !rules.is_empty() {
+                            suppression.enabled.extend(
+                                rules.iter().map(|rule| (rule.to_string(), comment.span.lo)),
+                            );
+                            return true;
+                        }
+
+                        let is_enabled = rules.is_empty()
+                            || suppression
+                                .rule
+                                .as_deref()
+                                .is_some_and(|rule| rules.contains(&rule));
+
+                        if is_enabled {
+                            if let Scope::Range(_, end) = &mut suppression.scope {
+                                *end = comment.span.lo;
+                            }
+                        }
+
+                        !is_enabled
+                    });
+                    continue;
+                }
+            };
+
+            let rules = if rules.is_empty() {
+                vec![None]
+            } else {
+                rules
+                    .into_iter()
+                    .map(|rule| Some(rule.to_string()))
+                    .collect()
+            };
+
+            for rule in rules {
+                if directive == DISABLE {
+                    disabled.push(suppressions.len());
+                }
+
+                suppressions.push(Suppression {
+                    rule,
+                    scope,
+                    enabled: Vec::new(),
+                    span: comment.span,
+                    used: false,
+                });
+            }
+        }
+
+        Self {
+            source_map,
+            suppressions,
+            report_unused: false,
+        }
+    }
+
+    /// Reports directives which suppress no diagnostic as warnings.
+    pub fn report_unused(mut self, report_unused: bool) -> Self {
+        self.report_unused = report_unused;
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.suppressions.is_empty()
+    }
+
+    /// Returns `true` if `diagnostic` is suppressed, and marks the directives
+    /// which suppress it as used.
+    pub fn is_suppressed(&mut self, diagnostic: &Diagnostic) -> bool {
+        let Some(DiagnosticId::Lint(rule)) = &diagnostic.code else {
+            return false;
+        };
+        let Some(span) = diagnostic.span.primary_span() else {
+            return false;
+        };
+        if span.is_dummy() {
+            return false;
+        }
+
+        let source_map = &self.source_map;
+        let mut line = None;
+        let mut is_suppressed = false;
+
+        for suppression in &mut self.suppressions {
+            if suppression.matches(rule, span.lo, || {
+                *line.get_or_insert_with(|| source_map.lookup_char_pos(span.lo).line)
+            }) {
+                suppression.used = true;
+                is_suppressed = true;
+            }
+        }
+
+        is_suppressed
+    }
+
+    /// Returns warnings of directives which have not suppressed any diagnostic.
+    pub fn unused(&self) -> Vec<Diagnostic> {
+        self.suppressions
+            .iter()
+            .filter(|suppression| !suppression.used)
+            .map(|suppression| {
+                let message = match &suppression.rule {
+                    Some(rule) => format!(
+                        "Unused swc-lint-disable directive (no problems were reported from \
+                         '{rule}')"
+                    ),
+                    None => "Unused swc-lint-disable directive (no problems were reported)".into(),
+                };
+
+                let mut diagnostic = Diagnostic::new(Level::Warning, &message);
+                diagnostic
+                    .set_span(suppression.span)
+                    .code(DiagnosticId::Lint(UNUSED_SUPPRESSION.into()));
+                diagnostic
+            })
+            .collect()
+    }
+}
+
+/// Drops diagnostics of `rule` which are suppressed by `suppressions`.
+pub fn suppressed_rule<R: Rule>(rule: R, suppressions: Suppressions) -> impl Rule {
+    SuppressedRule { rule, suppressions }
+}
+
+#[derive(Debug)]
+struct SuppressedRule<R: Rule> {
+    rule: R,
+    suppressions: Suppressions,
+}
+
+impl<R: Rule> SuppressedRule<R> {
+    fn lint(&mut self, op: impl FnOnce(&mut R)) {
+        if self.suppressions.is_empty() {
+            op(&mut self.rule);
+            return;
+        }
+
+        let capturing = Capturing::default();
+
+        HANDLER.set(
+            &Handler::with_emitter(true, false, Box::new(capturing.clone())),
+            || op(&mut self.rule),
+        );
+
+        let mut errors = take(&mut *capturing.errors.lock());
+        errors.retain(|error| !self.suppressions.is_suppressed(error));
+
+        if self.suppressions.report_unused {
+            errors.extend(self.suppressions.unused());
+            errors.sort_by_key(|error| error.span.primary_span());
+        }
+
+        HANDLER.with(|handler| {
+            for error in errors {
+                DiagnosticBuilder::new_diagnostic(handler, error).emit();
+            }
+        });
+    }
+}
+
+impl<R: Rule> Rule for SuppressedRule<R> {
+    fn lint_module(&mut self, program: &Module) {
+        self.lint(|rule| rule.lint_module(program));
+    }
+
+    fn lint_script(&mut self, program: &Script) {
+        self.lint(|rule| rule.lint_script(program));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use swc_common::{
+        comments::{Comment, CommentKind},
+        errors::{Diagnostic, DiagnosticId, Level},
+        BytePos, FileName, SourceMap, Span,
+    };
+
+    use super::{parse_directive, Suppressions, DISABLE, DISABLE_FILE, DISABLE_NEXT_LINE, ENABLE};
+
+    #[test]
+    fn parses_directives() {
+        assert_eq!(
+            parse_directive(" swc-lint-disable-next-line no-var, eqeqeq"),
+            Some((DISABLE_NEXT_LINE, vec!["no-var", "eqeqeq"]))
+        );
+        assert_eq!(
+            parse_directive(" swc-lint-disable-file no-var "),
+            Some((DISABLE_FILE, vec!["no-var"]))
+        );
+        assert_eq!(
+            parse_directive(" swc-lint-disable no-var,eqeqeq "),
+            Some((DISABLE, vec!["no-var", "eqeqeq"]))
+        );
+        assert_eq!(
+            parse_directive(" swc-lint-enable no-var "),
+            Some((ENABLE, vec!["no-var"]))
+        );
+    }
+
+    #[test]
+    fn parses_directives_without_rules() {
+        assert_eq!(parse_directive("swc-lint-disable"), Some((DISABLE, vec![])));
+        assert_eq!(
+            parse_directive(" swc-lint-disable-next-line "),
+            Some((DISABLE_NEXT_LINE, vec![]))
+        );
+    }
+
+    #[test]
+    fn ignores_descriptions() {
+        assert_eq!(
+            parse_directive(" swc-lint-disable-next-line no-var -- legacy code, see #1"),
+            Some((DISABLE_NEXT_LINE, vec!["no-var"]))
+        );
+        assert_eq!(
+            parse_directive(" swc-lint-disable -- generated"),
+            Some((DISABLE, vec![]))
+        );
+    }
+
+    #[test]
+    fn parses_block_comments() {
+        assert_eq!(
+            parse_directive("*\n * swc-lint-disable-file\n * no-var\n "),
+            Some((DISABLE_FILE, vec!["no-var"]))
+        );
+    }
+
+    #[test]
+    fn ignores_other_comments() {
+        assert_eq!(parse_directive(" swc-lint-disabled no-var"), None);
+        assert_eq!(parse_directive(" swc-lint-disable-next-line-foo"), None);
+        assert_eq!(parse_directive(" eslint-disable no-var"), None);
+        assert_eq!(parse_directive(" see swc-lint-disable"), None);
+    }
+
+    #[test]
+    fn enables_rules_within_suppressions_of_all_rules() {
+        let src = "/* swc-lint-disable */\nvar a = 1;\n/* swc-lint-enable no-var */\nvar b = a == \
+                   1;\n";
+        let cm = Arc::new(SourceMap::default());
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+
+        let span_of = |text: &str| {
+            let lo = fm.start_pos + BytePos(src.find(text).unwrap() as u32);
+            Span::new(lo, lo + BytePos(text.len() as u32))
+        };
+        let comments =
+            ["/* swc-lint-disable */", "/* swc-lint-enable no-var */"].map(|text| Comment {
+                kind: CommentKind::Block,
+                span: span_of(text),
+                text: text[2..text.len() - 2].into(),
+            });
+        let diagnostic = |rule: &str, text: &str| {
+            let mut diagnostic = Diagnostic::new(Level::Error, rule);
+            diagnostic
+                .set_span(span_of(text))
+                .code(DiagnosticId::Lint(rule.into()));
+            diagnostic
+        };
+
+        let mut suppressions = Suppressions::new(cm.clone(), &comments);
+
+        assert!(suppressions.is_suppressed(&diagnostic("no-var", "var a")));
+        assert!(!suppressions.is_suppressed(&diagnostic("no-var", "var b")));
+        assert!(suppressions.is_suppressed(&diagnostic("eqeqeq", "a == 1")));
+        assert!(suppressions.unused().is_empty());
+    }
+}
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 
 pub mod resolve;
 pub mod resolvers;
+pub mod trace;
+#[cfg(feature = "tsc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tsc")))]
+pub mod tsconfig;
 
 /// List of built in packages for latest stable node with LTS (node@16).
 ///
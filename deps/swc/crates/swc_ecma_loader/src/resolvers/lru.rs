@@ -5,7 +5,10 @@ use lru::LruCache;
 use parking_lot::Mutex;
 use swc_common::FileName;
 
-use crate::resolve::{Resolution, Resolve};
+use crate::{
+    resolve::{Resolution, Resolve},
+    trace::{self, TraceEvent},
+};
 
 #[derive(Debug)]
 pub struct CachingResolver<R>
@@ -48,6 +51,9 @@ where
             let mut lock = self.cache.lock();
             //
             if let Some(v) = lock.get(&(base.clone(), src.to_string())) {
+                trace::record(|| TraceEvent::CacheHit {
+                    resolution: v.clone(),
+                });
                 return Ok(v.clone());
             }
         }
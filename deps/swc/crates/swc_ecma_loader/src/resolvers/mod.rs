@@ -4,6 +4,11 @@ pub mod lru;
 #[cfg(feature = "node")]
 #[cfg_attr(docsrs, doc(cfg(feature = "node")))]
 pub mod node;
+#[cfg(feature = "persistent-cache")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistent-cache")))]
+pub mod persistent;
+#[cfg(any(feature = "node", feature = "tsc"))]
+pub(crate) mod subpath;
 #[cfg(feature = "tsc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "tsc")))]
 pub mod tsc;
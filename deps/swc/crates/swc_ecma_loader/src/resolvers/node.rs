@@ -4,9 +4,10 @@
 
 use std::{
     env::current_dir,
-    fs::File,
+    fs::{self, File},
     io::BufReader,
     path::{Component, Path, PathBuf},
+    time::SystemTime,
 };
 
 use anyhow::{bail, Context, Error};
@@ -24,6 +25,8 @@ use tracing::{debug, trace, Level};
 
 use crate::{
     resolve::{Resolution, Resolve},
+    resolvers::subpath::{match_subpath, PackageTarget},
+    trace::{self, TraceEvent},
     TargetEnv, NODE_BUILTINS,
 };
 
@@ -38,6 +41,21 @@ static PACKAGE: &str = "package.json";
 static BROWSER_CACHE: Lazy<DashMap<PathBuf, BrowserCache, FxBuildHasher>> =
     Lazy::new(Default::default);
 
+/// Map of cached `imports` fields from deserialized package.json, keyed by
+/// the directory containing the package.json file.
+static IMPORTS_CACHE: Lazy<DashMap<PathBuf, CachedImports, FxBuildHasher>> =
+    Lazy::new(Default::default);
+
+type SubpathImports = Vec<(String, PackageTarget)>;
+
+struct CachedImports {
+    /// The modification time of the package.json file, so edits are picked up
+    /// by long-lived processes like watch mode.
+    modified: Option<SystemTime>,
+    /// [None] if the file has no valid `imports` field.
+    imports: Option<SubpathImports>,
+}
+
 #[derive(Debug, Default)]
 struct BrowserCache {
     rewrites: FxHashMap<PathBuf, PathBuf>,
@@ -52,7 +70,7 @@ fn find_package_root(path: &Path) -> Option<PathBuf> {
     let mut parent = path.parent();
     while let Some(p) = parent {
         let pkg = p.join(PACKAGE);
-        if pkg.is_file() {
+        if is_file(&pkg) {
             return Some(p.to_path_buf());
         }
         parent = p.parent();
@@ -60,6 +78,61 @@ fn find_package_root(path: &Path) -> Option<PathBuf> {
     None
 }
 
+/// Returns `true` if `module_specifier` is a subpath import (`#foo`) defined
+/// in `imports` of the nearest `package.json` of `base`.
+pub fn is_subpath_import_defined(base: &Path, module_specifier: &str) -> bool {
+    if !module_specifier.starts_with('#') {
+        return false;
+    }
+
+    let Some(pkg_dir) = find_package_root(base) else {
+        return false;
+    };
+
+    let pkg_path = pkg_dir.join(PACKAGE);
+    let modified = fs::metadata(&pkg_path)
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    // This is recorded for cached imports too, so the trace does not depend on
+    // whether the package.json was read before.
+    let is_defined = |cached: &CachedImports| match &cached.imports {
+        Some(imports) => {
+            let is_defined = match_subpath(imports, module_specifier).is_some();
+            if is_defined {
+                trace::record(|| TraceEvent::PackageField {
+                    package_json: pkg_dir.join(PACKAGE),
+                    field: "imports",
+                    value: module_specifier.to_string(),
+                });
+            }
+            is_defined
+        }
+        None => false,
+    };
+
+    if let Some(cached) = IMPORTS_CACHE.get(&pkg_dir) {
+        if cached.modified == modified {
+            return is_defined(&cached);
+        }
+    }
+
+    let pkg = File::open(pkg_path)
+        .ok()
+        .and_then(|file| serde_json::from_reader::<_, PackageImports>(BufReader::new(file)).ok());
+    let cached = CachedImports {
+        modified,
+        imports: match pkg.and_then(|pkg| pkg.imports) {
+            Some(PackageTarget::Conditional(imports)) => Some(imports),
+            _ => None,
+        },
+    };
+
+    let is_defined = is_defined(&cached);
+    IMPORTS_CACHE.insert(pkg_dir, cached);
+    is_defined
+}
+
 pub fn to_absolute_path(path: &Path) -> Result<PathBuf, Error> {
     let absolute_path = if path.is_absolute() {
         path.to_path_buf()
@@ -71,6 +144,41 @@ pub fn to_absolute_path(path: &Path) -> Result<PathBuf, Error> {
     Ok(absolute_path)
 }
 
+/// Records the use of an entry of [BROWSER_CACHE], so resolutions served from
+/// it have the same dependencies as the one which filled it.
+///
+/// `target` is checked again, as it was checked while filling the cache.
+fn record_browser_field(pkg_dir: &Path, source: &str, target: Option<&Path>) {
+    trace::record(|| TraceEvent::PackageField {
+        package_json: pkg_dir.join(PACKAGE),
+        field: "browser",
+        value: source.to_string(),
+    });
+    if let Some(target) = target {
+        is_file(target);
+    }
+}
+
+/// [Path::is_file] which records the result to the resolution trace.
+fn is_file(path: &Path) -> bool {
+    let exists = path.is_file();
+    trace::record(|| TraceEvent::Candidate {
+        path: path.to_path_buf(),
+        exists,
+    });
+    exists
+}
+
+/// [Path::is_dir] which records the result to the resolution trace.
+fn is_dir(path: &Path) -> bool {
+    let exists = path.is_dir();
+    trace::record(|| TraceEvent::Candidate {
+        path: path.to_path_buf(),
+        exists,
+    });
+    exists
+}
+
 pub(crate) fn is_core_module(s: &str) -> bool {
     NODE_BUILTINS.contains(&s)
 }
@@ -85,6 +193,18 @@ struct PackageJson {
     module: Option<String>,
 }
 
+/// `imports` field of `package.json`.
+///
+/// See https://nodejs.org/api/packages.html#subpath-imports
+#[derive(Deserialize)]
+struct PackageImports {
+    #[serde(default)]
+    imports: Option<PackageTarget>,
+    /// Used to tell whether an importer is an ES module.
+    #[serde(default, rename = "type")]
+    module_type: Option<String>,
+}
+
 #[derive(Deserialize)]
 #[serde(untagged)]
 enum Browser {
@@ -174,20 +294,20 @@ impl NodeModulesResolver {
 
         let try_exact = path.extension().is_some();
         if try_exact {
-            if path.is_file() {
+            if is_file(path) {
                 return Ok(Some(path.to_path_buf()));
             }
         } else {
             // We try `.js` first.
             let mut path = path.to_path_buf();
             path.set_extension("js");
-            if path.is_file() {
+            if is_file(&path) {
                 return Ok(Some(path));
             }
         }
 
         // Try exact file after checking .js, for performance
-        if !try_exact && path.is_file() {
+        if !try_exact && is_file(path) {
             return Ok(Some(path.to_path_buf()));
         }
 
@@ -196,7 +316,7 @@ impl NodeModulesResolver {
             let name = name.to_string_lossy();
             for ext in EXTENSIONS {
                 ext_path.set_file_name(format!("{name}.{ext}"));
-                if ext_path.is_file() {
+                if is_file(&ext_path) {
                     return Ok(Some(ext_path));
                 }
             }
@@ -220,7 +340,7 @@ impl NodeModulesResolver {
                 for ext in extensions {
                     ext_path.set_extension(ext);
 
-                    if ext_path.is_file() {
+                    if is_file(&ext_path) {
                         return Ok(Some(ext_path));
                     }
                 }
@@ -257,7 +377,7 @@ impl NodeModulesResolver {
         }
 
         let pkg_path = path.join(PACKAGE);
-        if allow_package_entry && pkg_path.is_file() {
+        if allow_package_entry && is_file(&pkg_path) {
             if let Some(main) = self.resolve_package_entry(path, &pkg_path)? {
                 return Ok(Some(main));
             }
@@ -266,7 +386,7 @@ impl NodeModulesResolver {
         // Try to resolve to an index file.
         for ext in EXTENSIONS {
             let ext_path = path.join(format!("index.{ext}"));
-            if ext_path.is_file() {
+            if is_file(&ext_path) {
                 return Ok(Some(ext_path));
             }
         }
@@ -301,13 +421,17 @@ impl NodeModulesResolver {
 
         let main_fields = match self.target_env {
             TargetEnv::Node => {
-                vec![pkg.module.as_ref(), pkg.main.as_ref()]
+                vec![("module", pkg.module.as_ref()), ("main", pkg.main.as_ref())]
             }
             TargetEnv::Browser => {
                 if let Some(browser) = &pkg.browser {
                     match browser {
                         Browser::Str(path) => {
-                            vec![Some(path), pkg.module.as_ref(), pkg.main.as_ref()]
+                            vec![
+                                ("browser", Some(path)),
+                                ("module", pkg.module.as_ref()),
+                                ("main", pkg.main.as_ref()),
+                            ]
                         }
                         Browser::Obj(map) => {
                             let mut bucket = BrowserCache::default();
@@ -367,16 +491,22 @@ impl NodeModulesResolver {
 
                             BROWSER_CACHE.insert(pkg_dir.to_path_buf(), bucket);
 
-                            vec![pkg.module.as_ref(), pkg.main.as_ref()]
+                            vec![("module", pkg.module.as_ref()), ("main", pkg.main.as_ref())]
                         }
                     }
                 } else {
-                    vec![pkg.module.as_ref(), pkg.main.as_ref()]
+                    vec![("module", pkg.module.as_ref()), ("main", pkg.main.as_ref())]
                 }
             }
         };
 
-        if let Some(Some(target)) = main_fields.iter().find(|x| x.is_some()) {
+        if let Some((field, Some(target))) = main_fields.iter().find(|(_, x)| x.is_some()) {
+            trace::record(|| TraceEvent::PackageField {
+                package_json: pkg_path.to_path_buf(),
+                field,
+                value: target.to_string(),
+            });
+
             let path = pkg_dir.join(target);
             return self
                 .resolve_as_file(&path)
@@ -400,7 +530,7 @@ impl NodeModulesResolver {
         let mut path = Some(&*absolute_path);
         while let Some(dir) = path {
             let node_modules = dir.join("node_modules");
-            if node_modules.is_dir() {
+            if is_dir(&node_modules) {
                 let path = node_modules.join(target);
                 if let Some(result) = self
                     .resolve_as_file(&path)
@@ -417,6 +547,96 @@ impl NodeModulesResolver {
         Ok(None)
     }
 
+    /// Resolve a subpath import (`#foo`) using the `imports` field of the
+    /// nearest package.json.
+    ///
+    /// See https://nodejs.org/api/packages.html#subpath-imports
+    fn resolve_subpath_import(
+        &self,
+        base: &Path,
+        module_specifier: &str,
+    ) -> Result<FileName, Error> {
+        let pkg_dir = find_package_root(base)
+            .with_context(|| format!("failed to find package.json for `{module_specifier}`"))?;
+        let pkg_path = pkg_dir.join(PACKAGE);
+
+        let file = File::open(&pkg_path)?;
+        let reader = BufReader::new(file);
+        let pkg: PackageImports = serde_json::from_reader(reader)
+            .context(format!("failed to deserialize {}", pkg_path.display()))?;
+
+        let Some(PackageTarget::Conditional(imports)) = pkg.imports else {
+            bail!(
+                "`{module_specifier}` is a subpath import but {} has no valid `imports` field",
+                pkg_path.display()
+            )
+        };
+
+        let (target, subpath) = match_subpath(&imports, module_specifier).with_context(|| {
+            format!(
+                "`{module_specifier}` is not defined in `imports` of {}",
+                pkg_path.display()
+            )
+        })?;
+
+        // Like Node.js, the importer is an ES module if it's `.mjs` or it's not
+        // `.cjs` and the package is `"type": "module"`.
+        let is_esm = match base.extension().and_then(|ext| ext.to_str()) {
+            Some("mjs" | "mts") => true,
+            Some("cjs" | "cts") => false,
+            _ => pkg.module_type.as_deref() == Some("module"),
+        };
+        let conditions = [
+            match self.target_env {
+                TargetEnv::Node => "node",
+                TargetEnv::Browser => "browser",
+            },
+            if is_esm { "import" } else { "require" },
+            "default",
+        ];
+
+        let mut errors = Vec::new();
+        for target in target.candidates(&conditions) {
+            let target = target.replace('*', subpath);
+
+            trace::record(|| TraceEvent::PackageField {
+                package_json: pkg_path.clone(),
+                field: "imports",
+                value: target.clone(),
+            });
+
+            let res = if target.starts_with("./") {
+                let path = pkg_dir.join(&target).clean();
+                self.resolve_as_file(&path)
+                    .or_else(|_| self.resolve_as_directory(&path, false))
+                    .and_then(|p| self.wrap(p))
+            } else if target.starts_with('#') || Path::new(&target).is_absolute() {
+                Err(anyhow::anyhow!(
+                    "`{target}` is not a valid target of `imports.{module_specifier}`"
+                ))
+            } else {
+                // Targets without `./` are package specifiers.
+                self.resolve_filename(&FileName::Real(base.to_path_buf()), &target)
+            };
+
+            match res {
+                Ok(v) => return Ok(v),
+                Err(err) => {
+                    trace::record(|| TraceEvent::Rejected {
+                        candidate: target,
+                        reason: format!("{err:#}"),
+                    });
+                    errors.push(err)
+                }
+            }
+        }
+
+        bail!(
+            "`{module_specifier}` matched `imports` of {} but failed to resolve:\n{errors:?}",
+            pkg_path.display()
+        )
+    }
+
     fn resolve_filename(&self, base: &FileName, module_specifier: &str) -> Result<FileName, Error> {
         #[cfg(debug_assertions)]
         debug!(
@@ -441,7 +661,7 @@ impl NodeModulesResolver {
             _ => bail!("node-resolver supports only files"),
         };
 
-        let base_dir = if base.is_file() {
+        let base_dir = if is_file(base) {
             let cwd = &Path::new(".");
             base.parent().unwrap_or(cwd)
         } else {
@@ -455,9 +675,11 @@ impl NodeModulesResolver {
                 if let Some(item) = BROWSER_CACHE.get(&pkg_base) {
                     let value = item.value();
                     if value.module_ignores.contains(module_specifier) {
+                        record_browser_field(&pkg_base, module_specifier, None);
                         return Ok(FileName::Custom(module_specifier.into()));
                     }
                     if let Some(rewrite) = value.module_rewrites.get(module_specifier) {
+                        record_browser_field(&pkg_base, module_specifier, Some(rewrite));
                         return self.wrap(Some(rewrite.to_path_buf()));
                     }
                 }
@@ -475,6 +697,10 @@ impl NodeModulesResolver {
             }
         }
 
+        if module_specifier.starts_with('#') {
+            return self.resolve_subpath_import(base, module_specifier);
+        }
+
         // Aliases allow browser shims to be renamed so we can
         // map `stream` to `stream-browserify` for example
         let target = if let Some(alias) = self.alias.get(module_specifier) {
@@ -529,10 +755,13 @@ impl NodeModulesResolver {
                         if let Some(item) = BROWSER_CACHE.get(&pkg_base) {
                             let value = item.value();
                             let path = to_absolute_path(path).unwrap();
+                            let source = path.display().to_string();
                             if value.ignores.contains(&path) {
-                                return Ok(FileName::Custom(path.display().to_string()));
+                                record_browser_field(&pkg_base, &source, None);
+                                return Ok(FileName::Custom(source));
                             }
                             if let Some(rewrite) = value.rewrites.get(&path) {
+                                record_browser_field(&pkg_base, &source, Some(rewrite));
                                 return self.wrap(Some(rewrite.to_path_buf()));
                             }
                         }
@@ -548,10 +777,157 @@ impl NodeModulesResolver {
 
 impl Resolve for NodeModulesResolver {
     fn resolve(&self, base: &FileName, module_specifier: &str) -> Result<Resolution, Error> {
-        self.resolve_filename(base, module_specifier)
-            .map(|filename| Resolution {
-                filename,
-                slug: None,
-            })
+        trace::scope("node", base, module_specifier, || {
+            self.resolve_filename(base, module_specifier)
+                .map(|filename| Resolution {
+                    filename,
+                    slug: None,
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Resolves `#dep` from `importer` in a package with a conditional
+    /// `imports.#dep`.
+    fn resolve_conditional_import(package_type: &str, importer: &str) -> PathBuf {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+
+        write(
+            &root.join("package.json"),
+            &format!(
+                r##"{{
+                    {package_type}
+                    "imports": {{
+                        "#dep": {{ "require": "./dep.cjs", "import": "./dep.mjs" }}
+                    }}
+                }}"##
+            ),
+        );
+        write(&root.join("dep.cjs"), "");
+        write(&root.join("dep.mjs"), "");
+        write(&root.join(importer), "");
+
+        let resolver = NodeModulesResolver::new(TargetEnv::Node, Default::default(), false);
+        let res = resolver
+            .resolve(&FileName::Real(root.join(importer)), "#dep")
+            .unwrap();
+        let FileName::Real(path) = res.filename else {
+            panic!("expected a real file");
+        };
+
+        path.strip_prefix(&root).unwrap().to_path_buf()
+    }
+
+    #[test]
+    fn subpath_imports_of_commonjs_use_require_condition() {
+        assert_eq!(
+            resolve_conditional_import("", "index.js"),
+            Path::new("dep.cjs")
+        );
+        assert_eq!(
+            resolve_conditional_import(r#""type": "module","#, "index.cts"),
+            Path::new("dep.cjs")
+        );
+    }
+
+    #[test]
+    fn subpath_imports_of_es_modules_use_import_condition() {
+        assert_eq!(
+            resolve_conditional_import(r#""type": "module","#, "index.ts"),
+            Path::new("dep.mjs")
+        );
+        assert_eq!(
+            resolve_conditional_import("", "index.mjs"),
+            Path::new("dep.mjs")
+        );
+    }
+
+    #[test]
+    fn subpath_imports_use_only_the_first_matching_condition() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+
+        write(
+            &root.join("package.json"),
+            r##"{ "imports": { "#dep": { "require": "./missing.cjs", "default": "./dep.js" } } }"##,
+        );
+        write(&root.join("dep.js"), "");
+        write(&root.join("index.js"), "");
+
+        let resolver = NodeModulesResolver::new(TargetEnv::Node, Default::default(), false);
+        resolver
+            .resolve(&FileName::Real(root.join("index.js")), "#dep")
+            .unwrap_err();
+    }
+
+    #[test]
+    fn defined_subpath_imports_follow_edits_of_package_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let pkg_path = root.join("package.json");
+        let importer = root.join("index.js");
+        write(&importer, "");
+
+        write(&pkg_path, r##"{ "imports": { "#a": "./a.js" } }"##);
+        assert!(is_subpath_import_defined(&importer, "#a"));
+        assert!(!is_subpath_import_defined(&importer, "#b"));
+
+        write(&pkg_path, r##"{ "imports": { "#b": "./b.js" } }"##);
+        // The modification time may have a coarse resolution.
+        File::options()
+            .write(true)
+            .open(&pkg_path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(!is_subpath_import_defined(&importer, "#a"));
+        assert!(is_subpath_import_defined(&importer, "#b"));
+    }
+
+    #[test]
+    fn cached_browser_fields_are_traced() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let pkg_dir = root.join("node_modules/pkg");
+
+        write(&root.join("package.json"), "{}");
+        write(&root.join("index.js"), "");
+        write(
+            &pkg_dir.join("package.json"),
+            r#"{ "main": "index.js", "browser": { "fs": "./fs.js" } }"#,
+        );
+        write(&pkg_dir.join("index.js"), "");
+        write(&pkg_dir.join("fs.js"), "");
+
+        let resolver = NodeModulesResolver::new(TargetEnv::Browser, Default::default(), false);
+        // Fills the cache of the `browser` field.
+        resolver
+            .resolve(&FileName::Real(root.join("index.js")), "pkg")
+            .unwrap();
+
+        let (res, trace) =
+            trace::capture(|| resolver.resolve(&FileName::Real(pkg_dir.join("index.js")), "fs"));
+        assert_eq!(res.unwrap().filename, FileName::Real(pkg_dir.join("fs.js")));
+        assert!(trace.events.contains(&TraceEvent::PackageField {
+            package_json: pkg_dir.join("package.json"),
+            field: "browser",
+            value: "fs".into(),
+        }));
+        assert!(trace.events.contains(&TraceEvent::Candidate {
+            path: pkg_dir.join("fs.js"),
+            exists: true,
+        }));
     }
 }
@@ -0,0 +1,454 @@
+//! Resolution cache which is persisted to disk, so it can be reused by
+//! subsequent processes.
+
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{Context, Error};
+use dashmap::DashMap;
+use rustc_hash::FxBuildHasher;
+use serde::{Deserialize, Serialize};
+use swc_common::FileName;
+
+use crate::{
+    resolve::{Resolution, Resolve},
+    trace::{self, TraceEvent},
+};
+
+/// Bumped when the format of the cache file changes.
+const CACHE_VERSION: u32 = 3;
+
+/// A resolver which caches results of `inner` in a file.
+///
+/// Each entry records the modification times of the directories containing
+/// the paths checked by `inner` while resolving it, and of the JSON files
+/// (`package.json` and `tsconfig.json`) among them. An entry is used only if
+/// all of them are unchanged, so adding or removing a file in one of the
+/// directories or editing one of the JSON files invalidates it. Symlinks also
+/// record their targets, so retargeting one invalidates the entry as well.
+///
+/// Validating an entry takes a single `lstat` call for each of these paths,
+/// which is much less than probing the candidates again.
+///
+/// Paths are collected using [crate::trace], so `inner` should be built from
+/// resolvers of this crate, and it should not contain another caching
+/// resolver. Resolutions which did not check any path, or which were served
+/// from another cache, are not persisted.
+///
+/// `salt` should identify all configuration of `inner` which is not read from
+/// the file system, like `paths` of [super::tsc::TsConfigResolver]. A cache
+/// file created with a different `salt` is discarded.
+///
+/// The cache is written to disk by [PersistentCachingResolver::flush], which is
+/// also called on drop.
+#[derive(Debug)]
+pub struct PersistentCachingResolver<R>
+where
+    R: Resolve,
+{
+    path: PathBuf,
+    salt: String,
+    entries: DashMap<(String, String), Entry, FxBuildHasher>,
+    dirty: AtomicBool,
+    inner: R,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    salt: String,
+    entries: Vec<(String, String, Entry)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    filename: CachedFileName,
+    slug: Option<String>,
+    deps: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum CachedFileName {
+    Real(PathBuf),
+    Custom(String),
+}
+
+/// A directory or a JSON file which affected the resolution of an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Dependency {
+    path: PathBuf,
+    /// [None] if the path did not exist.
+    state: Option<FileState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileState {
+    mtime: Mtime,
+    len: u64,
+    /// The target of a symlink, in which case `mtime` and `len` are the ones of
+    /// the file it points to.
+    link_target: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Mtime {
+    secs: u64,
+    nanos: u32,
+}
+
+impl<R> PersistentCachingResolver<R>
+where
+    R: Resolve,
+{
+    /// Creates a resolver using the cache file at `path`.
+    ///
+    /// The file is ignored if it does not exist, if it cannot be read or if it
+    /// was created with another `salt`.
+    pub fn new(path: PathBuf, salt: String, inner: R) -> Self {
+        let entries = DashMap::default();
+
+        if let Some(file) = read_cache_file(&path) {
+            if file.version == CACHE_VERSION && file.salt == salt {
+                for (base, src, entry) in file.entries {
+                    entries.insert((base, src), entry);
+                }
+            }
+        }
+
+        Self {
+            path,
+            salt,
+            entries,
+            dirty: AtomicBool::new(false),
+            inner,
+        }
+    }
+
+    /// Writes the cache to disk if it's changed.
+    pub fn flush(&self) -> Result<(), Error> {
+        if !self.dirty.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let file = CacheFile {
+            version: CACHE_VERSION,
+            salt: self.salt.clone(),
+            entries: self
+                .entries
+                .iter()
+                .map(|e| (e.key().0.clone(), e.key().1.clone(), e.value().clone()))
+                .collect(),
+        };
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create `{}`", dir.display()))?;
+        }
+
+        // Write to a temporary file first, so other processes never see a partially
+        // written cache.
+        let mut tmp = self.path.clone().into_os_string();
+        tmp.push(format!(".{}.tmp", std::process::id()));
+        let tmp = PathBuf::from(tmp);
+
+        let writer = BufWriter::new(
+            File::create(&tmp).with_context(|| format!("failed to create `{}`", tmp.display()))?,
+        );
+        serde_json::to_writer(writer, &file).context("failed to serialize resolution cache")?;
+
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to write `{}`", self.path.display()))?;
+
+        Ok(())
+    }
+}
+
+impl<R> Resolve for PersistentCachingResolver<R>
+where
+    R: Resolve,
+{
+    fn resolve(&self, base: &FileName, src: &str) -> Result<Resolution, Error> {
+        let key = (base.to_string(), src.to_string());
+
+        if let Some(entry) = self.entries.get(&key) {
+            if entry.deps.iter().all(Dependency::is_fresh) {
+                let resolution = entry.to_resolution();
+                trace::record(|| TraceEvent::CacheHit {
+                    resolution: resolution.clone(),
+                });
+                return Ok(resolution);
+            }
+        }
+
+        let (resolved, trace) = trace::capture(|| self.inner.resolve(base, src));
+        let resolved = resolved?;
+
+        match Entry::new(&resolved, &trace.events) {
+            Some(entry) => {
+                self.entries.insert(key, entry);
+                self.dirty.store(true, Ordering::SeqCst);
+            }
+            None => {
+                if self.entries.remove(&key).is_some() {
+                    self.dirty.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl<R> Drop for PersistentCachingResolver<R>
+where
+    R: Resolve,
+{
+    fn drop(&mut self) {
+        if let Err(err) = self.flush() {
+            tracing::warn!("failed to write resolution cache: {:?}", err);
+        }
+    }
+}
+
+impl Entry {
+    fn new(resolution: &Resolution, events: &[TraceEvent]) -> Option<Self> {
+        let filename = match &resolution.filename {
+            FileName::Real(path) => CachedFileName::Real(path.clone()),
+            FileName::Custom(s) => CachedFileName::Custom(s.clone()),
+            _ => return None,
+        };
+
+        let mut deps: Vec<Dependency> = Vec::new();
+        let mut add_dep = |path: &Path| {
+            if !deps.iter().any(|dep| dep.path == path) {
+                deps.push(Dependency::new(path.to_path_buf()));
+            }
+        };
+
+        for event in events {
+            match event {
+                TraceEvent::Candidate { path, exists } => {
+                    // Whether a path exists is covered by the modification time of its
+                    // directory, but the contents of JSON files are read.
+                    if let Some(dir) = path.parent() {
+                        add_dep(dir);
+                    }
+                    if *exists && path.extension().is_some_and(|ext| ext == "json") {
+                        add_dep(path);
+                    }
+                }
+                // We don't know which paths were checked to create the cached result.
+                TraceEvent::CacheHit { .. } => return None,
+                _ => {}
+            }
+        }
+
+        if deps.is_empty() {
+            return None;
+        }
+
+        Some(Entry {
+            filename,
+            slug: resolution.slug.as_ref().map(|v| v.to_string()),
+            deps,
+        })
+    }
+
+    fn to_resolution(&self) -> Resolution {
+        Resolution {
+            filename: match &self.filename {
+                CachedFileName::Real(path) => FileName::Real(path.clone()),
+                CachedFileName::Custom(s) => FileName::Custom(s.clone()),
+            },
+            slug: self.slug.as_deref().map(From::from),
+        }
+    }
+}
+
+impl Dependency {
+    fn new(path: PathBuf) -> Self {
+        let state = file_state(&path);
+        Dependency { path, state }
+    }
+
+    fn is_fresh(&self) -> bool {
+        file_state(&self.path) == self.state
+    }
+}
+
+fn file_state(path: &Path) -> Option<FileState> {
+    let mut metadata = fs::symlink_metadata(path).ok()?;
+
+    let link_target = if metadata.is_symlink() {
+        let target = fs::read_link(path).ok()?;
+        metadata = fs::metadata(path).ok()?;
+        Some(target)
+    } else {
+        None
+    };
+
+    let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+    let since_epoch = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    Some(FileState {
+        mtime: Mtime {
+            secs: since_epoch.as_secs(),
+            nanos: since_epoch.subsec_nanos(),
+        },
+        len: metadata.len(),
+        link_target,
+    })
+}
+
+fn read_cache_file(path: &Path) -> Option<CacheFile> {
+    let file = File::open(path).ok()?;
+
+    match serde_json::from_reader(BufReader::new(file)) {
+        Ok(v) => Some(v),
+        Err(err) => {
+            tracing::warn!(
+                "ignoring invalid resolution cache `{}`: {:?}",
+                path.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+#[cfg(all(test, feature = "node"))]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{resolvers::node::NodeModulesResolver, TargetEnv};
+
+    type Resolver = PersistentCachingResolver<NodeModulesResolver>;
+
+    fn resolver(cache: &Path, salt: &str) -> Resolver {
+        PersistentCachingResolver::new(
+            cache.to_path_buf(),
+            salt.into(),
+            NodeModulesResolver::new(TargetEnv::Node, Default::default(), false),
+        )
+    }
+
+    /// Returns the resolved path and whether it was served from the cache.
+    fn resolve(resolver: &Resolver, base: &Path, src: &str) -> (PathBuf, bool) {
+        let (res, trace) = trace::capture(|| resolver.resolve(&FileName::Real(base.into()), src));
+        let FileName::Real(path) = res.unwrap().filename else {
+            panic!("expected a real file");
+        };
+        let hit = trace
+            .events
+            .iter()
+            .any(|event| matches!(event, TraceEvent::CacheHit { .. }));
+
+        (path, hit)
+    }
+
+    /// Returns a project directory and the path of a cache file, which is
+    /// outside of it so writing the cache doesn't invalidate entries.
+    fn setup() -> ([tempfile::TempDir; 2], PathBuf, PathBuf) {
+        let project = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let root = project.path().canonicalize().unwrap();
+        let cache_file = cache.path().join("cache.json");
+
+        ([project, cache], root, cache_file)
+    }
+
+    fn write(path: &Path, content: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn reuses_entries_of_previous_runs() {
+        let (_dirs, root, cache) = setup();
+        let base = root.join("src/index.js");
+        write(&base, "");
+        write(&root.join("src/foo.js"), "");
+
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "./foo"),
+            (root.join("src/foo.js"), false)
+        );
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "./foo"),
+            (root.join("src/foo.js"), true)
+        );
+    }
+
+    #[test]
+    fn invalidates_entries_if_package_json_is_edited() {
+        let (_dirs, root, cache) = setup();
+        let base = root.join("index.js");
+        let pkg = root.join("node_modules/pkg");
+        write(&base, "");
+        write(&pkg.join("package.json"), r#"{ "main": "a.js" }"#);
+        write(&pkg.join("a.js"), "");
+        write(&pkg.join("b.js"), "");
+
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "pkg"),
+            (pkg.join("a.js"), false)
+        );
+
+        write(&pkg.join("package.json"), r#"{ "main": "./b.js" }"#);
+
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "pkg"),
+            (pkg.join("b.js"), false)
+        );
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "pkg"),
+            (pkg.join("b.js"), true)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn invalidates_entries_if_symlink_is_retargeted() {
+        use std::os::unix::fs::symlink;
+
+        let (_dirs, root, cache) = setup();
+        let base = root.join("index.js");
+        let link = root.join("node_modules/pkg");
+        write(&base, "");
+        write(&root.join("packages/a/index.js"), "");
+        write(&root.join("packages/b/index.js"), "");
+        fs::create_dir_all(link.parent().unwrap()).unwrap();
+        symlink("../packages/a", &link).unwrap();
+
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "pkg"),
+            (root.join("packages/a/index.js"), false)
+        );
+
+        fs::remove_file(&link).unwrap();
+        symlink("../packages/b", &link).unwrap();
+
+        assert_eq!(
+            resolve(&resolver(&cache, "salt"), &base, "pkg"),
+            (root.join("packages/b/index.js"), false)
+        );
+    }
+
+    #[test]
+    fn discards_cache_file_of_another_salt() {
+        let (_dirs, root, cache) = setup();
+        let base = root.join("index.js");
+        write(&base, "");
+        write(&root.join("foo.js"), "");
+
+        assert!(!resolve(&resolver(&cache, "a"), &base, "./foo").1);
+        assert!(!resolve(&resolver(&cache, "b"), &base, "./foo").1);
+        assert!(resolve(&resolver(&cache, "b"), &base, "./foo").1);
+    }
+}
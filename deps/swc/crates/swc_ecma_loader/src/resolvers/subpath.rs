@@ -0,0 +1,233 @@
+//! Subpath patterns and conditional targets of `package.json`, which are
+//! shared by `imports` and `exports`.
+//!
+//! See https://nodejs.org/api/packages.html#subpath-patterns
+
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+/// A value of `imports` or `exports` of `package.json`.
+///
+/// Conditions are stored as a [Vec] because the order of keys matters.
+#[derive(Debug)]
+pub(crate) enum PackageTarget {
+    Null,
+    Path(String),
+    Array(Vec<PackageTarget>),
+    Conditional(Vec<(String, PackageTarget)>),
+}
+
+impl<'de> Deserialize<'de> for PackageTarget {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PackageTargetVisitor;
+
+        impl<'de> Visitor<'de> for PackageTargetVisitor {
+            type Value = PackageTarget;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a string, an array, an object or null")
+            }
+
+            fn visit_unit<E>(self) -> Result<Self::Value, E> {
+                Ok(PackageTarget::Null)
+            }
+
+            fn visit_none<E>(self) -> Result<Self::Value, E> {
+                Ok(PackageTarget::Null)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(PackageTarget::Path(v.to_string()))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(PackageTarget::Array(items))
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                let mut entries = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(PackageTarget::Conditional(entries))
+            }
+        }
+
+        deserializer.deserialize_any(PackageTargetVisitor)
+    }
+}
+
+impl PackageTarget {
+    /// Returns paths in this target which match `conditions`, in the order they
+    /// should be tried.
+    ///
+    /// Like `PACKAGE_TARGET_RESOLVE` of Node.js, only the target of the first
+    /// matching condition is used. Later conditions are used only if the
+    /// target has no matching condition itself. Items of arrays are all
+    /// returned, so they are tried if earlier ones do not resolve.
+    pub(crate) fn candidates<'a>(&'a self, conditions: &[&str]) -> Vec<&'a str> {
+        self.matching_candidates(conditions).unwrap_or_default()
+    }
+
+    /// [None] if no condition matches, which is `undefined` of
+    /// `PACKAGE_TARGET_RESOLVE`, while `null` excludes the subpath.
+    fn matching_candidates<'a>(&'a self, conditions: &[&str]) -> Option<Vec<&'a str>> {
+        match self {
+            PackageTarget::Null => Some(Vec::new()),
+            PackageTarget::Path(v) => Some(vec![v]),
+            PackageTarget::Array(items) => {
+                let matched = items
+                    .iter()
+                    .filter_map(|item| item.matching_candidates(conditions))
+                    .collect::<Vec<_>>();
+
+                (!matched.is_empty()).then(|| matched.into_iter().flatten().collect())
+            }
+            PackageTarget::Conditional(entries) => entries
+                .iter()
+                .filter(|(cond, _)| conditions.contains(&&**cond))
+                .find_map(|(_, target)| target.matching_candidates(conditions)),
+        }
+    }
+}
+
+/// Finds the entry of `imports` or `exports` matching `module_specifier`,
+/// returning the target and the substitution for `*`.
+///
+/// Exact keys take precedence over patterns, and among patterns the one with
+/// the longest prefix wins. `*` does not match an empty string.
+pub(crate) fn match_subpath<'a, 'b>(
+    entries: &'a [(String, PackageTarget)],
+    module_specifier: &'b str,
+) -> Option<(&'a PackageTarget, &'b str)> {
+    if let Some((_, target)) = entries.iter().find(|(key, _)| key == module_specifier) {
+        return Some((target, ""));
+    }
+
+    entries
+        .iter()
+        .filter_map(|(key, target)| {
+            let (prefix, suffix) = key.split_once('*')?;
+            if suffix.contains('*') || module_specifier.len() <= prefix.len() + suffix.len() {
+                return None;
+            }
+
+            let subpath = module_specifier
+                .strip_prefix(prefix)?
+                .strip_suffix(suffix)?;
+
+            Some((prefix.len(), target, subpath))
+        })
+        .max_by_key(|(prefix_len, ..)| *prefix_len)
+        .map(|(_, target, subpath)| (target, subpath))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> PackageTarget {
+        serde_json::from_str(json).unwrap()
+    }
+
+    fn entries(json: &str) -> Vec<(String, PackageTarget)> {
+        match parse(json) {
+            PackageTarget::Conditional(entries) => entries,
+            target => panic!("expected an object: {target:?}"),
+        }
+    }
+
+    /// Returns the candidates of the matched target and the substitution for
+    /// `*`.
+    fn resolve<'a>(
+        entries: &'a [(String, PackageTarget)],
+        module_specifier: &'a str,
+    ) -> Option<(Vec<&'a str>, &'a str)> {
+        match_subpath(entries, module_specifier)
+            .map(|(target, subpath)| (target.candidates(&["default"]), subpath))
+    }
+
+    #[test]
+    fn match_subpath_with_wildcard() {
+        let entries = entries(
+            r##"{
+                "#src/*": "./src/*.js",
+                "#src/internal/*": "./internal/*.js",
+                "#src/exact": "./exact.js",
+                "#*.css": "./styles/*.css"
+            }"##,
+        );
+
+        assert_eq!(
+            resolve(&entries, "#src/a/b"),
+            Some((vec!["./src/*.js"], "a/b"))
+        );
+        assert_eq!(
+            resolve(&entries, "#src/internal/a"),
+            Some((vec!["./internal/*.js"], "a"))
+        );
+        assert_eq!(
+            resolve(&entries, "#src/exact"),
+            Some((vec!["./exact.js"], ""))
+        );
+        assert_eq!(
+            resolve(&entries, "#main.css"),
+            Some((vec!["./styles/*.css"], "main"))
+        );
+        assert_eq!(resolve(&entries, "#src/"), None);
+        assert_eq!(resolve(&entries, "#lib/a"), None);
+    }
+
+    #[test]
+    fn conditional_targets_use_the_first_match() {
+        let target = parse(
+            r#"{
+                "types": "./a.d.ts",
+                "node": { "browser": "./browser.js" },
+                "import": ["./a.mjs", "./b.mjs"],
+                "default": "./a.js"
+            }"#,
+        );
+
+        // `node` has no matching condition, so `import` is used instead.
+        assert_eq!(
+            target.candidates(&["node", "import", "default"]),
+            vec!["./a.mjs", "./b.mjs"]
+        );
+        assert_eq!(target.candidates(&["default"]), vec!["./a.js"]);
+        assert!(target.candidates(&["require"]).is_empty());
+    }
+
+    #[test]
+    fn null_targets_exclude_subpaths() {
+        let entries = entries(
+            r##"{
+                "#src/*": "./src/*.js",
+                "#src/private/*": null
+            }"##,
+        );
+        assert_eq!(resolve(&entries, "#src/private/a"), Some((vec![], "a")));
+
+        let target = parse(r#"{ "node": null, "default": "./a.js" }"#);
+        assert!(target.candidates(&["node", "default"]).is_empty());
+        assert_eq!(target.candidates(&["default"]), vec!["./a.js"]);
+
+        let target = parse(r#"[null, "./a.js"]"#);
+        assert_eq!(target.candidates(&["default"]), vec!["./a.js"]);
+    }
+}
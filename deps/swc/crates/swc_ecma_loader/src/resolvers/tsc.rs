@@ -8,7 +8,10 @@ use swc_common::FileName;
 #[cfg(debug_assertions)]
 use tracing::{debug, info, trace, warn, Level};
 
-use crate::resolve::{Resolution, Resolve};
+use crate::{
+    resolve::{Resolution, Resolve},
+    trace::{self, TraceEvent},
+};
 
 #[derive(Debug)]
 enum Pattern {
@@ -19,6 +22,40 @@ enum Pattern {
     Exact(String),
 }
 
+impl Pattern {
+    fn parse(from: String, to: &[String]) -> Result<Self, Error> {
+        validate_paths_entry(&from, to)?;
+
+        Ok(match from.find('*') {
+            Some(pos) => Pattern::Wildcard {
+                prefix: from[..pos].to_string(),
+            },
+            None => Pattern::Exact(from),
+        })
+    }
+}
+
+/// Checks if an entry of `paths` of `tsconfig.json` can be used by
+/// [TsConfigResolver].
+pub(crate) fn validate_paths_entry(from: &str, to: &[String]) -> Result<(), Error> {
+    if to.is_empty() {
+        bail!("value of `paths.{from}` should not be an empty array");
+    }
+
+    if from.contains('*') {
+        if from.find('*') != from.rfind('*') {
+            bail!("`paths.{from}` should have only one wildcard");
+        }
+    } else if to.len() != 1 {
+        bail!(
+            "value of `paths.{from}` should be an array with one element because the src path \
+             does not contains * (wildcard)"
+        );
+    }
+
+    Ok(())
+}
+
 /// Support for `paths` of `tsconfig.json`.
 ///
 /// See https://www.typescriptlang.org/docs/handbook/module-resolution.html#path-mapping
@@ -54,7 +91,22 @@ where
     /// See https://www.typescriptlang.org/tsconfig#paths
     ///
     /// Note that this is not a hashmap because value is not used as a hash map.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry of `paths` is malformed. Use
+    /// [TsConfigResolver::try_new] to get an error instead.
     pub fn new(inner: R, base_url: PathBuf, paths: Vec<(String, Vec<String>)>) -> Self {
+        Self::try_new(inner, base_url, paths).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Same as [TsConfigResolver::new], but returns an error if an entry of
+    /// `paths` is malformed.
+    pub fn try_new(
+        inner: R,
+        base_url: PathBuf,
+        paths: Vec<(String, Vec<String>)>,
+    ) -> Result<Self, Error> {
         if cfg!(debug_assertions) {
             #[cfg(debug_assertions)]
             info!(
@@ -65,35 +117,8 @@ where
 
         let mut paths: Vec<(Pattern, Vec<String>)> = paths
             .into_iter()
-            .map(|(from, to)| {
-                assert!(
-                    !to.is_empty(),
-                    "value of `paths.{from}` should not be an empty array",
-                );
-
-                let pos = from.as_bytes().iter().position(|&c| c == b'*');
-                let pat = if from.contains('*') {
-                    if from.as_bytes().iter().rposition(|&c| c == b'*') != pos {
-                        panic!("`paths.{from}` should have only one wildcard")
-                    }
-
-                    Pattern::Wildcard {
-                        prefix: from[..pos.unwrap()].to_string(),
-                    }
-                } else {
-                    assert_eq!(
-                        to.len(),
-                        1,
-                        "value of `paths.{from}` should be an array with one element because the \
-                         src path does not contains * (wildcard)",
-                    );
-
-                    Pattern::Exact(from)
-                };
-
-                (pat, to)
-            })
-            .collect();
+            .map(|(from, to)| Ok((Pattern::parse(from, &to)?, to)))
+            .collect::<Result<_, Error>>()?;
 
         paths.sort_by(|(a, _), (b, _)| match (a, b) {
             (Pattern::Wildcard { .. }, Pattern::Exact(_)) => Ordering::Greater,
@@ -104,12 +129,12 @@ where
             }
         });
 
-        Self {
+        Ok(Self {
             inner,
             base_url_filename: FileName::Real(base_url.clone()),
             base_url,
             paths,
-        }
+        })
     }
 
     fn invoke_inner_resolver(
@@ -175,6 +200,21 @@ where
     R: Resolve,
 {
     fn resolve(&self, base: &FileName, module_specifier: &str) -> Result<Resolution, Error> {
+        trace::scope("tsc", base, module_specifier, || {
+            self.resolve_with_paths(base, module_specifier)
+        })
+    }
+}
+
+impl<R> TsConfigResolver<R>
+where
+    R: Resolve,
+{
+    fn resolve_with_paths(
+        &self,
+        base: &FileName,
+        module_specifier: &str,
+    ) -> Result<Resolution, Error> {
         #[cfg(debug_assertions)]
         let _tracing = if cfg!(debug_assertions) {
             Some(
@@ -239,6 +279,11 @@ where
                         debug!("Extra: `{}`", extra);
                     }
 
+                    trace::record(|| TraceEvent::PathsMatched {
+                        pattern: format!("{prefix}*"),
+                        targets: to.clone(),
+                    });
+
                     let mut errors = Vec::new();
                     for target in to {
                         let replaced = target.replace('*', extra);
@@ -270,7 +315,13 @@ where
 
                         errors.push(match res {
                             Ok(resolved) => return Ok(resolved),
-                            Err(err) => err,
+                            Err(err) => {
+                                trace::record(|| TraceEvent::Rejected {
+                                    candidate: replaced.clone(),
+                                    reason: format!("{err:#}"),
+                                });
+                                err
+                            }
                         });
 
                         if to.len() == 1 && !prefix.is_empty() {
@@ -304,6 +355,11 @@ where
                         continue;
                     }
 
+                    trace::record(|| TraceEvent::PathsMatched {
+                        pattern: from.clone(),
+                        targets: to.clone(),
+                    });
+
                     let tp = Path::new(&to[0]);
                     let slug = to[0]
                         .split([std::path::MAIN_SEPARATOR, '/'])
@@ -338,8 +394,12 @@ where
             let path = self.base_url.join(module_specifier);
 
             // https://www.typescriptlang.org/docs/handbook/modules/reference.html#baseurl
-            if let Ok(v) = self.invoke_inner_resolver(base, &path.to_string_lossy()) {
-                return Ok(v);
+            match self.invoke_inner_resolver(base, &path.to_string_lossy()) {
+                Ok(v) => return Ok(v),
+                Err(err) => trace::record(|| TraceEvent::Rejected {
+                    candidate: path.display().to_string(),
+                    reason: format!("not resolvable from `baseUrl`: {err:#}"),
+                }),
             }
         }
 
@@ -0,0 +1,383 @@
+//! Opt-in tracing of module resolution, similar to `tsc --traceResolution`.
+//!
+//! Resolvers of this crate record what they do while a trace is being
+//! captured. Recording is a no-op otherwise.
+//!
+//! Use [TracingResolver] to capture a trace for each call to
+//! [Resolve::resolve], or [capture] to capture a trace for arbitrary code.
+
+use std::{cell::RefCell, fmt, path::PathBuf};
+
+use anyhow::Error;
+use swc_common::FileName;
+
+use crate::resolve::{Resolution, Resolve};
+
+thread_local! {
+    static TRACE: RefCell<Option<Vec<TraceEvent>>> = const { RefCell::new(None) };
+}
+
+/// A step taken by a resolver.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A resolver started resolving `module_specifier`.
+    Start {
+        resolver: &'static str,
+        base: FileName,
+        module_specifier: String,
+    },
+
+    /// A file or directory was checked.
+    ///
+    /// Resolvers record every path they check, so the result of a resolution
+    /// depends only on these paths.
+    Candidate { path: PathBuf, exists: bool },
+
+    /// A field of `package.json` was used.
+    PackageField {
+        package_json: PathBuf,
+        field: &'static str,
+        value: String,
+    },
+
+    /// An entry of `paths` of `tsconfig.json` matched.
+    PathsMatched {
+        pattern: String,
+        targets: Vec<String>,
+    },
+
+    /// A candidate was rejected.
+    Rejected { candidate: String, reason: String },
+
+    /// The result was served from a cache.
+    CacheHit { resolution: Resolution },
+
+    /// A resolver finished resolving `module_specifier`.
+    Done {
+        resolver: &'static str,
+        module_specifier: String,
+        result: Result<FileName, String>,
+    },
+}
+
+/// Steps recorded while resolving modules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+/// Returns `true` if a trace is being captured on the current thread.
+pub fn is_enabled() -> bool {
+    TRACE.with(|trace| trace.borrow().is_some())
+}
+
+/// Records an event if a trace is being captured.
+///
+/// `event` is not called otherwise, so it's cheap to call this on hot paths.
+pub fn record(event: impl FnOnce() -> TraceEvent) {
+    TRACE.with(|trace| {
+        if let Some(events) = &mut *trace.borrow_mut() {
+            events.push(event());
+        }
+    })
+}
+
+/// Captures a trace of resolutions performed by `op` on the current thread.
+///
+/// This can be nested, and events recorded by the inner call are also
+/// recorded by the outer one.
+pub fn capture<T>(op: impl FnOnce() -> T) -> (T, Trace) {
+    let mut guard = CaptureGuard {
+        prev: TRACE.with(|trace| trace.borrow_mut().replace(Vec::new())),
+        finished: false,
+    };
+
+    let ret = op();
+
+    let events = guard.finish();
+
+    (ret, Trace { events })
+}
+
+/// Restores the trace of the outer [capture], even if the operation panics.
+struct CaptureGuard {
+    prev: Option<Vec<TraceEvent>>,
+    finished: bool,
+}
+
+impl CaptureGuard {
+    /// Returns the events recorded since the guard was created.
+    fn finish(&mut self) -> Vec<TraceEvent> {
+        if self.finished {
+            return Vec::new();
+        }
+        self.finished = true;
+
+        TRACE.with(|trace| {
+            let mut trace = trace.borrow_mut();
+            let events = trace.take().unwrap_or_default();
+            if let Some(mut prev) = self.prev.take() {
+                prev.extend(events.iter().cloned());
+                *trace = Some(prev);
+            }
+            events
+        })
+    }
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Records [TraceEvent::Start] and [TraceEvent::Done] around `op`.
+#[cfg(any(feature = "node", feature = "tsc"))]
+pub(crate) fn scope(
+    resolver: &'static str,
+    base: &FileName,
+    module_specifier: &str,
+    op: impl FnOnce() -> Result<Resolution, Error>,
+) -> Result<Resolution, Error> {
+    record(|| TraceEvent::Start {
+        resolver,
+        base: base.clone(),
+        module_specifier: module_specifier.to_string(),
+    });
+
+    let res = op();
+
+    record(|| TraceEvent::Done {
+        resolver,
+        module_specifier: module_specifier.to_string(),
+        result: match &res {
+            Ok(v) => Ok(v.filename.clone()),
+            Err(err) => Err(format!("{err:#}")),
+        },
+    });
+
+    res
+}
+
+impl fmt::Display for TraceEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceEvent::Start {
+                resolver,
+                base,
+                module_specifier,
+            } => write!(
+                f,
+                "======== [{resolver}] Resolving module '{module_specifier}' from '{base}'. \
+                 ========"
+            ),
+            TraceEvent::Candidate { path, exists } => {
+                if *exists {
+                    write!(f, "Path '{}' exists.", path.display())
+                } else {
+                    write!(f, "Path '{}' does not exist.", path.display())
+                }
+            }
+            TraceEvent::PackageField {
+                package_json,
+                field,
+                value,
+            } => write!(
+                f,
+                "'package.json' at '{}' has '{field}' field '{value}'.",
+                package_json.display()
+            ),
+            TraceEvent::PathsMatched { pattern, targets } => write!(
+                f,
+                "'paths' option is specified, using pattern '{pattern}' with targets {targets:?}."
+            ),
+            TraceEvent::Rejected { candidate, reason } => {
+                write!(f, "Rejected '{candidate}': {reason}")
+            }
+            TraceEvent::CacheHit { resolution } => {
+                write!(f, "Using cached resolution '{}'.", resolution.filename)
+            }
+            TraceEvent::Done {
+                resolver,
+                module_specifier,
+                result,
+            } => match result {
+                Ok(filename) => write!(
+                    f,
+                    "======== [{resolver}] Module name '{module_specifier}' was successfully \
+                     resolved to '{filename}'. ========"
+                ),
+                Err(err) => write!(
+                    f,
+                    "======== [{resolver}] Module name '{module_specifier}' was not resolved: \
+                     {err} ========"
+                ),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for event in &self.events {
+            writeln!(f, "{event}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A resolver which captures a [Trace] for each resolution and passes it to
+/// `on_trace`.
+pub struct TracingResolver<R, F>
+where
+    R: Resolve,
+    F: Fn(&FileName, &str, &Trace) + Send + Sync,
+{
+    inner: R,
+    on_trace: F,
+}
+
+impl<R, F> TracingResolver<R, F>
+where
+    R: Resolve,
+    F: Fn(&FileName, &str, &Trace) + Send + Sync,
+{
+    pub fn new(inner: R, on_trace: F) -> Self {
+        Self { inner, on_trace }
+    }
+
+    /// Resolves `module_specifier` and returns the trace alongside the result,
+    /// without calling `on_trace`.
+    pub fn resolve_with_trace(
+        &self,
+        base: &FileName,
+        module_specifier: &str,
+    ) -> (Result<Resolution, Error>, Trace) {
+        capture(|| self.inner.resolve(base, module_specifier))
+    }
+}
+
+impl<R, F> Resolve for TracingResolver<R, F>
+where
+    R: Resolve,
+    F: Fn(&FileName, &str, &Trace) + Send + Sync,
+{
+    fn resolve(&self, base: &FileName, module_specifier: &str) -> Result<Resolution, Error> {
+        let (res, trace) = self.resolve_with_trace(base, module_specifier);
+
+        (self.on_trace)(base, module_specifier, &trace);
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    use super::*;
+
+    fn candidate(path: &str) -> TraceEvent {
+        TraceEvent::Candidate {
+            path: path.into(),
+            exists: true,
+        }
+    }
+
+    #[test]
+    fn records_only_while_capturing() {
+        record(|| panic!("should not be called"));
+        assert!(!is_enabled());
+
+        let ((), trace) = capture(|| {
+            assert!(is_enabled());
+            record(|| candidate("a"));
+        });
+
+        assert!(!is_enabled());
+        assert_eq!(trace.events, vec![candidate("a")]);
+    }
+
+    #[test]
+    fn nested_captures_share_events() {
+        let ((), outer) = capture(|| {
+            record(|| candidate("a"));
+
+            let ((), inner) = capture(|| record(|| candidate("b")));
+            assert_eq!(inner.events, vec![candidate("b")]);
+
+            record(|| candidate("c"));
+        });
+
+        assert_eq!(
+            outer.events,
+            vec![candidate("a"), candidate("b"), candidate("c")]
+        );
+    }
+
+    #[test]
+    fn restores_outer_trace_on_panic() {
+        let result = catch_unwind(|| {
+            capture(|| {
+                record(|| candidate("a"));
+                panic!("failed to resolve");
+            })
+        });
+        assert!(result.is_err());
+        assert!(!is_enabled());
+
+        let ((), outer) = capture(|| {
+            record(|| candidate("a"));
+
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                capture(|| {
+                    record(|| candidate("b"));
+                    panic!("failed to resolve");
+                })
+            }));
+            assert!(result.is_err());
+
+            record(|| candidate("c"));
+        });
+
+        assert_eq!(
+            outer.events,
+            vec![candidate("a"), candidate("b"), candidate("c")]
+        );
+    }
+
+    #[cfg(feature = "node")]
+    #[test]
+    fn traces_node_resolution() {
+        use crate::{resolvers::node::NodeModulesResolver, TargetEnv};
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("index.js"), "").unwrap();
+        std::fs::write(root.join("foo.ts"), "").unwrap();
+
+        let resolver = TracingResolver::new(
+            NodeModulesResolver::new(TargetEnv::Node, Default::default(), false),
+            |_, _, _| {},
+        );
+        let (res, trace) =
+            resolver.resolve_with_trace(&FileName::Real(root.join("index.js")), "./foo");
+
+        assert_eq!(res.unwrap().filename, FileName::Real(root.join("foo.ts")));
+        assert!(matches!(
+            trace.events.first(),
+            Some(TraceEvent::Start { module_specifier, .. }) if module_specifier == "./foo"
+        ));
+        assert!(trace.events.contains(&TraceEvent::Candidate {
+            path: root.join("foo.ts"),
+            exists: true,
+        }));
+        assert!(matches!(
+            trace.events.last(),
+            Some(TraceEvent::Done { result: Ok(filename), .. })
+                if *filename == FileName::Real(root.join("foo.ts"))
+        ));
+        assert!(trace.to_string().contains("was successfully resolved"));
+    }
+}
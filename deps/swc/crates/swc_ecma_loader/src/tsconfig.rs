@@ -0,0 +1,635 @@
+//! Loader for `tsconfig.json`.
+//!
+//! See https://www.typescriptlang.org/tsconfig
+
+use std::{
+    ffi::OsString,
+    fmt,
+    fs::read_to_string,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Error};
+use jsonc_parser::{parse_to_serde_value, ParseOptions};
+use rustc_hash::FxHashSet;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    resolve::Resolve,
+    resolvers::{
+        subpath::{match_subpath, PackageTarget},
+        tsc::{validate_paths_entry, TsConfigResolver},
+    },
+};
+
+/// Conditions of `exports` used to resolve `extends`, like TypeScript.
+const EXTENDS_CONDITIONS: &[&str] = &["node", "require", "types", "default"];
+
+/// `tsconfig.json` with its `extends` chain applied.
+#[derive(Debug, Clone, Default)]
+pub struct TsConfig {
+    pub compiler_options: CompilerOptions,
+
+    /// Problems found while loading the file and the files it extends.
+    ///
+    /// Invalid options are ignored instead of making the whole file fail to
+    /// load.
+    pub diagnostics: Vec<TsConfigDiagnostic>,
+
+    /// Directory of the file declaring `paths`, which is used to resolve
+    /// `paths` if `baseUrl` is not specified.
+    paths_base: Option<PathBuf>,
+}
+
+/// Subset of `compilerOptions` used by swc.
+///
+/// All fields are [None] if they are not specified by the file or by the files
+/// it extends.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompilerOptions {
+    /// https://www.typescriptlang.org/tsconfig#baseUrl
+    ///
+    /// This is an absolute path, resolved from the file declaring it.
+    pub base_url: Option<PathBuf>,
+
+    /// https://www.typescriptlang.org/tsconfig#paths
+    ///
+    /// Malformed entries are excluded and reported as diagnostics.
+    pub paths: Option<Vec<(String, Vec<String>)>>,
+
+    /// https://www.typescriptlang.org/tsconfig#target
+    pub target: Option<String>,
+
+    /// https://www.typescriptlang.org/tsconfig#verbatimModuleSyntax
+    pub verbatim_module_syntax: Option<bool>,
+
+    /// https://www.typescriptlang.org/tsconfig#experimentalDecorators
+    pub experimental_decorators: Option<bool>,
+
+    /// https://www.typescriptlang.org/tsconfig#emitDecoratorMetadata
+    pub emit_decorator_metadata: Option<bool>,
+
+    /// https://www.typescriptlang.org/tsconfig#useDefineForClassFields
+    pub use_define_for_class_fields: Option<bool>,
+
+    /// https://www.typescriptlang.org/tsconfig#rewriteRelativeImportExtensions
+    pub rewrite_relative_import_extensions: Option<bool>,
+
+    /// https://www.typescriptlang.org/tsconfig#jsx
+    pub jsx: Option<Jsx>,
+
+    /// https://www.typescriptlang.org/tsconfig#jsxFactory
+    pub jsx_factory: Option<String>,
+
+    /// https://www.typescriptlang.org/tsconfig#jsxFragmentFactory
+    pub jsx_fragment_factory: Option<String>,
+
+    /// https://www.typescriptlang.org/tsconfig#jsxImportSource
+    pub jsx_import_source: Option<String>,
+}
+
+/// https://www.typescriptlang.org/tsconfig#jsx
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Jsx {
+    #[serde(rename = "preserve")]
+    Preserve,
+    #[serde(rename = "react")]
+    React,
+    #[serde(rename = "react-jsx")]
+    ReactJsx,
+    #[serde(rename = "react-jsxdev")]
+    ReactJsxDev,
+    #[serde(rename = "react-native")]
+    ReactNative,
+}
+
+/// A problem found while loading `tsconfig.json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TsConfigDiagnostic {
+    /// The file which contains the problem.
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl fmt::Display for TsConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.message)
+    }
+}
+
+impl TsConfig {
+    /// Loads `tsconfig.json` at `path`, following `extends`.
+    ///
+    /// This only fails if `path` itself cannot be read or parsed. Problems in
+    /// the files it extends are reported as [TsConfig::diagnostics].
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let mut loader = Loader::default();
+
+        let path = path
+            .canonicalize()
+            .with_context(|| format!("failed to canonicalize `{}`", path.display()))?;
+        let (compiler_options, paths_base) = loader.load_file(&path)?;
+
+        Ok(TsConfig {
+            compiler_options,
+            diagnostics: loader.diagnostics,
+            paths_base,
+        })
+    }
+
+    /// The directory `paths` are resolved from.
+    ///
+    /// This is `baseUrl` if it's specified, and otherwise the directory of the
+    /// file declaring `paths`.
+    pub fn base_url(&self) -> Option<PathBuf> {
+        self.compiler_options
+            .base_url
+            .clone()
+            .or_else(|| self.paths_base.clone())
+    }
+
+    /// Creates a resolver which applies `baseUrl` and `paths` of this file.
+    ///
+    /// Malformed entries of `paths` are excluded while loading, but
+    /// [TsConfig::compiler_options] may be modified afterwards.
+    pub fn resolver<R>(&self, inner: R) -> Result<TsConfigResolver<R>, Error>
+    where
+        R: Resolve,
+    {
+        TsConfigResolver::try_new(
+            inner,
+            self.base_url().unwrap_or_default(),
+            self.compiler_options.paths.clone().unwrap_or_default(),
+        )
+    }
+}
+
+impl CompilerOptions {
+    /// Whether class fields are emitted using `[[Define]]` semantics.
+    ///
+    /// If `useDefineForClassFields` is not specified, this is `true` when
+    /// `target` is `ES2022` or later, including `ESNext`, like TypeScript.
+    pub fn use_define_for_class_fields(&self) -> bool {
+        self.use_define_for_class_fields.unwrap_or_else(|| {
+            self.target.as_deref().is_some_and(|target| {
+                let target = target.to_ascii_lowercase();
+
+                target == "esnext"
+                    || target
+                        .strip_prefix("es")
+                        .and_then(|year| year.parse::<u32>().ok())
+                        .is_some_and(|year| year >= 2022)
+            })
+        })
+    }
+
+    /// Fills options which are not specified using `parent`.
+    fn inherit(&mut self, parent: &Self) {
+        macro_rules! inherit {
+            ($($field:ident),*) => {
+                $(
+                    if self.$field.is_none() {
+                        self.$field = parent.$field.clone();
+                    }
+                )*
+            };
+        }
+
+        inherit!(
+            base_url,
+            paths,
+            target,
+            verbatim_module_syntax,
+            experimental_decorators,
+            emit_decorator_metadata,
+            use_define_for_class_fields,
+            rewrite_relative_import_extensions,
+            jsx,
+            jsx_factory,
+            jsx_fragment_factory,
+            jsx_import_source
+        );
+    }
+}
+
+#[derive(Default)]
+struct Loader {
+    /// Files in the current `extends` chain, used to detect cycles.
+    stack: FxHashSet<PathBuf>,
+    diagnostics: Vec<TsConfigDiagnostic>,
+}
+
+impl Loader {
+    fn load_file(&mut self, path: &Path) -> Result<(CompilerOptions, Option<PathBuf>), Error> {
+        let src =
+            read_to_string(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+
+        let value = parse_to_serde_value(
+            src.trim_start_matches('\u{feff}'),
+            &ParseOptions {
+                allow_comments: true,
+                allow_trailing_commas: true,
+                allow_loose_object_property_names: false,
+            },
+        )
+        .with_context(|| format!("failed to parse `{}`", path.display()))?;
+
+        let Some(Value::Object(value)) = value else {
+            bail!("`{}` should contain an object", path.display())
+        };
+
+        let dir = path.parent().unwrap_or(path);
+
+        let mut compiler_options = match value.get("compilerOptions") {
+            Some(Value::Object(options)) => self.parse_compiler_options(path, dir, options),
+            Some(_) => {
+                self.report(path, "`compilerOptions` should be an object");
+                Default::default()
+            }
+            None => Default::default(),
+        };
+        let mut paths_base = compiler_options.paths.as_ref().map(|_| dir.to_path_buf());
+
+        let extends = match value.get("extends") {
+            Some(Value::String(s)) => vec![s.as_str()],
+            Some(Value::Array(v)) => v
+                .iter()
+                .filter_map(|v| {
+                    let s = v.as_str();
+                    if s.is_none() {
+                        self.report(path, "`extends` should be a string or an array of strings");
+                    }
+                    s
+                })
+                .collect(),
+            Some(_) => {
+                self.report(path, "`extends` should be a string or an array of strings");
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        self.stack.insert(path.to_path_buf());
+
+        // Files later in `extends` take precedence over earlier ones.
+        for specifier in extends.into_iter().rev() {
+            let Some(parent_path) = resolve_extends(dir, specifier) else {
+                self.report(path, format!("failed to resolve `extends`: `{specifier}`"));
+                continue;
+            };
+
+            if self.stack.contains(&parent_path) {
+                self.report(
+                    path,
+                    format!("circular `extends`: `{}`", parent_path.display()),
+                );
+                continue;
+            }
+
+            match self.load_file(&parent_path) {
+                Ok((parent, parent_paths_base)) => {
+                    if compiler_options.paths.is_none() {
+                        paths_base = parent_paths_base;
+                    }
+                    compiler_options.inherit(&parent);
+                }
+                Err(err) => self.report(path, format!("{err:#}")),
+            }
+        }
+
+        self.stack.remove(path);
+
+        Ok((compiler_options, paths_base))
+    }
+
+    fn parse_compiler_options(
+        &mut self,
+        path: &Path,
+        dir: &Path,
+        options: &Map<String, Value>,
+    ) -> CompilerOptions {
+        CompilerOptions {
+            base_url: self
+                .parse_option::<PathBuf>(path, options, "baseUrl")
+                .map(|v| dir.join(v)),
+            paths: options
+                .get("paths")
+                .filter(|v| !v.is_null())
+                .map(|v| self.parse_paths(path, v)),
+            target: self.parse_option(path, options, "target"),
+            verbatim_module_syntax: self.parse_option(path, options, "verbatimModuleSyntax"),
+            experimental_decorators: self.parse_option(path, options, "experimentalDecorators"),
+            emit_decorator_metadata: self.parse_option(path, options, "emitDecoratorMetadata"),
+            use_define_for_class_fields: self.parse_option(
+                path,
+                options,
+                "useDefineForClassFields",
+            ),
+            rewrite_relative_import_extensions: self.parse_option(
+                path,
+                options,
+                "rewriteRelativeImportExtensions",
+            ),
+            jsx: self.parse_option(path, options, "jsx"),
+            jsx_factory: self.parse_option(path, options, "jsxFactory"),
+            jsx_fragment_factory: self.parse_option(path, options, "jsxFragmentFactory"),
+            jsx_import_source: self.parse_option(path, options, "jsxImportSource"),
+        }
+    }
+
+    fn parse_option<T>(&mut self, path: &Path, options: &Map<String, Value>, key: &str) -> Option<T>
+    where
+        T: DeserializeOwned,
+    {
+        let value = options.get(key).filter(|v| !v.is_null())?;
+
+        match T::deserialize(value) {
+            Ok(v) => Some(v),
+            Err(err) => {
+                self.report(path, format!("invalid `compilerOptions.{key}`: {err}"));
+                None
+            }
+        }
+    }
+
+    fn parse_paths(&mut self, path: &Path, value: &Value) -> Vec<(String, Vec<String>)> {
+        let Value::Object(paths) = value else {
+            self.report(path, "`compilerOptions.paths` should be an object");
+            return Vec::new();
+        };
+
+        let mut result = Vec::with_capacity(paths.len());
+
+        for (from, to) in paths {
+            let to = match Vec::<String>::deserialize(to) {
+                Ok(to) => to,
+                Err(err) => {
+                    self.report(path, format!("invalid `paths.{from}`: {err}"));
+                    continue;
+                }
+            };
+
+            if let Err(err) = validate_paths_entry(from, &to) {
+                self.report(path, err.to_string());
+                continue;
+            }
+
+            result.push((from.clone(), to));
+        }
+
+        result
+    }
+
+    fn report(&mut self, path: &Path, message: impl Into<String>) {
+        self.diagnostics.push(TsConfigDiagnostic {
+            path: path.to_path_buf(),
+            message: message.into(),
+        });
+    }
+}
+
+/// Resolves a value of `extends`, which is either a path or a module specifier
+/// of a package.
+fn resolve_extends(dir: &Path, specifier: &str) -> Option<PathBuf> {
+    let is_path = Path::new(specifier).is_absolute()
+        || specifier.starts_with("./")
+        || specifier.starts_with("../")
+        || specifier.starts_with(".\\")
+        || specifier.starts_with("..\\");
+
+    if is_path {
+        return resolve_json_file(&dir.join(specifier));
+    }
+
+    let (name, subpath) = split_package_specifier(specifier);
+
+    for ancestor in dir.ancestors() {
+        let pkg_dir = ancestor.join("node_modules").join(name);
+
+        // Files which are not exported are not visible.
+        if let Some(exports) = read_package_json(&pkg_dir).and_then(|pkg| pkg.exports) {
+            return resolve_exports(&pkg_dir, &exports, &subpath);
+        }
+
+        let path = ancestor.join("node_modules").join(specifier);
+
+        if let Some(path) = resolve_json_file(&path) {
+            return Some(path);
+        }
+
+        if path.is_dir() {
+            if let Some(tsconfig) = package_tsconfig(&path) {
+                return Some(tsconfig);
+            }
+
+            let tsconfig = path.join("tsconfig.json");
+            if tsconfig.is_file() {
+                return tsconfig.canonicalize().ok();
+            }
+        }
+    }
+
+    None
+}
+
+/// Resolves `path` as is, or with `.json` extension appended.
+fn resolve_json_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() {
+        return path.canonicalize().ok();
+    }
+
+    let mut with_ext = OsString::from(path.as_os_str());
+    with_ext.push(".json");
+    let with_ext = PathBuf::from(with_ext);
+
+    if with_ext.is_file() {
+        return with_ext.canonicalize().ok();
+    }
+
+    None
+}
+
+/// Splits `specifier` into the name of a package and a subpath of `exports`,
+/// which is `.` for the package itself.
+fn split_package_specifier(specifier: &str) -> (&str, String) {
+    let name_len = if specifier.starts_with('@') {
+        specifier
+            .match_indices('/')
+            .nth(1)
+            .map_or(specifier.len(), |(i, _)| i)
+    } else {
+        specifier.find('/').unwrap_or(specifier.len())
+    };
+
+    let (name, rest) = specifier.split_at(name_len);
+
+    (name, format!(".{rest}"))
+}
+
+/// Resolves `subpath` of the package in `pkg_dir` using `exports` of its
+/// `package.json`.
+fn resolve_exports(pkg_dir: &Path, exports: &PackageTarget, subpath: &str) -> Option<PathBuf> {
+    let (target, replacement) = match exports {
+        // Keys of subpath exports start with `.`, and others are conditions.
+        PackageTarget::Conditional(entries)
+            if entries.iter().all(|(key, _)| key.starts_with('.')) =>
+        {
+            match_subpath(entries, subpath)?
+        }
+        _ if subpath == "." => (exports, ""),
+        _ => return None,
+    };
+
+    target
+        .candidates(EXTENDS_CONDITIONS)
+        .into_iter()
+        .filter(|target| target.starts_with("./"))
+        .find_map(|target| resolve_json_file(&pkg_dir.join(target.replace('*', replacement))))
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    tsconfig: Option<String>,
+    exports: Option<PackageTarget>,
+}
+
+fn read_package_json(dir: &Path) -> Option<PackageJson> {
+    let src = read_to_string(dir.join("package.json")).ok()?;
+
+    serde_json::from_str(&src).ok()
+}
+
+/// Uses the `tsconfig` field of `package.json` in `dir`.
+fn package_tsconfig(dir: &Path) -> Option<PathBuf> {
+    resolve_json_file(&dir.join(read_package_json(dir)?.tsconfig?))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn load(root: &Path) -> TsConfig {
+        TsConfig::load(&root.join("tsconfig.json")).unwrap()
+    }
+
+    #[test]
+    fn extends_package_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        write(
+            root,
+            "node_modules/@org/tsconfig/package.json",
+            r#"{ "exports": { "./strict": "./configs/strict.json", "./*": { "types": "./configs/*.json" } } }"#,
+        );
+        write(
+            root,
+            "node_modules/@org/tsconfig/configs/strict.json",
+            r#"{ "compilerOptions": { "verbatimModuleSyntax": true } }"#,
+        );
+        write(
+            root,
+            "node_modules/@org/tsconfig/configs/node.json",
+            r#"{ "compilerOptions": { "target": "ES2022", "verbatimModuleSyntax": false } }"#,
+        );
+        // Not reachable, because `exports` takes precedence over the file tree.
+        write(
+            root,
+            "node_modules/@org/tsconfig/hidden.json",
+            r#"{ "compilerOptions": {} }"#,
+        );
+        write(
+            root,
+            "tsconfig.json",
+            r#"{
+                // Files later in `extends` take precedence.
+                "extends": ["@org/tsconfig/node", "@org/tsconfig/strict", "@org/tsconfig/hidden.json"],
+            }"#,
+        );
+
+        let tsconfig = load(root);
+
+        assert_eq!(tsconfig.compiler_options.target.as_deref(), Some("ES2022"));
+        assert_eq!(tsconfig.compiler_options.verbatim_module_syntax, Some(true));
+        assert_eq!(
+            tsconfig
+                .diagnostics
+                .iter()
+                .map(|d| d.message.as_str())
+                .collect::<Vec<_>>(),
+            ["failed to resolve `extends`: `@org/tsconfig/hidden.json`"]
+        );
+    }
+
+    #[test]
+    fn paths_diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+
+        write(
+            root,
+            "tsconfig.json",
+            r#"{
+                "compilerOptions": {
+                    "paths": {
+                        "@app/*": ["./src/*"],
+                        "empty": [],
+                        "two/*/*": ["./*"],
+                        "exact": ["./a", "./b"],
+                        "string": "./src"
+                    }
+                }
+            }"#,
+        );
+
+        let tsconfig = load(root);
+
+        assert_eq!(
+            tsconfig.compiler_options.paths,
+            Some(vec![("@app/*".to_string(), vec!["./src/*".to_string()])])
+        );
+        assert_eq!(tsconfig.base_url(), Some(root.canonicalize().unwrap()));
+
+        let messages = tsconfig
+            .diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 4, "{messages:?}");
+        for key in ["empty", "two/*/*", "exact", "string"] {
+            assert!(
+                messages
+                    .iter()
+                    .any(|m| m.contains(&format!("`paths.{key}`"))),
+                "{key}: {messages:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn use_define_for_class_fields_defaults_from_target() {
+        let options = |target: Option<&str>, value: Option<bool>| CompilerOptions {
+            target: target.map(From::from),
+            use_define_for_class_fields: value,
+            ..Default::default()
+        };
+
+        assert!(!options(None, None).use_define_for_class_fields());
+        assert!(!options(Some("ES2021"), None).use_define_for_class_fields());
+        assert!(!options(Some("es6"), None).use_define_for_class_fields());
+        assert!(options(Some("ES2022"), None).use_define_for_class_fields());
+        assert!(options(Some("es2024"), None).use_define_for_class_fields());
+        assert!(options(Some("ESNext"), None).use_define_for_class_fields());
+        assert!(!options(Some("ESNext"), Some(false)).use_define_for_class_fields());
+        assert!(options(Some("ES5"), Some(true)).use_define_for_class_fields());
+    }
+}
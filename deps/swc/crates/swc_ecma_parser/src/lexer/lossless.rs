@@ -0,0 +1,270 @@
+//! Lossless view of a token stream.
+//!
+//! The [Lexer](super::Lexer) only yields tokens, while comments are passed to
+//! [Comments](swc_common::comments::Comments) and whitespace is dropped.
+//! [lossless_tokens] fills the gaps between tokens with [Trivia], so
+//! concatenating the source text of every item reproduces the input exactly.
+//!
+//! This is useful for tools which rewrite source text in place, like
+//! formatters and codemods. Tokens can be collected from the
+//! [Lexer](super::Lexer), or from [Capturing](super::capturing::Capturing)
+//! while parsing.
+
+use swc_common::{BytePos, SourceFile, Span, Spanned};
+
+/// An item of a lossless token stream.
+#[derive(Debug, Clone, Copy)]
+pub enum LosslessToken<T> {
+    Token(T),
+    Trivia(Trivia),
+}
+
+impl<T: Spanned> LosslessToken<T> {
+    pub fn span(&self) -> Span {
+        match self {
+            LosslessToken::Token(t) => t.span(),
+            LosslessToken::Trivia(t) => t.span,
+        }
+    }
+
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, LosslessToken::Trivia(..))
+    }
+}
+
+/// Source text between tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriviaKind {
+    /// Consecutive whitespace characters other than line terminators.
+    Whitespace,
+    /// A single line terminator. `\r\n` is one line break.
+    LineBreak,
+    /// `// ...`, excluding the line terminator.
+    LineComment,
+    /// `/* ... */`, which may span multiple lines.
+    BlockComment,
+    /// Source text which was not captured as a token, e.g. a token consumed
+    /// by a rewound lookahead, or text of an unterminated comment.
+    ///
+    /// It extends to the next token, as the uncaptured text may contain
+    /// strings or templates which look like comments or whitespace.
+    Skipped,
+}
+
+/// Interleaves `tokens` with the [Trivia] between them.
+///
+/// `tokens` should be the tokens of `fm`, sorted by position. Tokens
+/// overlapping a previous token are ignored.
+///
+/// The spans of the returned items are contiguous and cover the whole
+/// `fm`.
+pub fn lossless_tokens<T>(fm: &SourceFile, tokens: &[T]) -> Vec<LosslessToken<T>>
+where
+    T: Spanned + Clone,
+{
+    let mut out = Vec::with_capacity(tokens.len() * 2);
+    let mut pos = fm.start_pos;
+
+    for token in tokens {
+        let span = token.span();
+        if span.lo < pos {
+            continue;
+        }
+
+        push_trivia(&mut out, fm, pos, span.lo);
+        out.push(LosslessToken::Token(token.clone()));
+        pos = span.hi;
+    }
+
+    push_trivia(&mut out, fm, pos, fm.end_pos);
+
+    out
+}
+
+fn push_trivia<T>(out: &mut Vec<LosslessToken<T>>, fm: &SourceFile, lo: BytePos, hi: BytePos) {
+    if lo >= hi {
+        return;
+    }
+
+    let offset = (lo - fm.start_pos).0 as usize;
+    let src = &fm.src[offset..(hi - fm.start_pos).0 as usize];
+
+    let mut i = 0;
+    while i < src.len() {
+        let rest = &src[i..];
+        let c = rest.chars().next().unwrap();
+
+        let (kind, len) = if rest.starts_with("\r\n") {
+            (TriviaKind::LineBreak, 2)
+        } else if is_line_break(c) {
+            (TriviaKind::LineBreak, c.len_utf8())
+        } else if rest.starts_with("//") {
+            let len = rest.find(is_line_break).unwrap_or(rest.len());
+            (TriviaKind::LineComment, len)
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            match body.find("*/") {
+                Some(end) => (TriviaKind::BlockComment, end + 4),
+                None => (TriviaKind::Skipped, rest.len()),
+            }
+        } else if is_whitespace(c) {
+            let len = rest
+                .find(|c| !is_whitespace(c) || is_line_break(c))
+                .unwrap_or(rest.len());
+            (TriviaKind::Whitespace, len)
+        } else {
+            (TriviaKind::Skipped, rest.len())
+        };
+
+        let start = lo + BytePos(i as u32);
+        out.push(LosslessToken::Trivia(Trivia {
+            kind,
+            span: Span::new(start, start + BytePos(len as u32)),
+        }));
+        i += len;
+    }
+}
+
+/// https://tc39.es/ecma262/#sec-line-terminators
+fn is_line_break(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+/// https://tc39.es/ecma262/#sec-white-space
+fn is_whitespace(c: char) -> bool {
+    is_line_break(c)
+        || matches!(
+            c,
+            '\t' | '\u{000B}' | '\u{000C}' | '\u{FEFF}'
+            // Space_Separator
+            | ' ' | '\u{00A0}' | '\u{1680}' | '\u{2000}'
+                ..='\u{200A}' | '\u{202F}' | '\u{205F}' | '\u{3000}'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::{FileName, SourceMap};
+    use swc_ecma_ast::EsVersion;
+
+    use super::*;
+    use crate::{lexer::Lexer, StringInput, Syntax};
+
+    fn source(src: &str) -> swc_common::sync::Lrc<SourceFile> {
+        let cm = SourceMap::default();
+        cm.new_source_file(FileName::Anon.into(), src.to_string())
+    }
+
+    fn text(fm: &SourceFile, span: Span) -> &str {
+        &fm.src[(span.lo - fm.start_pos).0 as usize..(span.hi - fm.start_pos).0 as usize]
+    }
+
+    fn trivia_kinds<T: Spanned>(items: &[LosslessToken<T>]) -> Vec<Option<TriviaKind>> {
+        items
+            .iter()
+            .map(|item| match item {
+                LosslessToken::Token(..) => None,
+                LosslessToken::Trivia(t) => Some(t.kind),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reproduces_input() {
+        let src = "/** doc */\r\nconst a = 1; // one\n\u{2028}\tlet b\u{3000}= `x${a}`;/* end";
+        let fm = source(src);
+        let tokens: Vec<_> = Lexer::new(
+            Syntax::default(),
+            EsVersion::latest(),
+            StringInput::from(&*fm),
+            None,
+        )
+        .collect();
+
+        let items = lossless_tokens(&fm, &tokens);
+
+        let mut pos = fm.start_pos;
+        let mut out = String::new();
+        for item in &items {
+            assert_eq!(item.span().lo, pos);
+            pos = item.span().hi;
+            out.push_str(text(&fm, item.span()));
+        }
+        assert_eq!(pos, fm.end_pos);
+        assert_eq!(out, src);
+    }
+
+    #[test]
+    fn classifies_trivia() {
+        let fm = source("a /* b */ // c\r\nd");
+        let tokens: Vec<_> = Lexer::new(
+            Syntax::default(),
+            EsVersion::latest(),
+            StringInput::from(&*fm),
+            None,
+        )
+        .collect();
+
+        let items = lossless_tokens(&fm, &tokens);
+
+        assert_eq!(
+            trivia_kinds(&items),
+            vec![
+                None,
+                Some(TriviaKind::Whitespace),
+                Some(TriviaKind::BlockComment),
+                Some(TriviaKind::Whitespace),
+                Some(TriviaKind::LineComment),
+                Some(TriviaKind::LineBreak),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_uncaptured_and_overlapping_tokens() {
+        let fm = source("ab cd");
+        let at =
+            |lo: u32, hi: u32| Span::new(fm.start_pos + BytePos(lo), fm.start_pos + BytePos(hi));
+        // `cd` is not captured and `b` overlaps `ab`.
+        let tokens = [at(0, 2), at(1, 2)];
+
+        let items = lossless_tokens(&fm, &tokens);
+
+        assert_eq!(
+            trivia_kinds(&items),
+            vec![
+                None,
+                Some(TriviaKind::Whitespace),
+                Some(TriviaKind::Skipped)
+            ]
+        );
+        assert_eq!(text(&fm, items[2].span()), "cd");
+    }
+
+    #[test]
+    fn does_not_classify_uncaptured_strings() {
+        let fm = source("a 'b // c' /* d */");
+        let at =
+            |lo: u32, hi: u32| Span::new(fm.start_pos + BytePos(lo), fm.start_pos + BytePos(hi));
+        // The string is not captured.
+        let tokens = [at(0, 1)];
+
+        let items = lossless_tokens(&fm, &tokens);
+
+        assert_eq!(
+            trivia_kinds(&items),
+            vec![
+                None,
+                Some(TriviaKind::Whitespace),
+                Some(TriviaKind::Skipped)
+            ]
+        );
+        assert_eq!(text(&fm, items[2].span()), "'b // c' /* d */");
+    }
+}
@@ -39,6 +39,8 @@ pub(crate) mod capturing;
 mod char_ext;
 mod comments_buffer;
 mod jsx;
+#[cfg(feature = "unstable")]
+pub(crate) mod lossless;
 mod number;
 pub(crate) mod search;
 mod state;
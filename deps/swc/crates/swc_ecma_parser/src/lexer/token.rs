@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use swc_atoms::{Atom, Wtf8Atom};
-use swc_common::{BytePos, Span};
+use swc_common::{BytePos, Span, Spanned};
 use swc_ecma_ast::AssignOp;
 
 use super::LexResult;
@@ -882,6 +882,13 @@ pub struct TokenAndSpan {
     pub span: Span,
 }
 
+impl Spanned for TokenAndSpan {
+    #[inline(always)]
+    fn span(&self) -> Span {
+        self.span
+    }
+}
+
 impl TokenAndSpan {
     #[inline(always)]
     pub fn new(token: Token, span: Span, had_line_break: bool) -> Self {
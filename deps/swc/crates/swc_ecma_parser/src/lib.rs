@@ -175,8 +175,12 @@ pub mod unstable {
     //! the performance.
     //!
     //! Also see the dicussion https://github.com/swc-project/swc/discussions/10683
+    //!
+    //! Use [lossless_tokens] to interleave the tokens captured by [Capturing]
+    //! with comments and whitespace.
     pub use crate::lexer::{
         capturing::Capturing,
+        lossless::{lossless_tokens, LosslessToken, Trivia, TriviaKind},
         token::{NextTokenAndSpan, Token, TokenAndSpan, TokenValue},
     };
 }
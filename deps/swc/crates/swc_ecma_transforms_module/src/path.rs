@@ -13,7 +13,10 @@ use pathdiff::diff_paths;
 use swc_atoms::Atom;
 use swc_common::{FileName, Mark, Span, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
-use swc_ecma_loader::resolve::{Resolution, Resolve};
+use swc_ecma_loader::{
+    resolve::{Resolution, Resolve},
+    resolvers::node::is_subpath_import_defined,
+};
 use swc_ecma_utils::{quote_ident, ExprFactory};
 #[cfg(debug_assertions)]
 use tracing::{debug, info, warn, Level};
@@ -103,6 +106,13 @@ pub struct Config {
     pub base_dir: Option<PathBuf>,
     pub resolve_fully: bool,
     pub file_extension: String,
+    /// If `false`, subpath imports (`#foo`) defined in `imports` of
+    /// `package.json` are preserved as is, so they are resolved by node.js at
+    /// runtime.
+    ///
+    /// If `true`, they are resolved and rewritten to relative paths like other
+    /// imports.
+    pub resolve_subpath_imports: bool,
 }
 
 impl Default for Config {
@@ -111,6 +121,7 @@ impl Default for Config {
             file_extension: crate::util::Config::default_js_ext(),
             resolve_fully: bool::default(),
             base_dir: Option::default(),
+            resolve_subpath_imports: bool::default(),
         }
     }
 }
@@ -267,6 +278,16 @@ where
             None
         };
 
+        // Subpath imports are resolved by node.js at runtime. Other specifiers starting
+        // with `#`, like aliases of `paths`, are resolved as usual.
+        if !self.config.resolve_subpath_imports {
+            if let FileName::Real(base) = base {
+                if is_subpath_import_defined(base, module_specifier) {
+                    return Ok(module_specifier.into());
+                }
+            }
+        }
+
         let orig_slug = module_specifier.split('/').next_back();
 
         let target = self.resolver.resolve(base, module_specifier);
@@ -1,9 +1,7 @@
-use std::path::Path;
-
-use swc_atoms::{Atom, Wtf8Atom};
 use swc_common::{util::take::Take, DUMMY_SP};
 use swc_ecma_ast::*;
 use swc_ecma_transforms_base::helper;
+use swc_ecma_utils::get_output_extension_wtf8;
 use swc_ecma_visit::{visit_mut_pass, VisitMut, VisitMutWith};
 
 /// This is the implementation of rewriteRelativeImportExtensions in TypeScript
@@ -74,38 +72,3 @@ impl VisitMut for Rewriter {
         }
     }
 }
-
-fn get_output_extension(specifier: &Atom) -> Option<Atom> {
-    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/utilities.ts#L4331
-    let path = Path::new(specifier.as_str());
-    if !(path.starts_with("./")
-        || path.starts_with("../")
-        || path.starts_with(".\\")
-        || path.starts_with("..\\"))
-    {
-        return None;
-    }
-
-    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/parser.ts#L10556
-    if path.file_name()?.to_str()?.contains(".d.") {
-        return None;
-    }
-
-    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/emitter.ts#L540
-    let ext = path.extension()?.to_str()?;
-    let ext = match ext {
-        "js" | "ts" => "js",
-        "json" => "json",
-        "jsx" | "tsx" => "jsx",
-        "mjs" | "mts" => "mjs",
-        "cjs" | "cts" => "cjs",
-        _ => return None,
-    };
-
-    Some(Atom::new(path.with_extension(ext).to_str()?))
-}
-
-fn get_output_extension_wtf8(specifier: &Wtf8Atom) -> Option<Wtf8Atom> {
-    let normalized = specifier.to_atom_lossy();
-    get_output_extension(normalized.as_ref()).map(Into::into)
-}
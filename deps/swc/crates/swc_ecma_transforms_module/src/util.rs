@@ -47,6 +47,11 @@ pub struct Config {
     #[serde(default)]
     pub resolve_fully: bool,
 
+    /// Resolve subpath imports (`#foo`) using `imports` of `package.json`
+    /// instead of preserving them.
+    #[serde(default)]
+    pub resolve_subpath_imports: bool,
+
     #[serde(default = "Config::default_js_ext")]
     pub out_file_extension: String,
 }
@@ -70,6 +75,7 @@ impl Default for Config {
             ignore_dynamic: false,
             preserve_import_meta: false,
             resolve_fully: false,
+            resolve_subpath_imports: false,
             out_file_extension: "js".to_string(),
         }
     }
@@ -21,6 +21,7 @@ use rustc_hash::{FxHashMap, FxHashSet};
 use swc_atoms::{
     atom,
     wtf8::{Wtf8, Wtf8Buf},
+    Atom, Wtf8Atom,
 };
 use swc_common::{util::take::Take, Mark, Span, Spanned, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
@@ -1581,6 +1582,119 @@ pub fn opt_chain_test(
     }
 }
 
+/// Check if erasing the type assertions of `expr`, like `a + b as T`, would
+/// change the grouping of the binary expression which continues with
+/// `next_op` after them.
+///
+/// For example, `a + b as any * c` is `(a + b) * c`, but stripping the type
+/// assertion yields `a + b * c`.
+pub fn assertion_chain_would_change_binary_grouping(expr: &Expr, next_op: BinaryOp) -> bool {
+    let mut expr = expr;
+
+    let base_op = loop {
+        match expr {
+            Expr::TsAs(TsAsExpr { expr: inner, .. })
+            | Expr::TsSatisfies(TsSatisfiesExpr { expr: inner, .. })
+            | Expr::TsConstAssertion(TsConstAssertion { expr: inner, .. }) => {
+                expr = inner;
+            }
+            Expr::Bin(bin_expr) => break bin_expr.op,
+            _ => return false,
+        }
+    };
+
+    let base_precedence = binary_operator_precedence(base_op);
+    let next_precedence = binary_operator_precedence(next_op);
+
+    if next_precedence != base_precedence {
+        return next_precedence > base_precedence;
+    }
+
+    // `**` is right-associative, so flattening equal-precedence groups is not safe.
+    base_op == BinaryOp::Exp || next_op == BinaryOp::Exp
+}
+
+/// Unlike [BinaryOp::precedence], `??` binds weaker than `||`.
+fn binary_operator_precedence(op: BinaryOp) -> u8 {
+    match op {
+        BinaryOp::Exp => 15,
+        BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 14,
+        BinaryOp::Add | BinaryOp::Sub => 13,
+        BinaryOp::LShift | BinaryOp::RShift | BinaryOp::ZeroFillRShift => 12,
+        BinaryOp::Lt
+        | BinaryOp::LtEq
+        | BinaryOp::Gt
+        | BinaryOp::GtEq
+        | BinaryOp::In
+        | BinaryOp::InstanceOf => 11,
+        BinaryOp::EqEq | BinaryOp::NotEq | BinaryOp::EqEqEq | BinaryOp::NotEqEq => 10,
+        BinaryOp::BitAnd => 9,
+        BinaryOp::BitXor => 8,
+        BinaryOp::BitOr => 7,
+        BinaryOp::LogicalAnd => 6,
+        BinaryOp::LogicalOr => 5,
+        BinaryOp::NullishCoalescing => 4,
+        #[cfg(swc_ast_unknown)]
+        _ => panic!("unable to access unknown nodes"),
+    }
+}
+
+/// Returns `specifier` with a TypeScript extension replaced by the extension
+/// of the emitted JavaScript file, or [None] if `specifier` is not a relative
+/// path to a TypeScript file.
+///
+/// This is how `rewriteRelativeImportExtensions` of TypeScript rewrites
+/// module specifiers.
+pub fn get_output_extension(specifier: &Atom) -> Option<Atom> {
+    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/utilities.ts#L4331
+    let path = std::path::Path::new(specifier.as_str());
+    if !(path.starts_with("./")
+        || path.starts_with("../")
+        || path.starts_with(".\\")
+        || path.starts_with("..\\"))
+    {
+        return None;
+    }
+
+    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/parser.ts#L10556
+    if path.file_name()?.to_str()?.contains(".d.") {
+        return None;
+    }
+
+    // https://github.com/microsoft/TypeScript/blob/3eb7b6a1794a6d2cde7948a3016c57e628b104b9/src/compiler/emitter.ts#L540
+    let ext = path.extension()?.to_str()?;
+    let ext = match ext {
+        "js" | "ts" => "js",
+        "json" => "json",
+        "jsx" | "tsx" => "jsx",
+        "mjs" | "mts" => "mjs",
+        "cjs" | "cts" => "cjs",
+        _ => return None,
+    };
+
+    Some(Atom::new(path.with_extension(ext).to_str()?))
+}
+
+/// [get_output_extension] for specifiers which may contain lone surrogates.
+pub fn get_output_extension_wtf8(specifier: &Wtf8Atom) -> Option<Wtf8Atom> {
+    if let Some(specifier) = specifier.as_atom() {
+        return get_output_extension(specifier).map(Into::into);
+    }
+
+    let normalized = specifier.to_atom_lossy();
+    let output = get_output_extension(normalized.as_ref())?;
+
+    // Only the extension is replaced, so the rest of `specifier` is kept as is
+    // instead of the lossy one. Lone surrogates and U+FFFD have the same length
+    // in WTF-8.
+    let ext_start = normalized.rfind('.')?;
+    let mut buf = Wtf8Buf::with_capacity(output.len());
+    buf.push_wtf8(specifier.slice_to(ext_start));
+    buf.push_str(&output[ext_start..]);
+
+    Some(buf.into())
+}
+
 /// inject `branch` after directives
 #[inline]
 pub fn prepend_stmt<T: StmtLike>(stmts: &mut Vec<T>, stmt: T) {
@@ -3895,6 +4009,30 @@ mod tests {
         assert!(has_top_level_await("export const foo = await 1;"));
         assert!(has_top_level_await("export default await 1;"));
     }
+
+    fn would_change_binary_grouping(text: &str) -> bool {
+        let mut p = Parser::new(
+            Syntax::Typescript(Default::default()),
+            StringInput::new(text, BytePos(0), BytePos(text.len() as u32)),
+            None,
+        );
+        let expr = p.parse_expr().unwrap();
+        let Expr::Bin(BinExpr { left, op, .. }) = &*expr else {
+            panic!("expected a binary expression: {text}");
+        };
+
+        assertion_chain_would_change_binary_grouping(left, *op)
+    }
+
+    #[test]
+    fn assertion_chain_binary_grouping() {
+        assert!(would_change_binary_grouping("a + b as any * c"));
+        assert!(would_change_binary_grouping("a << b as any as T + c"));
+        assert!(would_change_binary_grouping("a + b satisfies T * c"));
+        assert!(!would_change_binary_grouping("a * b as any + c"));
+        assert!(!would_change_binary_grouping("a - b as any - c"));
+        assert!(!would_change_binary_grouping("a as any * c"));
+    }
 }
 
 #[cfg(test)]
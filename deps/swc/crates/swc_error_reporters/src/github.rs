@@ -0,0 +1,164 @@
+//! Diagnostics as [workflow commands of GitHub Actions](https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions),
+//! which are shown as annotations of files.
+
+use std::io::Write;
+
+use swc_common::{
+    errors::{Diagnostic, DiagnosticBuilder, Emitter, Level},
+    sync::Lrc,
+    SourceMap,
+};
+
+use crate::structured::{code, file_path, lookup_span, utf16_col};
+
+/// Returns a workflow command like `::error file=a.js,line=1,col=5::message`.
+pub fn to_github_annotation(cm: &SourceMap, d: &Diagnostic) -> String {
+    let command = match d.level {
+        Level::Warning => "warning",
+        _ if d.is_error() => "error",
+        _ => "notice",
+    };
+
+    let mut properties = Vec::new();
+
+    if let Some((start, end)) = d.span.primary_span().and_then(|span| lookup_span(cm, span)) {
+        properties.push(format!(
+            "file={}",
+            escape_property(&file_path(&start.file.name))
+        ));
+        properties.push(format!("line={}", start.line));
+        properties.push(format!("endLine={}", end.line));
+        properties.push(format!("col={}", utf16_col(&start)));
+        properties.push(format!("endColumn={}", utf16_col(&end)));
+    }
+
+    if let Some(code) = code(d) {
+        properties.push(format!("title={}", escape_property(code)));
+    }
+
+    if properties.is_empty() {
+        format!("::{command}::{}", escape_data(&d.message()))
+    } else {
+        format!(
+            "::{command} {}::{}",
+            properties.join(","),
+            escape_data(&d.message())
+        )
+    }
+}
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Writes each diagnostic as a workflow command of GitHub Actions.
+pub struct GithubActionsEmitter {
+    dst: Box<dyn Write + Send>,
+    cm: Lrc<SourceMap>,
+}
+
+impl GithubActionsEmitter {
+    pub fn new(dst: Box<dyn Write + Send>, cm: Lrc<SourceMap>) -> Self {
+        Self { dst, cm }
+    }
+}
+
+impl Emitter for GithubActionsEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        if let Err(e) = writeln!(self.dst, "{}", to_github_annotation(&self.cm, db)) {
+            panic!("failed to emit error: {e}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use swc_common::{
+        errors::{Diagnostic, DiagnosticId, Handler, Level},
+        sync::Lrc,
+        BytePos, FileName, SourceMap, Span,
+    };
+
+    use super::{to_github_annotation, GithubActionsEmitter};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn emits_workflow_commands() {
+        let cm = Lrc::new(SourceMap::default());
+        let fm = cm.new_source_file(
+            Lrc::new(FileName::Custom("a,b.js".into())),
+            "let a = 1;\nvar b = a;".to_string(),
+        );
+        let span =
+            |lo: u32, hi: u32| Span::new(fm.start_pos + BytePos(lo), fm.start_pos + BytePos(hi));
+
+        let buffer = Buffer::default();
+        let handler = Handler::with_emitter(
+            true,
+            false,
+            Box::new(GithubActionsEmitter::new(
+                Box::new(buffer.clone()),
+                cm.clone(),
+            )),
+        );
+
+        handler
+            .struct_span_warn_with_code(
+                span(11, 21),
+                "Unexpected var\n100% sure",
+                DiagnosticId::Lint("no-var".into()),
+            )
+            .emit();
+        handler
+            .struct_span_err_with_code(
+                span(4, 5),
+                "Unused variable",
+                DiagnosticId::Error("a:b".into()),
+            )
+            .emit();
+        handler.struct_err("Failed to lint").emit();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(
+            output.lines().collect::<Vec<_>>(),
+            vec![
+                "::warning file=a%2Cb.js,line=2,endLine=2,col=1,endColumn=11,title=no-var::Unexpected var%0A100%25 sure",
+                "::error file=a%2Cb.js,line=1,endLine=1,col=5,endColumn=6,title=a%3Ab::Unused variable",
+                "::error::Failed to lint",
+            ]
+        );
+    }
+
+    #[test]
+    fn notes_are_notices() {
+        let cm = SourceMap::default();
+
+        assert_eq!(
+            to_github_annotation(&cm, &Diagnostic::new(Level::Note, "a note")),
+            "::notice::a note"
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Diagnostics as JSON objects.
+
+use std::{
+    io::Write,
+    mem::take,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use swc_common::{
+    errors::{Diagnostic, DiagnosticBuilder, Emitter, Handler, SubDiagnostic, HANDLER},
+    sync::Lrc,
+    SourceMap,
+};
+
+use crate::{
+    snippet::render_snippet,
+    structured::{code, lookup_span, severity},
+};
+
+/// A diagnostic which is serializable.
+///
+/// Lines are 1-based and columns are 0-based, like [swc_common::Loc].
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonDiagnostic {
+    /// Error code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// The first part of the message, without the highlighted parts which
+    /// follow it.
+    pub message: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<JsonSubdiagnostic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonSubdiagnostic {
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snippet: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+impl JsonDiagnostic {
+    pub fn new(cm: &SourceMap, d: &Diagnostic) -> Self {
+        let span = d.span.primary_span();
+        let snippet = span.and_then(|span| render_snippet(cm, span));
+        let (start, end) = span.and_then(|span| lookup_span(cm, span)).unzip();
+
+        Self {
+            code: code(d).map(String::from),
+            message: d
+                .message
+                .first()
+                .map(|message| message.0.clone())
+                .unwrap_or_default(),
+            snippet,
+            filename: start.as_ref().map(|loc| loc.file.name.to_string()),
+            start_line: start.as_ref().map(|loc| loc.line),
+            start_column: start.as_ref().map(|loc| loc.col_display),
+            end_line: end.as_ref().map(|loc| loc.line),
+            end_column: end.as_ref().map(|loc| loc.col_display),
+            children: d
+                .children
+                .iter()
+                .map(|child| JsonSubdiagnostic::new(cm, child))
+                .collect(),
+        }
+    }
+}
+
+impl JsonSubdiagnostic {
+    pub fn new(cm: &SourceMap, d: &SubDiagnostic) -> Self {
+        let span = d.span.primary_span();
+        let start = span
+            .and_then(|span| lookup_span(cm, span))
+            .map(|(start, _)| start);
+
+        Self {
+            message: d.message(),
+            snippet: span.and_then(|span| render_snippet(cm, span)),
+            filename: start.as_ref().map(|loc| loc.file.name.to_string()),
+            line: start.as_ref().map(|loc| loc.line),
+        }
+    }
+}
+
+/// A [JsonDiagnostic] with its severity, which is an entry of JSON Lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonRecord {
+    /// `error`, `warning` or `note`.
+    pub level: &'static str,
+    #[serde(flatten)]
+    pub diagnostic: JsonDiagnostic,
+}
+
+impl JsonRecord {
+    pub fn new(cm: &SourceMap, d: &Diagnostic) -> Self {
+        Self {
+            level: severity(d.level),
+            diagnostic: JsonDiagnostic::new(cm, d),
+        }
+    }
+}
+
+/// Writes each diagnostic as a line of [JsonRecord], which is known as JSON
+/// Lines.
+pub struct JsonLinesEmitter {
+    dst: Box<dyn Write + Send>,
+    cm: Lrc<SourceMap>,
+}
+
+impl JsonLinesEmitter {
+    pub fn new(dst: Box<dyn Write + Send>, cm: Lrc<SourceMap>) -> Self {
+        Self { dst, cm }
+    }
+}
+
+impl Emitter for JsonLinesEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        let record = JsonRecord::new(&self.cm, db);
+
+        if let Err(e) = serde_json::to_writer(&mut self.dst, &record) {
+            panic!("failed to emit error: {e}")
+        }
+        if let Err(e) = writeln!(self.dst) {
+            panic!("failed to emit error: {e}")
+        }
+    }
+}
+
+/// Collects diagnostics as [JsonDiagnostic]s, keeping errors separate from
+/// warnings.
+///
+/// Clones share the collected diagnostics, so a clone can be passed to a
+/// [Handler] while the original is used to take them.
+#[derive(Clone)]
+pub struct JsonCollectingEmitter {
+    errors: Arc<Mutex<Vec<JsonDiagnostic>>>,
+    warnings: Arc<Mutex<Vec<JsonDiagnostic>>>,
+    cm: Lrc<SourceMap>,
+}
+
+impl JsonCollectingEmitter {
+    pub fn new(cm: Lrc<SourceMap>) -> Self {
+        Self {
+            errors: Default::default(),
+            warnings: Default::default(),
+            cm,
+        }
+    }
+
+    /// Removes and returns the collected errors.
+    pub fn take_errors(&self) -> Vec<JsonDiagnostic> {
+        take(
+            &mut *self
+                .errors
+                .lock()
+                .expect("Failed to access the diagnostics lock"),
+        )
+    }
+
+    /// Removes and returns the collected diagnostics which are not errors,
+    /// like warnings and notes.
+    pub fn take_warnings(&self) -> Vec<JsonDiagnostic> {
+        take(
+            &mut *self
+                .warnings
+                .lock()
+                .expect("Failed to access the diagnostics lock"),
+        )
+    }
+}
+
+impl Emitter for JsonCollectingEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        let diagnostic = JsonDiagnostic::new(&self.cm, db);
+        let diagnostics = if db.is_error() {
+            &self.errors
+        } else {
+            &self.warnings
+        };
+
+        diagnostics
+            .lock()
+            .expect("Failed to access the diagnostics lock")
+            .push(diagnostic);
+    }
+}
+
+/// Try operation with a [Handler] which collects diagnostics as
+/// [JsonDiagnostic]s, and returns the warnings alongside the result.
+///
+/// An error returned by `op` without emitting errors to the handler is
+/// reported as a diagnostic without span, so [Err] always contains at least
+/// one diagnostic.
+pub fn try_with_json_handler<F, Ret>(
+    cm: Lrc<SourceMap>,
+    op: F,
+) -> Result<(Ret, Vec<JsonDiagnostic>), Vec<JsonDiagnostic>>
+where
+    F: FnOnce(&Handler) -> Result<Ret, anyhow::Error>,
+{
+    let emitter = JsonCollectingEmitter::new(cm);
+    let handler = Handler::with_emitter(true, false, Box::new(emitter.clone()));
+
+    let ret = HANDLER.set(&handler, || op(&handler));
+
+    match ret {
+        Ok(ret) if !handler.has_errors() => Ok((ret, emitter.take_warnings())),
+        Ok(_) => Err(emitter.take_errors()),
+        Err(err) => {
+            if !handler.has_errors() {
+                handler.struct_err(&format!("{err:#}")).emit();
+            }
+
+            Err(emitter.take_errors())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{self, Write},
+        sync::{Arc, Mutex},
+    };
+
+    use serde_json::{json, Value};
+    use swc_common::{
+        errors::{Diagnostic, DiagnosticId, Handler, Level, Message, Style},
+        sync::Lrc,
+        BytePos, FileName, SourceMap, Span,
+    };
+
+    use super::{try_with_json_handler, JsonDiagnostic, JsonLinesEmitter};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn emits_json_lines() {
+        let cm = Lrc::new(SourceMap::default());
+        let fm = cm.new_source_file(
+            Lrc::new(FileName::Custom("a.js".into())),
+            "var a = 1;\nvar b = a;".to_string(),
+        );
+        let span =
+            |lo: u32, hi: u32| Span::new(fm.start_pos + BytePos(lo), fm.start_pos + BytePos(hi));
+
+        let buffer = Buffer::default();
+        let handler = Handler::with_emitter(
+            true,
+            false,
+            Box::new(JsonLinesEmitter::new(Box::new(buffer.clone()), cm.clone())),
+        );
+
+        handler
+            .struct_span_warn_with_code(
+                span(15, 18),
+                "Unexpected var",
+                DiagnosticId::Lint("no-var".into()),
+            )
+            .span_note(span(4, 5), "`a` is declared here")
+            .emit();
+        handler.struct_err("Failed to lint").emit();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let mut records = output
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(records.len(), 2);
+
+        // Snippets are rendered by the graphical reporter.
+        assert!(records[0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("var b = a;"));
+        assert!(records[0]["children"][0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("var a = 1;"));
+        records[0].as_object_mut().unwrap().remove("snippet");
+        records[0]["children"][0]
+            .as_object_mut()
+            .unwrap()
+            .remove("snippet");
+
+        assert_eq!(
+            records[0],
+            json!({
+                "level": "warning",
+                "code": "no-var",
+                "message": "Unexpected var",
+                "filename": "a.js",
+                "startLine": 2,
+                "startColumn": 4,
+                "endLine": 2,
+                "endColumn": 7,
+                "children": [{
+                    "message": "`a` is declared here",
+                    "filename": "a.js",
+                    "line": 1,
+                }],
+            })
+        );
+        assert_eq!(
+            records[1],
+            json!({ "level": "error", "message": "Failed to lint" })
+        );
+    }
+
+    #[test]
+    fn collects_errors_and_warnings_separately() {
+        let cm = Lrc::new(SourceMap::default());
+
+        let (ret, warnings) = try_with_json_handler(cm.clone(), |handler| {
+            handler.warn("Unused directive");
+            Ok(1)
+        })
+        .unwrap();
+        assert_eq!(ret, 1);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].message, "Unused directive");
+
+        let errors = try_with_json_handler(cm.clone(), |handler| {
+            handler.warn("Unused directive");
+            handler.err("Unexpected token");
+            Ok(())
+        })
+        .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Unexpected token");
+    }
+
+    #[test]
+    fn reports_errors_which_are_not_emitted() {
+        let cm = Lrc::new(SourceMap::default());
+
+        let errors = try_with_json_handler(cm, |_| -> anyhow::Result<()> {
+            Err(anyhow::anyhow!("invalid source map"))
+        })
+        .unwrap_err();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "invalid source map");
+        assert_eq!(errors[0].filename, None);
+    }
+
+    #[test]
+    fn uses_the_first_part_of_messages() {
+        let cm = SourceMap::default();
+        let mut d = Diagnostic::new(Level::Error, "Unexpected token");
+        d.message.push(Message(" `;`".into(), Style::Highlight));
+
+        assert_eq!(JsonDiagnostic::new(&cm, &d).message, "Unexpected token");
+    }
+}
@@ -9,7 +9,12 @@ use swc_common::{
 };
 
 mod diagnostic;
+pub mod github;
 pub mod handler;
+pub mod json;
+pub mod sarif;
+mod snippet;
+mod structured;
 pub use diagnostic::{convert_span, to_pretty_source_code, ToPrettyDiagnostic};
 
 pub struct ErrorEmitter {
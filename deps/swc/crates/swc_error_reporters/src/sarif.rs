@@ -0,0 +1,249 @@
+//! Diagnostics as a [SARIF 2.1.0](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html)
+//! log, which is consumed by code scanning tools.
+
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+use swc_common::{
+    errors::{Diagnostic, DiagnosticBuilder, Emitter},
+    sync::Lrc,
+    SourceMap,
+};
+
+use crate::structured::{code, file_uri, lookup_span, severity, utf16_col};
+
+/// The tool which reports diagnostics.
+#[derive(Debug, Clone)]
+pub struct SarifTool {
+    pub name: String,
+    pub version: Option<String>,
+    pub information_uri: Option<String>,
+}
+
+impl Default for SarifTool {
+    fn default() -> Self {
+        Self {
+            name: "swc".into(),
+            version: None,
+            information_uri: Some("https://swc.rs".into()),
+        }
+    }
+}
+
+/// Returns a SARIF log with a run of `tool`, which contains `diagnostics`.
+///
+/// Lines and columns of regions are 1-based, and columns are counted in UTF-16
+/// code units, which is the default `columnKind` of SARIF.
+pub fn to_sarif<'a>(
+    cm: &SourceMap,
+    tool: &SarifTool,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> Value {
+    let diagnostics = diagnostics.into_iter().collect::<Vec<_>>();
+
+    let mut rule_ids = diagnostics
+        .iter()
+        .filter_map(|d| code(d))
+        .collect::<Vec<_>>();
+    rule_ids.sort_unstable();
+    rule_ids.dedup();
+
+    let results = diagnostics
+        .iter()
+        .map(|d| sarif_result(cm, d))
+        .collect::<Vec<_>>();
+
+    let mut driver = json!({
+        "name": tool.name,
+        "rules": rule_ids
+            .iter()
+            .map(|id| json!({ "id": id }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(version) = &tool.version {
+        driver["version"] = version.as_str().into();
+    }
+    if let Some(information_uri) = &tool.information_uri {
+        driver["informationUri"] = information_uri.as_str().into();
+    }
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": driver },
+            "columnKind": "utf16CodeUnits",
+            "results": results,
+        }],
+    })
+}
+
+fn sarif_result(cm: &SourceMap, d: &Diagnostic) -> Value {
+    let mut result = json!({
+        "level": severity(d.level),
+        "message": { "text": d.message() },
+    });
+
+    if let Some(id) = code(d) {
+        result["ruleId"] = id.into();
+    }
+
+    if let Some((start, end)) = d.span.primary_span().and_then(|span| lookup_span(cm, span)) {
+        result["locations"] = json!([{
+            "physicalLocation": {
+                "artifactLocation": { "uri": file_uri(&start.file.name) },
+                "region": {
+                    "startLine": start.line,
+                    "startColumn": utf16_col(&start),
+                    "endLine": end.line,
+                    "endColumn": utf16_col(&end),
+                },
+            },
+        }]);
+    }
+
+    result
+}
+
+/// Collects diagnostics, which are written as a SARIF log by
+/// [SarifEmitter::to_sarif] at the end, as a log can not be streamed.
+#[derive(Clone)]
+pub struct SarifEmitter {
+    diagnostics: Arc<Mutex<Vec<Diagnostic>>>,
+    cm: Lrc<SourceMap>,
+    tool: SarifTool,
+}
+
+impl SarifEmitter {
+    pub fn new(cm: Lrc<SourceMap>, tool: SarifTool) -> Self {
+        Self {
+            diagnostics: Default::default(),
+            cm,
+            tool,
+        }
+    }
+
+    /// Returns a SARIF log of the diagnostics emitted so far.
+    pub fn to_sarif(&self) -> Value {
+        let diagnostics = self
+            .diagnostics
+            .lock()
+            .expect("Failed to access the diagnostics lock");
+
+        to_sarif(&self.cm, &self.tool, diagnostics.iter())
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit(&mut self, db: &mut DiagnosticBuilder<'_>) {
+        self.diagnostics
+            .lock()
+            .expect("Failed to access the diagnostics lock")
+            .push(db.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use swc_common::{
+        errors::{DiagnosticId, Handler},
+        sync::Lrc,
+        BytePos, FileName, SourceMap, Span,
+    };
+
+    use super::{SarifEmitter, SarifTool};
+
+    #[test]
+    fn collects_diagnostics_into_log() {
+        let cm = Lrc::new(SourceMap::default());
+        let fm = cm.new_source_file(
+            Lrc::new(FileName::Custom("a b.js".into())),
+            "let s = '\u{1F600}';\nvar a = 1;".to_string(),
+        );
+        let span = |lo: usize, hi: usize| {
+            Span::new(
+                fm.start_pos + BytePos(lo as u32),
+                fm.start_pos + BytePos(hi as u32),
+            )
+        };
+        let line = "let s = '\u{1F600}';\n".len();
+
+        let emitter = SarifEmitter::new(
+            cm.clone(),
+            SarifTool {
+                version: Some("1.0.0".into()),
+                ..Default::default()
+            },
+        );
+        let handler = Handler::with_emitter(true, false, Box::new(emitter.clone()));
+
+        handler
+            .struct_span_warn_with_code(
+                span(line, line + 3),
+                "Unexpected var",
+                DiagnosticId::Lint("no-var".into()),
+            )
+            .emit();
+        handler
+            .struct_span_err_with_code(
+                span(0, line - 1),
+                "Unexpected string",
+                DiagnosticId::Lint("no-string".into()),
+            )
+            .emit();
+        // Rules are listed once.
+        handler
+            .struct_span_warn_with_code(
+                span(line + 4, line + 5),
+                "Unexpected var",
+                DiagnosticId::Lint("no-var".into()),
+            )
+            .emit();
+        handler.struct_err("Failed to lint").emit();
+
+        let log = emitter.to_sarif();
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(
+            run["tool"]["driver"],
+            json!({
+                "name": "swc",
+                "version": "1.0.0",
+                "informationUri": "https://swc.rs",
+                "rules": [{ "id": "no-string" }, { "id": "no-var" }],
+            })
+        );
+        assert_eq!(run["columnKind"], "utf16CodeUnits");
+        assert_eq!(run["results"].as_array().unwrap().len(), 4);
+        assert_eq!(
+            run["results"][0],
+            json!({
+                "level": "warning",
+                "message": { "text": "Unexpected var" },
+                "ruleId": "no-var",
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "a%20b.js" },
+                        "region": {
+                            "startLine": 2,
+                            "startColumn": 1,
+                            "endLine": 2,
+                            "endColumn": 4,
+                        },
+                    },
+                }],
+            })
+        );
+        // The emoji is two UTF-16 code units.
+        assert_eq!(
+            run["results"][1]["locations"][0]["physicalLocation"]["region"],
+            json!({ "startLine": 1, "startColumn": 1, "endLine": 1, "endColumn": 14 })
+        );
+        assert_eq!(
+            run["results"][3],
+            json!({ "level": "error", "message": { "text": "Failed to lint" } })
+        );
+    }
+}
@@ -1,11 +1,91 @@
-use std::fmt::{self};
+use std::{
+    fmt::{self},
+    iter::once,
+};
 
 use miette::{
-    Diagnostic, GraphicalTheme, LabeledSpan, ReportHandler, SourceCode, SourceSpan, ThemeCharacters,
+    Diagnostic, GraphicalTheme, LabeledSpan, ReportHandler, SourceCode, SourceSpan,
+    ThemeCharacters, ThemeStyles,
 };
 use owo_colors::{OwoColorize, Style};
+use swc_common::{SourceMap, Span};
 use unicode_width::UnicodeWidthChar;
 
+use crate::{convert_span, to_pretty_source_code};
+
+/// Renders the source code around `span` as plain text, without the message of
+/// the diagnostic.
+pub(crate) fn render_snippet(cm: &SourceMap, span: Span) -> Option<String> {
+    let reporter = SwcReportHandler::default().with_theme(GraphicalTheme {
+        characters: ThemeCharacters {
+            hbar: ' ',
+            vbar: ' ',
+            xbar: ' ',
+            vbar_break: ' ',
+            ltop: ' ',
+            rtop: ' ',
+            mtop: ' ',
+            lbot: ' ',
+            rbot: ' ',
+            mbot: ' ',
+            error: "".into(),
+            warning: "".into(),
+            advice: "".into(),
+            ..ThemeCharacters::ascii()
+        },
+        styles: ThemeStyles::none(),
+    });
+
+    let mut snippet = String::new();
+    reporter
+        .render_report(
+            &mut snippet,
+            &Snippet {
+                source_code: &to_pretty_source_code(cm, true),
+                span,
+            },
+        )
+        .ok()?;
+
+    Some(snippet)
+}
+
+struct Snippet<'a> {
+    source_code: &'a dyn miette::SourceCode,
+    span: Span,
+}
+
+impl miette::Diagnostic for Snippet<'_> {
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        if self.span.lo().is_dummy() || self.span.hi().is_dummy() {
+            return None;
+        }
+
+        Some(self.source_code)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = miette::LabeledSpan> + '_>> {
+        Some(Box::new(once(LabeledSpan::new_with_span(
+            None,
+            convert_span(self.span),
+        ))))
+    }
+}
+
+impl std::error::Error for Snippet<'_> {}
+
+impl fmt::Display for Snippet<'_> {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Snippet<'_> {
+    fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(())
+    }
+}
+
 /**
 A [`ReportHandler`] that displays a given [`Report`](crate::Report) in a
 quasi-graphical way, using terminal colors, unicode drawing characters, and
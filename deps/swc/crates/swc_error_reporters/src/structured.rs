@@ -0,0 +1,182 @@
+//! Helpers shared by structured reporters.
+
+use swc_common::{
+    errors::{Diagnostic, DiagnosticId, Level},
+    FileName, Loc, SourceMap, Span,
+};
+
+/// `error`, `warning` or `note`, which are shared by SARIF and most of tools.
+pub(crate) fn severity(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error | Level::FailureNote => {
+            "error"
+        }
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::Cancelled => "note",
+    }
+}
+
+pub(crate) fn code(d: &Diagnostic) -> Option<&str> {
+    d.code.as_ref().map(|code| match code {
+        DiagnosticId::Error(code) | DiagnosticId::Lint(code) => &**code,
+    })
+}
+
+/// Locations of the start and the end of `span`.
+pub(crate) fn lookup_span(cm: &SourceMap, span: Span) -> Option<(Loc, Loc)> {
+    if span.is_dummy() {
+        return None;
+    }
+
+    let start = cm.try_lookup_char_pos(span.lo).ok()?;
+    let end = cm.try_lookup_char_pos(span.hi).ok()?;
+
+    Some((start, end))
+}
+
+/// The (1-based) column of `loc` in UTF-16 code units, which is the default
+/// unit of SARIF and the one of editors based on JavaScript.
+///
+/// Unlike [Loc::col_display], [Loc::col] is already counted in UTF-16 code
+/// units, as a character outside of the BMP is two [swc_common::CharPos].
+pub(crate) fn utf16_col(loc: &Loc) -> usize {
+    loc.col.0 + 1
+}
+
+/// The path of a file, which is relative to the current directory if
+/// possible.
+pub(crate) fn file_path(name: &FileName) -> String {
+    match name {
+        FileName::Real(path) => {
+            let path = std::env::current_dir()
+                .ok()
+                .and_then(|cwd| path.strip_prefix(cwd).ok())
+                .unwrap_or(path);
+
+            path.to_string_lossy().replace('\\', "/")
+        }
+        _ => name.to_string(),
+    }
+}
+
+/// The path of a file as a URI reference, which is relative to the current
+/// directory if possible, and a `file:` URL otherwise.
+pub(crate) fn file_uri(name: &FileName) -> String {
+    if let FileName::Real(path) = name {
+        if path.is_absolute() {
+            let relative = std::env::current_dir()
+                .ok()
+                .is_some_and(|cwd| path.starts_with(cwd));
+
+            if !relative {
+                if let Ok(url) = url::Url::from_file_path(path) {
+                    return url.into();
+                }
+            }
+        }
+    }
+
+    percent_encode_path(&file_path(name))
+}
+
+/// Percent-encodes the characters of `path` which can't appear in a path of a
+/// URI reference, keeping `/` as the separator of segments.
+fn percent_encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+
+    for b in path.bytes() {
+        match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'/'
+            | b'!'
+            | b'$'
+            | b'&'
+            | b'\''
+            | b'('
+            | b')'
+            | b'*'
+            | b'+'
+            | b','
+            | b';'
+            | b'='
+            | b'@' => encoded.push(b as char),
+            _ => encoded.push_str(&format!("%{b:02X}")),
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::{
+        errors::{Diagnostic, DiagnosticId, Level},
+        sync::Lrc,
+        BytePos, FileName, SourceMap, Span, DUMMY_SP,
+    };
+
+    use super::{code, file_path, file_uri, lookup_span, severity, utf16_col};
+
+    #[test]
+    fn maps_levels_to_severities() {
+        assert_eq!(severity(Level::Fatal), "error");
+        assert_eq!(severity(Level::Error), "error");
+        assert_eq!(severity(Level::Warning), "warning");
+        assert_eq!(severity(Level::Note), "note");
+        assert_eq!(severity(Level::Help), "note");
+    }
+
+    #[test]
+    fn codes_of_errors_and_lints() {
+        let error =
+            Diagnostic::new_with_code(Level::Error, Some(DiagnosticId::Error("E1".into())), "a");
+        let lint = Diagnostic::new_with_code(
+            Level::Warning,
+            Some(DiagnosticId::Lint("no-var".into())),
+            "b",
+        );
+
+        assert_eq!(code(&error), Some("E1"));
+        assert_eq!(code(&lint), Some("no-var"));
+        assert_eq!(code(&Diagnostic::new(Level::Error, "c")), None);
+    }
+
+    #[test]
+    fn counts_columns_in_utf16_code_units() {
+        let cm = SourceMap::default();
+        let fm = cm.new_source_file(Lrc::new(FileName::Anon), "a\n\u{1F600}é = 1;".to_string());
+
+        // `=` is after a character outside of the BMP and `é`.
+        let lo = fm.start_pos + BytePos("a\n\u{1F600}é ".len() as u32);
+        let (start, end) = lookup_span(&cm, Span::new(lo, lo + BytePos(1))).unwrap();
+
+        assert_eq!((start.line, utf16_col(&start)), (2, 5));
+        assert_eq!((end.line, utf16_col(&end)), (2, 6));
+        assert!(lookup_span(&cm, DUMMY_SP).is_none());
+    }
+
+    #[test]
+    fn paths_relative_to_current_dir() {
+        let cwd = std::env::current_dir().unwrap();
+        let name = FileName::Real(cwd.join("src").join("a b#.js"));
+
+        assert_eq!(file_path(&name), "src/a b#.js");
+        assert_eq!(file_uri(&name), "src/a%20b%23.js");
+        assert_eq!(file_path(&FileName::Anon), "<anon>");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn uris_of_paths_outside_current_dir() {
+        let name = FileName::Real("/nonexistent/a b.js".into());
+
+        assert_eq!(file_path(&name), "/nonexistent/a b.js");
+        assert_eq!(file_uri(&name), "file:///nonexistent/a%20b.js");
+    }
+}
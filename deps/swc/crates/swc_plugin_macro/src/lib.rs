@@ -12,19 +12,72 @@ pub fn plugin_transform(
     let token = proc_macro2::TokenStream::from(input);
     let parsed_results = syn::parse2::<SynItem>(token).expect("Failed to parse tokens");
     match parsed_results {
-        SynItem::Fn(func) => handle_func(func, Ident::new("Program", Span::call_site())),
+        SynItem::Fn(func) => handle_func(func, Ident::new("Program", Span::call_site()), false),
+        _ => panic!("Please confirm if plugin macro is specified for the function"),
+    }
+}
+
+/// Declares a lint plugin, which receives `&Program` and reports diagnostics
+/// to `HANDLER` instead of transforming the program.
+///
+/// Codes of diagnostics are names of rules, which are configured by
+/// `jsc.lints.plugins` of the host.
+#[proc_macro_attribute]
+pub fn plugin_lint(
+    _args: proc_macro::TokenStream,
+    input: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let token = proc_macro2::TokenStream::from(input);
+    let parsed_results = syn::parse2::<SynItem>(token).expect("Failed to parse tokens");
+    match parsed_results {
+        SynItem::Fn(func) => handle_func(func, Ident::new("Program", Span::call_site()), true),
         _ => panic!("Please confirm if plugin macro is specified for the function"),
     }
 }
 
 #[allow(clippy::redundant_clone)]
-fn handle_func(func: ItemFn, ast_type: Ident) -> TokenStream {
+fn handle_func(func: ItemFn, ast_type: Ident, is_lint: bool) -> TokenStream {
     let ident = func.sig.ident.clone();
     let transform_process_impl_ident =
         Ident::new("__transform_plugin_process_impl", Span::call_site());
     let transform_core_pkg_diag_ident =
         Ident::new("__get_transform_plugin_core_pkg_diag", Span::call_site());
 
+    // Lint plugins return the program they received as is.
+    let process_program = if is_lint {
+        quote! {
+            swc_core::common::errors::HANDLER.set(&handler, || {
+                #ident(&program, metadata)
+            });
+
+            send_transform_result_to_host(ast_ptr as _, ast_ptr_len);
+            0
+        }
+    } else {
+        quote! {
+            // Take original plugin fn ident, then call it with interop'ed args
+            let transformed_program = swc_core::common::plugin::serialized::VersionedSerializable::new(swc_core::common::errors::HANDLER.set(&handler, || {
+                #ident(program, metadata)
+            }));
+
+            // Serialize transformed result, return back to the host.
+            let serialized_result = swc_core::common::plugin::serialized::PluginSerializedBytes::try_serialize(
+                &transformed_program
+            );
+
+            if serialized_result.is_err() {
+                let err = swc_core::common::plugin::serialized::PluginError::Serialize("Failed to serialize transformed program".to_string());
+                return construct_error_ptr(err);
+            }
+
+            let serialized_result = serialized_result.expect("Should be a realized transformed program");
+            let (serialized_result_ptr, serialized_result_ptr_len) = serialized_result.as_ptr();
+
+            send_transform_result_to_host(serialized_result_ptr as _, serialized_result_ptr_len as u32);
+            0
+        }
+    };
+
     let ret = quote! {
         #func
 
@@ -140,26 +193,7 @@ fn handle_func(func: ItemFn, ast_type: Ident) -> TokenStream {
                 unresolved_mark: swc_core::common::Mark::from_u32(unresolved_mark as u32),
             };
 
-            // Take original plugin fn ident, then call it with interop'ed args
-            let transformed_program = swc_core::common::plugin::serialized::VersionedSerializable::new(swc_core::common::errors::HANDLER.set(&handler, || {
-                #ident(program, metadata)
-            }));
-
-            // Serialize transformed result, return back to the host.
-            let serialized_result = swc_core::common::plugin::serialized::PluginSerializedBytes::try_serialize(
-                &transformed_program
-            );
-
-            if serialized_result.is_err() {
-                let err = swc_core::common::plugin::serialized::PluginError::Serialize("Failed to serialize transformed program".to_string());
-                return construct_error_ptr(err);
-            }
-
-            let serialized_result = serialized_result.expect("Should be a realized transformed program");
-            let (serialized_result_ptr, serialized_result_ptr_len) = serialized_result.as_ptr();
-
-            send_transform_result_to_host(serialized_result_ptr as _, serialized_result_ptr_len as u32);
-            0
+            #process_program
         }
     };
 
@@ -0,0 +1,256 @@
+use std::borrow::Cow;
+
+use crate::{
+    builder::SourceMapBuilder,
+    errors::Result,
+    types::{DecodedMap, SourceMap, Token},
+};
+
+impl DecodedMap {
+    /// Converts this into a regular sourcemap.
+    ///
+    /// Index maps are flattened, which requires that all referenced
+    /// sourcemaps are attached.
+    pub fn flatten(&self) -> Result<Cow<'_, SourceMap>> {
+        match self {
+            DecodedMap::Regular(sm) => Ok(Cow::Borrowed(sm)),
+            DecodedMap::Index(smi) => Ok(Cow::Owned(smi.flatten()?)),
+            DecodedMap::Hermes(smh) => Ok(Cow::Borrowed(&smh.sm)),
+        }
+    }
+}
+
+/// Composes a chain of sourcemaps into one.
+///
+/// `maps` should be ordered from the first transformation to the last one,
+/// e.g. `[ts_to_js, js_to_minified, minified_to_bundled]`, and the result maps
+/// the generated file of the last map to the original sources.
+///
+/// Each source is composed separately: the input map of a source is the
+/// latest preceding map whose `file` is the name of the source, like
+/// [`SourceMap::adjust_mappings_from_multiple`]. If there is none, but the
+/// previous map has no `file` and the map has only one source, the previous
+/// map is used, so a plain chain of maps does not require `file`. Sources
+/// without an input map are original sources.
+///
+/// Each mapping of the last map is traced back to the original sources.
+/// Mappings which cannot be traced, because an input map has no mapping on
+/// the same line, are dropped.
+///
+/// - Names are taken from the earliest map which has a name for the mapping.
+/// - A source is in the ignore list if any of the maps ignores it, or a
+///   generated file it was traced through.
+/// - `file` and the debug id are taken from the last map, and source contents
+///   from the map of the original source.
+pub fn compose(maps: &[DecodedMap]) -> Result<SourceMap> {
+    let maps = maps
+        .iter()
+        .map(DecodedMap::flatten)
+        .collect::<Result<Vec<_>>>()?;
+
+    let Some(last) = maps.last() else {
+        return Ok(SourceMap::new(None, vec![], vec![], vec![], None));
+    };
+    if maps.len() == 1 {
+        return Ok(last.clone().into_owned());
+    }
+
+    let mut builder = SourceMapBuilder::new(last.get_file().cloned());
+    builder.set_debug_id(last.get_debug_id());
+
+    for token in last.tokens() {
+        let Some(traced) = trace(&maps, token) else {
+            continue;
+        };
+
+        let original = traced.token;
+        let Some(source) = original.get_source() else {
+            continue;
+        };
+
+        let src_id = builder.add_source(source.clone());
+        builder.set_source_contents(
+            src_id,
+            original
+                .sourcemap()
+                .get_source_contents(original.get_src_id())
+                .cloned(),
+        );
+        if traced.ignored {
+            builder.add_to_ignore_list(src_id);
+        }
+
+        let name_id = traced.name.map(|name| builder.add_name(name.clone()));
+
+        builder.add_raw(
+            token.get_dst_line(),
+            token.get_dst_col(),
+            original.get_src_line(),
+            original.get_src_col(),
+            Some(src_id),
+            name_id,
+            false,
+        );
+    }
+
+    Ok(builder.into_sourcemap())
+}
+
+struct Traced<'a> {
+    /// The token of the map of the original source.
+    token: Token<'a>,
+    name: Option<&'a bytes_str::BytesStr>,
+    ignored: bool,
+}
+
+/// Traces `token` of the last map back to its original source.
+fn trace<'a>(maps: &'a [Cow<'a, SourceMap>], token: Token<'a>) -> Option<Traced<'a>> {
+    let mut traced = Traced {
+        token,
+        name: token.get_name(),
+        ignored: is_ignored(&token),
+    };
+    let mut idx = maps.len() - 1;
+
+    loop {
+        let source = traced.token.get_source()?;
+        let Some(input_idx) = input_map(maps, idx, source) else {
+            return Some(traced);
+        };
+
+        let (line, col) = traced.token.get_src();
+
+        let prev = maps[input_idx].lookup_token(line, col)?;
+        if prev.get_dst_line() != line || !prev.has_source() {
+            return None;
+        }
+
+        if let Some(name) = prev.get_name() {
+            traced.name = Some(name);
+        }
+        traced.ignored |= is_ignored(&prev);
+        traced.token = prev;
+        idx = input_idx;
+    }
+}
+
+/// Finds the index of the map which generated `source` of `maps[idx]`.
+fn input_map(maps: &[Cow<'_, SourceMap>], idx: usize, source: &str) -> Option<usize> {
+    let inputs = &maps[..idx];
+
+    inputs
+        .iter()
+        .rposition(|map| map.get_file().is_some_and(|file| file == source))
+        .or_else(|| {
+            let prev = inputs.last()?;
+            (prev.get_file().is_none() && maps[idx].get_source_count() == 1).then(|| idx - 1)
+        })
+}
+
+fn is_ignored(token: &Token<'_>) -> bool {
+    token.sourcemap().ignore_list.contains(&token.get_src_id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compose;
+    use crate::{builder::SourceMapBuilder, types::DecodedMap, SourceMap};
+
+    /// `(dst_line, dst_col, source, src_line, src_col, name)`
+    type Mapping<'a> = (u32, u32, &'a str, u32, u32, Option<&'a str>);
+
+    fn map(file: Option<&'static str>, tokens: &[Mapping<'static>]) -> DecodedMap {
+        let mut builder = SourceMapBuilder::new(file.map(Into::into));
+        for &(dst_line, dst_col, source, src_line, src_col, name) in tokens {
+            builder.add(
+                dst_line,
+                dst_col,
+                src_line,
+                src_col,
+                Some(source.into()),
+                name.map(Into::into),
+                false,
+            );
+        }
+        DecodedMap::Regular(builder.into_sourcemap())
+    }
+
+    fn mappings(map: &SourceMap) -> Vec<Mapping<'_>> {
+        map.tokens()
+            .map(|token| {
+                (
+                    token.get_dst_line(),
+                    token.get_dst_col(),
+                    token.get_source().map(|s| &**s).unwrap_or_default(),
+                    token.get_src_line(),
+                    token.get_src_col(),
+                    token.get_name().map(|s| &**s),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn composes_chain_without_file() {
+        let ts = map(
+            None,
+            &[
+                (0, 0, "a.ts", 0, 0, None),
+                (0, 6, "a.ts", 0, 6, Some("foo")),
+            ],
+        );
+        let min = map(
+            Some("a.min.js"),
+            &[(0, 0, "a.js", 0, 0, None), (0, 4, "a.js", 0, 6, None)],
+        );
+
+        let composed = compose(&[ts, min]).unwrap();
+
+        assert_eq!(composed.get_file().map(|s| &**s), Some("a.min.js"));
+        assert_eq!(
+            mappings(&composed),
+            vec![
+                (0, 0, "a.ts", 0, 0, None),
+                (0, 4, "a.ts", 0, 6, Some("foo"))
+            ]
+        );
+    }
+
+    #[test]
+    fn composes_each_source_with_its_input_map() {
+        let a = map(Some("a.js"), &[(0, 0, "a.ts", 2, 0, None)]);
+        let b = map(Some("b.js"), &[(0, 0, "b.ts", 5, 4, Some("b"))]);
+        let bundle = map(
+            Some("bundle.js"),
+            &[
+                (0, 0, "a.js", 0, 0, None),
+                (1, 0, "b.js", 0, 0, None),
+                (2, 0, "c.js", 3, 0, None),
+            ],
+        );
+
+        let composed = compose(&[a, b, bundle]).unwrap();
+
+        assert_eq!(
+            mappings(&composed),
+            vec![
+                (0, 0, "a.ts", 2, 0, None),
+                (1, 0, "b.ts", 5, 4, Some("b")),
+                (2, 0, "c.js", 3, 0, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_mappings_missing_from_input_map() {
+        let a = map(Some("a.js"), &[(0, 0, "a.ts", 0, 0, None)]);
+        let bundle = map(
+            Some("bundle.js"),
+            &[(0, 0, "a.js", 0, 0, None), (1, 0, "a.js", 1, 0, None)],
+        );
+
+        let composed = compose(&[a, bundle]).unwrap();
+
+        assert_eq!(mappings(&composed), vec![(0, 0, "a.ts", 0, 0, None)]);
+    }
+}
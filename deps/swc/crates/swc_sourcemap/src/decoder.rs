@@ -273,7 +273,7 @@ pub fn decode_regular(rsm: RawSourceMap) -> Result<SourceMap> {
     // Use _debug_id_new (from "debugId" key) only if debug_id
     // from ( "debug_id" key) is unset
     sm.set_debug_id(rsm.debug_id.or(rsm._debug_id_new));
-    if let Some(ignore_list) = rsm.ignore_list {
+    if let Some(ignore_list) = rsm.ignore_list.or(rsm.x_google_ignore_list) {
         for idx in ignore_list {
             sm.add_to_ignore_list(idx);
         }
@@ -421,6 +421,7 @@ mod tests {
             range_mappings: None,
             mappings: None,
             ignore_list: None,
+            x_google_ignore_list: None,
             x_facebook_offsets: None,
             x_metro_module_paths: None,
             x_facebook_sources: None,
@@ -451,6 +452,7 @@ mod tests {
             range_mappings: None,
             mappings: None,
             ignore_list: None,
+            x_google_ignore_list: None,
             x_facebook_offsets: None,
             x_metro_module_paths: None,
             x_facebook_sources: None,
@@ -484,6 +486,7 @@ mod tests {
             range_mappings: None,
             mappings: None,
             ignore_list: None,
+            x_google_ignore_list: None,
             x_facebook_offsets: None,
             x_metro_module_paths: None,
             x_facebook_sources: None,
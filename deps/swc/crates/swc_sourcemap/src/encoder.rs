@@ -160,6 +160,11 @@ impl Encodable for SourceMap {
                 }
             })
             .collect();
+        let ignore_list: Option<Vec<u32>> = if self.ignore_list.is_empty() {
+            None
+        } else {
+            Some(self.ignore_list.iter().cloned().collect())
+        };
         RawSourceMap {
             version: Some(3),
             file: self.get_file().map(|x| Value::String(x.to_string())),
@@ -171,11 +176,8 @@ impl Encodable for SourceMap {
             scopes: self.get_scopes().map(|x| x.to_string()),
             range_mappings: serialize_range_mappings(self),
             mappings: Some(serialize_mappings(self)),
-            ignore_list: if self.ignore_list.is_empty() {
-                None
-            } else {
-                Some(self.ignore_list.iter().cloned().collect())
-            },
+            x_google_ignore_list: ignore_list.clone().filter(|_| self.x_google_ignore_list),
+            ignore_list,
             x_facebook_offsets: None,
             x_metro_module_paths: None,
             x_facebook_sources: None,
@@ -212,6 +214,7 @@ impl Encodable for SourceMapIndex {
             range_mappings: None,
             mappings: None,
             ignore_list: None,
+            x_google_ignore_list: None,
             x_facebook_offsets: None,
             x_metro_module_paths: None,
             x_facebook_sources: None,
@@ -278,6 +281,7 @@ mod tests {
                 range_mappings: None,
                 mappings: None,
                 ignore_list: None,
+                x_google_ignore_list: None,
                 x_facebook_offsets: None,
                 x_metro_module_paths: None,
                 x_facebook_sources: None,
@@ -309,6 +313,7 @@ mod tests {
                 range_mappings: None,
                 mappings: None,
                 ignore_list: None,
+                x_google_ignore_list: None,
                 x_facebook_offsets: None,
                 x_metro_module_paths: None,
                 x_facebook_sources: None,
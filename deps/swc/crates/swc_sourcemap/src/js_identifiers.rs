@@ -1,5 +1,5 @@
 /// Returns true if `c` is a valid character for an identifier start.
-fn is_valid_start(c: char) -> bool {
+pub(crate) fn is_valid_start(c: char) -> bool {
     c == '$' || c == '_' || c.is_ascii_alphabetic() || {
         if c.is_ascii() {
             false
@@ -10,7 +10,7 @@ fn is_valid_start(c: char) -> bool {
 }
 
 /// Returns true if `c` is a valid character for an identifier part after start.
-fn is_valid_continue(c: char) -> bool {
+pub(crate) fn is_valid_continue(c: char) -> bool {
     // As specified by the ECMA-262 spec, U+200C (ZERO WIDTH NON-JOINER) and U+200D
     // (ZERO WIDTH JOINER) are format-control characters that are used to make
     // necessary distinctions when forming words or phrases in certain
@@ -50,6 +50,13 @@ pub struct RawSourceMap {
     pub mappings: Option<String>,
     #[serde(rename = "ignoreList", skip_serializing_if = "Option::is_none")]
     pub ignore_list: Option<Vec<u32>>,
+    /// Same as `ignore_list`, which is emitted for devtools which do not support
+    /// `ignoreList` yet.
+    #[serde(
+        rename = "x_google_ignoreList",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub x_google_ignore_list: Option<Vec<u32>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub x_facebook_offsets: Option<Vec<Option<u32>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -46,6 +46,7 @@
 //! * `ram_bundle`: turns on RAM bundle support
 pub use crate::{
     builder::SourceMapBuilder,
+    compose::compose,
     decoder::{decode, decode_data_url, decode_slice},
     detector::{
         is_sourcemap, is_sourcemap_slice, locate_sourcemap_reference,
@@ -59,9 +60,11 @@ pub use crate::{
         SourceMapIndex, SourceMapSection, SourceMapSectionIter, Token, TokenIter,
     },
     utils::make_relative_path,
+    validate::{Issue, IssueKind, Validator},
 };
 
 mod builder;
+mod compose;
 mod decoder;
 mod detector;
 mod encoder;
@@ -73,6 +76,7 @@ pub mod lazy;
 mod sourceview;
 mod types;
 mod utils;
+mod validate;
 
 #[cfg(feature = "ram_bundle")]
 pub mod ram_bundle;
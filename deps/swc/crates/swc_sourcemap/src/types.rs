@@ -506,6 +506,7 @@ pub struct SourceMap {
     pub(crate) sources_prefixed: Option<Vec<BytesStr>>,
     pub(crate) sources_content: Vec<Option<SourceView>>,
     pub(crate) ignore_list: BTreeSet<u32>,
+    pub(crate) x_google_ignore_list: bool,
     pub(crate) debug_id: Option<DebugId>,
 }
 
@@ -633,6 +634,7 @@ impl SourceMap {
                 .map(|opt| opt.map(SourceView::new))
                 .collect(),
             ignore_list: BTreeSet::default(),
+            x_google_ignore_list: false,
             debug_id: None,
         }
     }
@@ -711,6 +713,12 @@ impl SourceMap {
         self.ignore_list.iter()
     }
 
+    /// Also writes the ignore list as `x_google_ignoreList`, which is read by
+    /// devtools that do not support `ignoreList` yet.
+    pub fn set_x_google_ignore_list(&mut self, value: bool) {
+        self.x_google_ignore_list = value;
+    }
+
     /// Looks up a token by its index.
     pub fn get_token(&self, idx: usize) -> Option<Token<'_>> {
         self.tokens.get(idx).map(|raw| Token {
@@ -1509,6 +1517,10 @@ impl SourceMapIndex {
 
                 src_id_map.push(src_id);
 
+                if map.ignore_list.contains(&(original_id as u32)) {
+                    builder.add_to_ignore_list(src_id);
+                }
+
                 if let Some(contents) = contents {
                     builder.set_source_contents(src_id, Some(contents.clone()));
                 }
@@ -1545,7 +1557,7 @@ impl SourceMapIndex {
                     name_id_map.get(original_name_id as usize).copied()
                 };
 
-                let raw = builder.add_raw(
+                builder.add_raw(
                     token.get_dst_line() + off_line,
                     dst_col,
                     token.get_src_line(),
@@ -1554,10 +1566,6 @@ impl SourceMapIndex {
                     name_id,
                     token.is_range(),
                 );
-
-                if map.ignore_list.contains(&token.get_src_id()) {
-                    builder.add_to_ignore_list(raw.src_id);
-                }
             }
         }
 
@@ -1910,6 +1918,7 @@ mod tests {
             sources_prefixed: None,
             sources_content: vec![],
             ignore_list: BTreeSet::new(),
+            x_google_ignore_list: false,
             debug_id: None,
         });
 
@@ -1929,6 +1938,7 @@ mod tests {
                 sources_prefixed: None,
                 sources_content: vec![],
                 ignore_list: BTreeSet::new(),
+                x_google_ignore_list: false,
                 debug_id: Some(DEBUG_ID.parse().expect("valid debug id")),
             })
         );
@@ -0,0 +1,545 @@
+//! Validation and visualization of sourcemaps.
+//!
+//! ```ignore
+//! let validator = Validator::new(map.as_bytes(), &generated_code)?;
+//! assert_eq!(validator.validate(), vec![], "{}", validator.visualize());
+//! ```
+
+use std::fmt::{self, Write};
+
+use bytes_str::BytesStr;
+
+use crate::{
+    decoder::decode_slice,
+    errors::Result,
+    js_identifiers::{is_valid_continue, is_valid_start},
+    jsontypes::RawSourceMap,
+    sourceview::SourceView,
+    types::{SourceMap, Token},
+    vlq::parse_vlq_segment,
+};
+
+/// Maximum number of characters of a snippet in [Validator::visualize].
+const MAX_SNIPPET_LEN: usize = 24;
+
+/// Checks a sourcemap against the generated code and the original sources.
+pub struct Validator {
+    map: SourceMap,
+    /// Raw `mappings` of the input, because tokens are sorted while decoding.
+    mappings: Option<String>,
+    generated: SourceView,
+    sources: Vec<Option<SourceView>>,
+}
+
+/// A problem found by [Validator::validate].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub kind: IssueKind,
+    /// 0-based line of the mapping in the generated code.
+    pub dst_line: u32,
+    /// 0-based column of the mapping in the generated code, in UTF-16 code
+    /// units.
+    pub dst_col: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IssueKind {
+    /// The generated position is outside of the generated code.
+    GeneratedOutOfRange,
+    /// The original position is outside of the original source.
+    OriginalOutOfRange {
+        src_id: u32,
+        src_line: u32,
+        src_col: u32,
+    },
+    /// The generated position is in the middle of an identifier.
+    GeneratedMidIdentifier,
+    /// The original position is in the middle of an identifier.
+    OriginalMidIdentifier {
+        src_id: u32,
+        src_line: u32,
+        src_col: u32,
+    },
+    /// The generated column is between the UTF-16 code units of a surrogate
+    /// pair.
+    GeneratedMidSurrogatePair,
+    /// The original column is between the UTF-16 code units of a surrogate
+    /// pair.
+    OriginalMidSurrogatePair {
+        src_id: u32,
+        src_line: u32,
+        src_col: u32,
+    },
+    /// The generated column of a segment is less than the one of the
+    /// previous segment on the same line.
+    NonMonotonic,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.dst_line + 1, self.dst_col)?;
+
+        match &self.kind {
+            IssueKind::GeneratedOutOfRange => {
+                write!(f, "generated position is out of range")
+            }
+            IssueKind::OriginalOutOfRange {
+                src_id,
+                src_line,
+                src_col,
+            } => write!(
+                f,
+                "original position {}:{} of source {} is out of range",
+                src_line + 1,
+                src_col,
+                src_id
+            ),
+            IssueKind::GeneratedMidIdentifier => {
+                write!(f, "generated position is in the middle of an identifier")
+            }
+            IssueKind::OriginalMidIdentifier {
+                src_id,
+                src_line,
+                src_col,
+            } => write!(
+                f,
+                "original position {}:{} of source {} is in the middle of an identifier",
+                src_line + 1,
+                src_col,
+                src_id
+            ),
+            IssueKind::GeneratedMidSurrogatePair => {
+                write!(f, "generated position is in the middle of a surrogate pair")
+            }
+            IssueKind::OriginalMidSurrogatePair {
+                src_id,
+                src_line,
+                src_col,
+            } => write!(
+                f,
+                "original position {}:{} of source {} is in the middle of a surrogate pair",
+                src_line + 1,
+                src_col,
+                src_id
+            ),
+            IssueKind::NonMonotonic => {
+                write!(f, "segment is before the previous segment")
+            }
+        }
+    }
+}
+
+impl Validator {
+    /// Creates a validator for the sourcemap `map` of `generated`.
+    ///
+    /// Index maps are flattened. The original sources are read from
+    /// `sourcesContent`, and can be overridden with
+    /// [Validator::set_source_contents].
+    pub fn new(map: &[u8], generated: &str) -> Result<Self> {
+        let raw: RawSourceMap = serde_json::from_slice(map)?;
+        let map = decode_slice(map)?.flatten()?.into_owned();
+
+        let sources = (0..map.get_source_count())
+            .map(|idx| {
+                map.get_source_contents(idx)
+                    .map(|contents| SourceView::new(contents.clone()))
+            })
+            .collect();
+
+        Ok(Validator {
+            map,
+            mappings: raw.mappings,
+            generated: SourceView::new(BytesStr::from(generated.to_string())),
+            sources,
+        })
+    }
+
+    /// Sets the original source of `src_id`.
+    pub fn set_source_contents(&mut self, src_id: u32, contents: &str) {
+        if let Some(source) = self.sources.get_mut(src_id as usize) {
+            *source = Some(SourceView::new(BytesStr::from(contents.to_string())));
+        }
+    }
+
+    /// Returns the parsed sourcemap.
+    pub fn sourcemap(&self) -> &SourceMap {
+        &self.map
+    }
+
+    /// Returns all problems of the sourcemap, ordered by the generated
+    /// position.
+    ///
+    /// Original positions are checked only if the original source is known.
+    pub fn validate(&self) -> Vec<Issue> {
+        let mut issues = self.check_monotonic();
+
+        for token in self.map.tokens() {
+            let issue = |kind| Issue {
+                kind,
+                dst_line: token.get_dst_line(),
+                dst_col: token.get_dst_col(),
+            };
+
+            match split_line(&self.generated, token.get_dst_line(), token.get_dst_col()) {
+                Ok((before, after)) => {
+                    if is_mid_identifier(before, after) {
+                        issues.push(issue(IssueKind::GeneratedMidIdentifier));
+                    }
+                }
+                Err(SplitError::OutOfRange) => issues.push(issue(IssueKind::GeneratedOutOfRange)),
+                Err(SplitError::MidSurrogatePair) => {
+                    issues.push(issue(IssueKind::GeneratedMidSurrogatePair))
+                }
+            }
+
+            let Some(source) = self.source_of(&token) else {
+                continue;
+            };
+
+            let (src_id, src_line, src_col) = (
+                token.get_src_id(),
+                token.get_src_line(),
+                token.get_src_col(),
+            );
+
+            match split_line(source, src_line, src_col) {
+                Ok((before, after)) => {
+                    if is_mid_identifier(before, after) {
+                        issues.push(issue(IssueKind::OriginalMidIdentifier {
+                            src_id,
+                            src_line,
+                            src_col,
+                        }));
+                    }
+                }
+                Err(SplitError::OutOfRange) => issues.push(issue(IssueKind::OriginalOutOfRange {
+                    src_id,
+                    src_line,
+                    src_col,
+                })),
+                Err(SplitError::MidSurrogatePair) => {
+                    issues.push(issue(IssueKind::OriginalMidSurrogatePair {
+                        src_id,
+                        src_line,
+                        src_col,
+                    }))
+                }
+            }
+        }
+
+        issues.sort_by_key(|issue| (issue.dst_line, issue.dst_col));
+        issues
+    }
+
+    /// Renders each mapping as a line, with the generated code at the mapping
+    /// on the left side and the original code on the right side.
+    ///
+    /// ```text
+    /// 1:0    var E = /*#__PURE__*/    | index.ts:1:0    enum E { A }
+    /// ```
+    pub fn visualize(&self) -> String {
+        let mut out = String::new();
+        let mut tokens = self.map.tokens().peekable();
+
+        while let Some(token) = tokens.next() {
+            // Up to the next mapping on the same line.
+            let len = tokens
+                .peek()
+                .filter(|next| next.get_dst_line() == token.get_dst_line())
+                .map(|next| next.get_dst_col().saturating_sub(token.get_dst_col()));
+
+            let generated = snippet(
+                &self.generated,
+                token.get_dst_line(),
+                token.get_dst_col(),
+                len,
+            );
+            let dst = format!("{}:{}", token.get_dst_line() + 1, token.get_dst_col());
+
+            let _ = write!(
+                out,
+                "{dst:<8}{generated:<width$} | ",
+                width = MAX_SNIPPET_LEN
+            );
+
+            match token.get_source() {
+                Some(name) => {
+                    let src = format!(
+                        "{}:{}:{}",
+                        name,
+                        token.get_src_line() + 1,
+                        token.get_src_col()
+                    );
+                    let _ = write!(out, "{src:<16}");
+
+                    if let Some(source) = self.source_of(&token) {
+                        out.push_str(&snippet(
+                            source,
+                            token.get_src_line(),
+                            token.get_src_col(),
+                            None,
+                        ));
+                    }
+                }
+                None => out.push_str("(unmapped)"),
+            }
+
+            if let Some(name) = token.get_name() {
+                let _ = write!(out, " ({name})");
+            }
+
+            out.truncate(out.trim_end().len());
+            out.push('\n');
+        }
+
+        out
+    }
+
+    fn source_of(&self, token: &Token<'_>) -> Option<&SourceView> {
+        if !token.has_source() {
+            return None;
+        }
+
+        self.sources.get(token.get_src_id() as usize)?.as_ref()
+    }
+
+    fn check_monotonic(&self) -> Vec<Issue> {
+        let mut issues = vec![];
+
+        let Some(mappings) = &self.mappings else {
+            return issues;
+        };
+
+        for (dst_line, line) in mappings.split(';').enumerate() {
+            let mut dst_col = 0i64;
+
+            for (idx, segment) in line.split(',').enumerate() {
+                let Some(&delta) = parse_vlq_segment(segment)
+                    .ok()
+                    .as_ref()
+                    .and_then(|nums| nums.first())
+                else {
+                    continue;
+                };
+
+                dst_col += delta;
+
+                if idx != 0 && delta < 0 {
+                    issues.push(Issue {
+                        kind: IssueKind::NonMonotonic,
+                        dst_line: dst_line as u32,
+                        dst_col: dst_col.max(0) as u32,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SplitError {
+    OutOfRange,
+    MidSurrogatePair,
+}
+
+/// Splits a line at a column in UTF-16 code units.
+fn split_line(
+    sv: &SourceView,
+    line: u32,
+    col: u32,
+) -> std::result::Result<(&str, &str), SplitError> {
+    let line = sv.get_line(line).ok_or(SplitError::OutOfRange)?;
+    let col = col as usize;
+
+    let mut idx = 0;
+    for (offset, c) in line.char_indices() {
+        if idx == col {
+            return Ok(line.split_at(offset));
+        }
+        idx += c.len_utf16();
+        if idx > col {
+            return Err(SplitError::MidSurrogatePair);
+        }
+    }
+
+    if idx == col {
+        Ok((line, ""))
+    } else {
+        Err(SplitError::OutOfRange)
+    }
+}
+
+/// Returns `true` if the position between `before` and `after` is in the
+/// middle of an identifier.
+fn is_mid_identifier(before: &str, after: &str) -> bool {
+    if !after.chars().next().is_some_and(is_valid_continue) {
+        return false;
+    }
+
+    // The start of the word which ends at the position.
+    match before
+        .chars()
+        .rev()
+        .take_while(|&c| is_valid_continue(c))
+        .last()
+    {
+        Some(start) => is_valid_start(start),
+        None => false,
+    }
+}
+
+/// Returns the text at a position, up to `len` UTF-16 code units.
+fn snippet(sv: &SourceView, line: u32, col: u32, len: Option<u32>) -> String {
+    let after = match split_line(sv, line, col) {
+        Ok((_, after)) => after,
+        Err(SplitError::OutOfRange) => return "(out of range)".to_string(),
+        Err(SplitError::MidSurrogatePair) => return "(in a surrogate pair)".to_string(),
+    };
+
+    let mut out = String::new();
+    let mut idx = 0;
+    for c in after.chars() {
+        if len.is_some_and(|len| idx >= len as usize) || out.chars().count() >= MAX_SNIPPET_LEN {
+            break;
+        }
+        idx += c.len_utf16();
+
+        match c {
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes_str::BytesStr;
+
+    use super::{split_line, Issue, IssueKind, SplitError, Validator};
+    use crate::sourceview::SourceView;
+
+    fn validator(generated: &str, original: &str, mappings: &str) -> Validator {
+        let map = serde_json::json!({
+            "version": 3,
+            "sources": ["a.ts"],
+            "sourcesContent": [original],
+            "names": [],
+            "mappings": mappings,
+        });
+
+        Validator::new(map.to_string().as_bytes(), generated).unwrap()
+    }
+
+    fn issue(kind: IssueKind, dst_line: u32, dst_col: u32) -> Issue {
+        Issue {
+            kind,
+            dst_line,
+            dst_col,
+        }
+    }
+
+    #[test]
+    fn test_split_line() {
+        let sv = SourceView::new(BytesStr::from_static("a\u{1F600}b\nc"));
+
+        assert_eq!(split_line(&sv, 0, 0), Ok(("", "a\u{1F600}b")));
+        assert_eq!(split_line(&sv, 0, 1), Ok(("a", "\u{1F600}b")));
+        assert_eq!(split_line(&sv, 0, 2), Err(SplitError::MidSurrogatePair));
+        assert_eq!(split_line(&sv, 0, 3), Ok(("a\u{1F600}", "b")));
+        assert_eq!(split_line(&sv, 0, 4), Ok(("a\u{1F600}b", "")));
+        assert_eq!(split_line(&sv, 0, 5), Err(SplitError::OutOfRange));
+        assert_eq!(split_line(&sv, 2, 0), Err(SplitError::OutOfRange));
+    }
+
+    #[test]
+    fn test_valid() {
+        let v = validator("let a = 1;", "let a: number = 1;", "AAAA,IAAI,IAAY");
+
+        assert_eq!(v.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_out_of_range() {
+        let v = validator("a;", "a;", "AACA;AADA");
+
+        assert_eq!(
+            v.validate(),
+            vec![
+                issue(
+                    IssueKind::OriginalOutOfRange {
+                        src_id: 0,
+                        src_line: 1,
+                        src_col: 0
+                    },
+                    0,
+                    0
+                ),
+                issue(IssueKind::GeneratedOutOfRange, 1, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mid_identifier() {
+        let v = validator("foo;", "foo;", "CAAC");
+
+        assert_eq!(
+            v.validate(),
+            vec![
+                issue(IssueKind::GeneratedMidIdentifier, 0, 1),
+                issue(
+                    IssueKind::OriginalMidIdentifier {
+                        src_id: 0,
+                        src_line: 0,
+                        src_col: 1
+                    },
+                    0,
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mid_surrogate_pair() {
+        let v = validator("'\u{1F600}';", "'\u{1F600}';", "EAAE,CAAC");
+
+        assert_eq!(
+            v.validate(),
+            vec![
+                issue(IssueKind::GeneratedMidSurrogatePair, 0, 2),
+                issue(
+                    IssueKind::OriginalMidSurrogatePair {
+                        src_id: 0,
+                        src_line: 0,
+                        src_col: 2
+                    },
+                    0,
+                    2
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_non_monotonic() {
+        let v = validator("a b c", "a b c", "EAAE,DAAD");
+
+        assert_eq!(v.validate(), vec![issue(IssueKind::NonMonotonic, 0, 1)]);
+    }
+
+    #[test]
+    fn test_visualize() {
+        let v = validator("let a = 1;", "let a: number = 1;", "AAAA,IAAI,IAAY");
+
+        assert_eq!(
+            v.visualize(),
+            "1:0     let                      | a.ts:1:0        let a: number = 1;\n1:4     a =                      \
+             | a.ts:1:4        a: number = 1;\n1:8     1;                       | a.ts:1:16       1;\n"
+        );
+    }
+}
@@ -1,26 +1,31 @@
 use std::fmt::Display;
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use bytes_str::BytesStr;
+use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
 use swc_common::{
-    comments::SingleThreadedComments,
-    errors::{DiagnosticId, Handler, HANDLER},
-    source_map::DefaultSourceMapGenConfig,
+    comments::{Comments, SingleThreadedComments},
+    errors::{DiagnosticId, Handler, Level, HANDLER},
+    source_map::{DefaultSourceMapGenConfig, SourceMapGenConfig},
     sync::Lrc,
     BytePos, FileName, Mark, SourceMap, Span, Spanned,
 };
 use swc_ecma_ast::{
-    ArrayPat, ArrowExpr, AutoAccessor, BinaryOp, BindingIdent, Class, ClassDecl, ClassMethod,
-    ClassProp, Constructor, Decl, DefaultDecl, DoWhileStmt, EsVersion, ExportAll, ExportDecl,
-    ExportDefaultDecl, ExportSpecifier, Expr, FnDecl, ForInStmt, ForOfStmt, ForStmt, GetterProp,
-    IfStmt, ImportDecl, ImportSpecifier, ModuleDecl, ModuleItem, NamedExport, ObjectPat, Param,
-    Pat, PrivateMethod, PrivateProp, Program, ReturnStmt, SetterProp, Stmt, ThrowStmt, TsAsExpr,
-    TsConstAssertion, TsEnumDecl, TsExportAssignment, TsImportEqualsDecl, TsIndexSignature,
-    TsInstantiation, TsModuleDecl, TsModuleName, TsNamespaceBody, TsNonNullExpr, TsParamPropParam,
-    TsSatisfiesExpr, TsTypeAliasDecl, TsTypeAnn, TsTypeAssertion, TsTypeParamDecl,
-    TsTypeParamInstantiation, VarDeclarator, WhileStmt, YieldExpr,
+    ArrayPat, ArrowExpr, AutoAccessor, BinaryOp, BindingIdent, CallExpr, Class, ClassDecl,
+    ClassMethod, ClassProp, Constructor, Decl, DefaultDecl, DoWhileStmt, EsVersion, ExportAll,
+    ExportDecl, ExportDefaultDecl, ExportSpecifier, Expr, FnDecl, ForInStmt, ForOfStmt, ForStmt,
+    GetterProp, Ident, IdentName, IfStmt, ImportDecl, ImportSpecifier, Lit, ModuleDecl, ModuleItem,
+    NamedExport, ObjectPat, Param, Pat, PrivateMethod, PrivateProp, Program, ReturnStmt,
+    SetterProp, Stmt, Str, ThrowStmt, TsAsExpr, TsConstAssertion, TsEnumDecl, TsExportAssignment,
+    TsExternalModuleRef, TsImportEqualsDecl, TsIndexSignature, TsInstantiation, TsModuleDecl,
+    TsModuleName, TsNamespaceBody, TsNonNullExpr, TsParamPropParam, TsSatisfiesExpr,
+    TsTypeAliasDecl, TsTypeAnn, TsTypeAssertion, TsTypeParamDecl, TsTypeParamInstantiation,
+    VarDeclarator, WhileStmt, YieldExpr,
 };
+use swc_ecma_codegen::preserve::{with_original_source, OriginalSource};
+#[cfg(feature = "flow")]
+use swc_ecma_parser::FlowSyntax;
 use swc_ecma_parser::{
     lexer::Lexer,
     unstable::{Capturing, Token, TokenAndSpan},
@@ -32,11 +37,23 @@ use swc_ecma_transforms_base::{
     hygiene::hygiene,
     resolver,
 };
+use swc_ecma_transforms_proposal::decorators;
 use swc_ecma_transforms_typescript::typescript;
-use swc_ecma_visit::{Visit, VisitWith};
+use swc_ecma_utils::{
+    assertion_chain_would_change_binary_grouping, get_output_extension_wtf8,
+    is_maybe_branch_directive,
+};
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
 #[cfg(feature = "wasm-bindgen")]
 use wasm_bindgen::prelude::*;
 
+use crate::preserve_lines::LinePreservingWriter;
+
+mod preserve_lines;
+pub mod stack_trace;
+#[cfg(feature = "tsconfig")]
+mod tsconfig;
+
 #[derive(Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Options {
@@ -45,9 +62,17 @@ pub struct Options {
     #[serde(default)]
     pub filename: Option<String>,
 
+    #[serde(default)]
+    pub language: Language,
+
     #[serde(default = "default_ts_syntax")]
     pub parser: TsSyntax,
 
+    /// Parser options used if `language` is `flow`.
+    #[cfg(feature = "flow")]
+    #[serde(default)]
+    pub flow_parser: FlowSyntax,
+
     #[serde(default)]
     pub mode: Mode,
 
@@ -59,6 +84,83 @@ pub struct Options {
 
     #[serde(default)]
     pub source_map: bool,
+
+    /// Returns best-effort output if the parser recovered from syntax errors,
+    /// instead of failing.
+    ///
+    /// Recovered errors are reported to the handler as warnings, and the
+    /// output is marked as [TransformOutput::partial].
+    #[serde(default)]
+    pub recover: bool,
+
+    /// Places each statement of the output on its line in `input`.
+    ///
+    /// Generated code which is shorter than the original is padded with empty
+    /// lines, and longer code is packed onto fewer lines, so line numbers
+    /// stay the same as in strip-only mode.
+    ///
+    /// Only used in transform mode.
+    #[serde(default)]
+    pub preserve_lines: bool,
+
+    /// Reuses the original source text of statements and class members which
+    /// are not changed by the transforms, so their formatting and comments
+    /// are kept.
+    ///
+    /// Only used in transform mode.
+    #[serde(default)]
+    pub preserve_formatting: bool,
+
+    /// Source map of `input`, which is composed with the generated source map.
+    ///
+    /// Only used in transform mode with `source_map` enabled.
+    #[serde(default)]
+    pub input_source_map: InputSourceMap,
+
+    /// Embeds the input in `sourcesContent` of the source map.
+    ///
+    /// Defaults to `true` only if `filename` is not specified.
+    #[serde(default)]
+    pub inline_sources_content: Option<bool>,
+
+    /// Emits mappings for each column instead of each line.
+    ///
+    /// Defaults to `true`.
+    #[serde(default)]
+    pub emit_source_map_columns: Option<bool>,
+
+    /// Adds original names of identifiers to `names` of the source map.
+    #[serde(default)]
+    pub source_map_names: bool,
+
+    /// `sourceRoot` of the source map.
+    #[serde(default)]
+    pub source_root: Option<String>,
+
+    /// `file` of the source map, which is the name of the generated file.
+    #[serde(default)]
+    pub source_map_file: Option<String>,
+
+    /// Also emits the `ignoreList` of the source map as `x_google_ignoreList`.
+    #[serde(default)]
+    pub x_google_ignore_list: bool,
+}
+
+/// Source map of the input file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum InputSourceMap {
+    /// `true` is the same as `"inline"`.
+    Bool(bool),
+    /// `"inline"` to read the inline `sourceMappingURL` comment of the input,
+    /// or a source map as a JSON string.
+    Str(String),
+}
+
+impl Default for InputSourceMap {
+    fn default() -> Self {
+        InputSourceMap::Bool(false)
+    }
 }
 
 #[derive(Debug, Default, Deserialize)]
@@ -68,6 +170,23 @@ pub struct TransformConfig {
     #[serde(default)]
     pub jsx: Option<JsxConfig>,
 
+    /// Rewrites `.ts`, `.tsx`, `.mts` and `.cts` extensions of relative
+    /// module specifiers to their JavaScript counterparts.
+    ///
+    /// See https://www.typescriptlang.org/tsconfig/#rewriteRelativeImportExtensions
+    #[serde(default)]
+    pub rewrite_relative_import_extensions: bool,
+
+    /// Transforms decorators in the way of TypeScript's
+    /// `experimentalDecorators`, instead of emitting them as is.
+    #[serde(default)]
+    pub experimental_decorators: bool,
+
+    /// Emits design-time type metadata of decorated declarations. Only used
+    /// with `experimental_decorators`.
+    #[serde(default)]
+    pub emit_decorator_metadata: bool,
+
     #[serde(flatten)]
     pub typescript: typescript::Config,
 }
@@ -100,10 +219,102 @@ const Type_Options: &'static str = r#"
 interface Options {
     module?: boolean;
     filename?: string;
+    /**
+     * Language of the input.
+     *
+     * Defaults to "typescript".
+     */
+    language?: Language;
+    /**
+     * Parser options used if `language` is "flow".
+     */
+    flowParser?: FlowParserConfig;
     mode?: Mode;
     transform?: TransformConfig;
     deprecatedTsModuleAsError?: boolean;
     sourceMap?: boolean;
+    /**
+     * Returns best-effort output if the parser recovered from syntax errors,
+     * instead of failing. The errors are reported as `warnings`.
+     *
+     * Defaults to false.
+     */
+    recover?: boolean;
+    /**
+     * Places each statement of the output on its line in the input, so line
+     * numbers stay the same as in strip-only mode. Only used in transform
+     * mode.
+     *
+     * Defaults to false.
+     */
+    preserveLines?: boolean;
+    /**
+     * Reuses the original source text of statements and class members which
+     * are not changed by the transforms, so their formatting and comments
+     * are kept. Only used in transform mode.
+     *
+     * Defaults to false.
+     */
+    preserveFormatting?: boolean;
+    /**
+     * Source map of the input, which is composed with the generated source
+     * map in transform mode.
+     *
+     * `"inline"` (or `true`) reads the inline `sourceMappingURL` comment of
+     * the input. Other strings are parsed as a source map.
+     *
+     * Defaults to false.
+     */
+    inputSourceMap?: boolean | "inline" | string;
+    /**
+     * Embeds the input in `sourcesContent` of the source map.
+     *
+     * Defaults to true only if `filename` is not specified.
+     */
+    inlineSourcesContent?: boolean;
+    /**
+     * Emits mappings for each column instead of each line.
+     *
+     * Defaults to true.
+     */
+    emitSourceMapColumns?: boolean;
+    /**
+     * Adds original names of identifiers to `names` of the source map.
+     *
+     * Defaults to false.
+     */
+    sourceMapNames?: boolean;
+    /**
+     * `sourceRoot` of the source map.
+     */
+    sourceRoot?: string;
+    /**
+     * `file` of the source map, which is the name of the generated file.
+     */
+    sourceMapFile?: string;
+    /**
+     * Also emits the `ignoreList` of the source map as `x_google_ignoreList`,
+     * for devtools which do not support `ignoreList` yet.
+     *
+     * Defaults to false.
+     */
+    xGoogleIgnoreList?: boolean;
+}
+
+interface FlowParserConfig {
+    jsx?: boolean;
+    /**
+     * Parses all files as Flow, even without `@flow` pragma.
+     */
+    all?: boolean;
+    /**
+     * Requires `@flow` pragma to parse Flow syntax.
+     */
+    requireDirective?: boolean;
+    enums?: boolean;
+    decorators?: boolean;
+    components?: boolean;
+    patternMatching?: boolean;
 }
 
 interface TransformConfig {
@@ -133,6 +344,36 @@ interface TransformConfig {
      * Defaults to false.
      */
     tsEnumIsMutable?: boolean;
+    /**
+     * Rewrite `.ts`, `.tsx`, `.mts` and `.cts` extensions of relative
+     * import specifiers to `.js`, `.mjs` and `.cjs`. `.tsx` is rewritten to
+     * `.jsx` if JSX is not transformed.
+     *
+     * Dynamic imports with a non-literal argument are left unchanged.
+     *
+     * @see https://www.typescriptlang.org/tsconfig/#rewriteRelativeImportExtensions
+     *
+     * Defaults to false.
+     */
+    rewriteRelativeImportExtensions?: boolean;
+    /**
+     * Transforms decorators in the way of TypeScript's
+     * `experimentalDecorators`, instead of emitting them as is.
+     *
+     * @see https://www.typescriptlang.org/tsconfig/#experimentalDecorators
+     *
+     * Defaults to false.
+     */
+    experimentalDecorators?: boolean;
+    /**
+     * Emits design-time type metadata of decorated declarations. Only used
+     * with `experimentalDecorators`.
+     *
+     * @see https://www.typescriptlang.org/tsconfig/#emitDecoratorMetadata
+     *
+     * Defaults to false.
+     */
+    emitDecoratorMetadata?: boolean;
 
     /**
      * Available only on nightly builds.
@@ -150,7 +391,24 @@ interface JsxConfig {
 }
 "#;
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Language {
+    #[default]
+    Typescript,
+    /// Flow annotations are stripped in the same way as TypeScript types.
+    ///
+    /// Requires the `flow` feature.
+    Flow,
+}
+
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const Type_Language: &'static str = r#"
+type Language = "typescript" | "flow";
+"#;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum Mode {
     #[default]
@@ -175,6 +433,9 @@ fn default_ts_syntax() -> TsSyntax {
 pub struct TransformOutput {
     pub code: String,
     pub map: Option<String>,
+    /// `true` if the input had syntax errors which were recovered from.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
 }
 
 #[cfg(feature = "wasm-bindgen")]
@@ -183,6 +444,15 @@ const Type_TransformOutput: &'static str = r#"
 interface TransformOutput {
     code: string;
     map?: string;
+    /**
+     * True if the input had syntax errors which were recovered from.
+     * Only set if `recover` is enabled.
+     */
+    partial?: boolean;
+    /**
+     * Syntax errors which were recovered from.
+     */
+    warnings?: Diagnostic[];
 }
 "#;
 
@@ -246,7 +516,18 @@ pub fn operate(
     let source_len = input.len();
     let fm = cm.new_source_file(filename.into(), input);
 
-    let syntax = Syntax::Typescript(options.parser);
+    let syntax = match options.language {
+        Language::Typescript => Syntax::Typescript(options.parser),
+        #[cfg(feature = "flow")]
+        Language::Flow => Syntax::Flow(options.flow_parser),
+        #[cfg(not(feature = "flow"))]
+        Language::Flow => {
+            return Err(TsError {
+                message: "Flow is not supported by this build".to_string(),
+                code: ErrorCode::UnsupportedSyntax,
+            })
+        }
+    };
     let target = EsVersion::latest();
 
     let comments = SingleThreadedComments::default();
@@ -303,7 +584,15 @@ pub fn operate(
         }
     };
 
-    if !errors.is_empty() {
+    let partial = !errors.is_empty();
+
+    if partial && options.recover {
+        for e in errors {
+            let mut db = e.into_diagnostic(handler);
+            db.level = Level::Warning;
+            db.code(DiagnosticId::Error("InvalidSyntax".into())).emit();
+        }
+    } else if partial {
         for e in errors {
             e.into_diagnostic(handler)
                 .code(DiagnosticId::Error("InvalidSyntax".into()))
@@ -334,7 +623,8 @@ pub fn operate(
             }
 
             // Strip typescript types
-            let mut ts_strip = TsStrip::new(fm.src.clone(), tokens);
+            let mut ts_strip =
+                TsStrip::new(fm.src.clone(), tokens, options.language == Language::Flow);
 
             program.visit_with(&mut ts_strip);
             if handler.has_errors() {
@@ -351,6 +641,7 @@ pub fn operate(
                 return Ok(TransformOutput {
                     code: fm.src.to_string(),
                     map: Default::default(),
+                    partial,
                 });
             }
 
@@ -425,6 +716,7 @@ pub fn operate(
             Ok(TransformOutput {
                 code,
                 map: Default::default(),
+                partial,
             })
         }
 
@@ -432,6 +724,20 @@ pub fn operate(
             let unresolved_mark = Mark::new();
             let top_level_mark = Mark::new();
 
+            let original = options
+                .preserve_formatting
+                .then(|| OriginalSource::new(&program));
+
+            // Names are collected before the transforms, which rename identifiers and
+            // create new ones with spans of the original code.
+            let names = if options.source_map && options.source_map_names {
+                let mut v = IdentCollector::default();
+                program.visit_with(&mut v);
+                v.names
+            } else {
+                Default::default()
+            };
+
             HELPERS.set(&Helpers::new(false), || {
                 program.mutate(&mut resolver(unresolved_mark, top_level_mark, true));
 
@@ -450,7 +756,28 @@ pub fn operate(
                     }
                 }
 
-                let transform = options.transform.unwrap_or_default();
+                let mut transform = options.transform.unwrap_or_default();
+                if options.language == Language::Flow {
+                    transform.typescript.flow_syntax = true;
+                }
+
+                // `import x = require()` is lowered by the typescript transform.
+                if transform.rewrite_relative_import_extensions {
+                    program.visit_mut_with(&mut RewriteImportExtensions {
+                        // JSX is transformed only by nightly builds.
+                        preserve_jsx: !cfg!(feature = "nightly"),
+                    });
+                }
+
+                // Metadata is emitted from type annotations, which are stripped by the
+                // typescript transform.
+                if transform.experimental_decorators {
+                    program.mutate(&mut decorators(decorators::Config {
+                        legacy: true,
+                        emit_metadata: transform.emit_decorator_metadata,
+                        use_define_for_class_fields: transform.typescript.native_class_properties,
+                    }));
+                }
 
                 program.mutate(&mut typescript::typescript(
                     transform.typescript,
@@ -483,8 +810,13 @@ pub fn operate(
                     unresolved_mark,
                 ));
 
+                let len_before_helpers = program_body_len(&program);
                 program.mutate(&mut inject_helpers(unresolved_mark));
 
+                if options.source_map {
+                    map_injected_helpers(cm, &mut program, len_before_helpers);
+                }
+
                 program.mutate(&mut hygiene());
 
                 program.mutate(&mut fixer(Some(&comments)));
@@ -492,6 +824,23 @@ pub fn operate(
                 Ok(())
             })?;
 
+            let orig = if options.source_map {
+                read_input_source_map(&options.input_source_map, &fm.src).inspect_err(|err| {
+                    // The input source map is not a part of `input`, so the error has no span.
+                    handler.struct_err(&format!("{err:#}")).emit();
+                })?
+            } else {
+                None
+            };
+
+            if orig.is_some() {
+                // The reference to the input source map is stale once it's composed.
+                let (mut leading, mut trailing) = comments.borrow_all_mut();
+                for comments in leading.values_mut().chain(trailing.values_mut()) {
+                    comments.retain(|c| !c.text.trim_start().starts_with("# sourceMappingURL="));
+                }
+            }
+
             let mut src = std::vec::Vec::with_capacity(source_len);
             let mut src_map_buf = if options.source_map {
                 Some(Vec::new())
@@ -500,27 +849,45 @@ pub fn operate(
             };
 
             {
-                let mut emitter = swc_ecma_codegen::Emitter {
-                    cfg: swc_ecma_codegen::Config::default(),
-                    comments: if options.source_map {
-                        Some(&comments)
-                    } else {
-                        None
-                    },
-                    cm: cm.clone(),
-                    wr: swc_ecma_codegen::text_writer::JsWriter::new(
-                        cm.clone(),
-                        "\n",
-                        &mut src,
-                        src_map_buf.as_mut(),
-                    ),
+                let wr = swc_ecma_codegen::text_writer::JsWriter::new(
+                    cm.clone(),
+                    "\n",
+                    &mut src,
+                    src_map_buf.as_mut(),
+                );
+                // Reused source text contains comments anyway.
+                let comments = if options.source_map || original.is_some() {
+                    Some(&comments)
+                } else {
+                    None
                 };
 
-                emitter.emit_program(&program).unwrap();
+                if options.preserve_lines {
+                    let mut wr = LinePreservingWriter::new(wr, &fm);
+                    emit_program(cm, comments, &mut wr, &program, original.as_ref());
+                    wr.finish().unwrap();
+                } else {
+                    emit_program(cm, comments, wr, &program, original.as_ref());
+                }
 
                 let map = src_map_buf
                     .map(|map| {
-                        let map = cm.build_source_map(&map, None, DefaultSourceMapGenConfig);
+                        let mut map = cm.build_source_map(
+                            &map,
+                            orig,
+                            FastStripSourceMapConfig {
+                                names: &names,
+                                inline_sources_content: options.inline_sources_content,
+                                emit_columns: options.emit_source_map_columns.unwrap_or(true),
+                            },
+                        );
+                        if let Some(source_root) = options.source_root {
+                            map.set_source_root(Some(source_root));
+                        }
+                        if let Some(file) = options.source_map_file {
+                            map.set_file(Some(file));
+                        }
+                        map.set_x_google_ignore_list(options.x_google_ignore_list);
 
                         let mut s = std::vec::Vec::new();
                         map.to_writer(&mut s)
@@ -533,12 +900,194 @@ pub fn operate(
                 Ok(TransformOutput {
                     code: String::from_utf8(src).context("generated code was not utf-8")?,
                     map,
+                    partial,
                 })
             }
         }
     }
 }
 
+/// Name of the source used for helpers injected by the transforms.
+const HELPERS_SOURCE: &str = "swc:helpers";
+
+struct FastStripSourceMapConfig<'a> {
+    names: &'a FxHashMap<BytePos, swc_atoms::Atom>,
+    inline_sources_content: Option<bool>,
+    emit_columns: bool,
+}
+
+impl SourceMapGenConfig for FastStripSourceMapConfig<'_> {
+    fn file_name_to_source(&self, f: &FileName) -> String {
+        DefaultSourceMapGenConfig.file_name_to_source(f)
+    }
+
+    fn name_for_bytepos(&self, pos: BytePos) -> Option<&str> {
+        self.names.get(&pos).map(|v| &**v)
+    }
+
+    fn inline_sources_content(&self, f: &FileName) -> bool {
+        if is_helpers_source(f) {
+            return false;
+        }
+
+        self.inline_sources_content
+            .unwrap_or_else(|| DefaultSourceMapGenConfig.inline_sources_content(f))
+    }
+
+    fn emit_columns(&self, _: &FileName) -> bool {
+        self.emit_columns
+    }
+
+    fn ignore_list(&self, f: &FileName) -> bool {
+        is_helpers_source(f) || DefaultSourceMapGenConfig.ignore_list(f)
+    }
+}
+
+fn is_helpers_source(f: &FileName) -> bool {
+    matches!(f, FileName::Custom(name) if name == HELPERS_SOURCE)
+}
+
+#[derive(Default)]
+struct IdentCollector {
+    names: FxHashMap<BytePos, swc_atoms::Atom>,
+}
+
+impl Visit for IdentCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.span.lo, ident.sym.clone());
+    }
+
+    fn visit_ident_name(&mut self, ident: &IdentName) {
+        self.names.insert(ident.span.lo, ident.sym.clone());
+    }
+}
+
+fn emit_program<W>(
+    cm: &Lrc<SourceMap>,
+    comments: Option<&SingleThreadedComments>,
+    wr: W,
+    program: &Program,
+    original: Option<&OriginalSource>,
+) where
+    W: swc_ecma_codegen::text_writer::WriteJs,
+{
+    let mut emitter = swc_ecma_codegen::Emitter {
+        cfg: swc_ecma_codegen::Config::default(),
+        comments: comments.map(|c| c as &dyn Comments),
+        cm: cm.clone(),
+        wr,
+    };
+
+    match original {
+        Some(original) => {
+            with_original_source(original, program, || emitter.emit_program(program)).unwrap()
+        }
+        None => emitter.emit_program(program).unwrap(),
+    }
+}
+
+fn program_body_len(program: &Program) -> usize {
+    match program {
+        Program::Module(m) => m.body.len(),
+        Program::Script(s) => s.body.len(),
+        #[cfg(swc_ast_unknown)]
+        _ => 0,
+    }
+}
+
+/// Maps helpers injected after directives to [HELPERS_SOURCE], so they are
+/// added to the `ignoreList` of the source map.
+fn map_injected_helpers(cm: &Lrc<SourceMap>, program: &mut Program, len_before: usize) {
+    let added = program_body_len(program) - len_before;
+    if added == 0 {
+        return;
+    }
+
+    let fm = cm.new_source_file(
+        FileName::Custom(HELPERS_SOURCE.into()).into(),
+        String::new(),
+    );
+    let mut setter = SpanSetter(Span::new(fm.start_pos, fm.start_pos));
+
+    match program {
+        Program::Module(m) => {
+            let start = m
+                .body
+                .iter()
+                .take_while(|item| item.as_stmt().is_some_and(is_maybe_branch_directive))
+                .count();
+            for item in &mut m.body[start..start + added] {
+                item.visit_mut_with(&mut setter);
+            }
+        }
+        Program::Script(s) => {
+            let start = s
+                .body
+                .iter()
+                .take_while(|stmt| is_maybe_branch_directive(stmt))
+                .count();
+            for stmt in &mut s.body[start..start + added] {
+                stmt.visit_mut_with(&mut setter);
+            }
+        }
+        #[cfg(swc_ast_unknown)]
+        _ => {}
+    }
+}
+
+struct SpanSetter(Span);
+
+impl VisitMut for SpanSetter {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = self.0;
+    }
+}
+
+/// Reads the source map of `src` specified by `input_source_map`.
+fn read_input_source_map(
+    input_source_map: &InputSourceMap,
+    src: &str,
+) -> Result<Option<swc_sourcemap::SourceMap>, anyhow::Error> {
+    let map = match input_source_map {
+        InputSourceMap::Bool(false) => return Ok(None),
+        InputSourceMap::Bool(true) => read_inline_source_map(src),
+        InputSourceMap::Str(s) if s == "inline" => read_inline_source_map(src),
+        InputSourceMap::Str(s) => Some(
+            swc_sourcemap::decode_slice(s.as_bytes())
+                .context("failed to read input source map from user-provided sourcemap")?,
+        ),
+    };
+
+    let rewrite_options = swc_sourcemap::RewriteOptions {
+        with_names: true,
+        with_source_contents: true,
+        ..Default::default()
+    };
+
+    match map {
+        None => Ok(None),
+        Some(swc_sourcemap::DecodedMap::Regular(map)) => Ok(Some(
+            map.rewrite(&rewrite_options)
+                .context("failed to rewrite input source map")?,
+        )),
+        Some(swc_sourcemap::DecodedMap::Index(map)) => Ok(Some(
+            map.flatten_and_rewrite(&rewrite_options)
+                .context("failed to flatten input source map")?,
+        )),
+        Some(_) => bail!("unsupported kind of input source map"),
+    }
+}
+
+/// Reads the source map embedded by the `sourceMappingURL` comment of `src`.
+///
+/// Source maps in separate files are not supported, and invalid ones are
+/// ignored.
+fn read_inline_source_map(src: &str) -> Option<swc_sourcemap::DecodedMap> {
+    let reference = swc_sourcemap::locate_sourcemap_reference_slice(src.as_bytes()).ok()??;
+
+    reference.get_embedded_sourcemap().ok()?
+}
+
 struct ErrorOnTsModule<'a> {
     src: &'a str,
     tokens: &'a [TokenAndSpan],
@@ -609,6 +1158,84 @@ impl Visit for ErrorOnTsModule<'_> {
     }
 }
 
+/// Rewrites TypeScript extensions of relative module specifiers.
+///
+/// Unlike `typescript_import_rewriter`, this does not rewrite dynamic imports
+/// with a non-literal argument, as the runtime helper it relies on is not
+/// available to the stripped output.
+struct RewriteImportExtensions {
+    /// `.tsx` is rewritten to `.jsx` instead of `.js` if JSX is not
+    /// transformed, like `"jsx": "preserve"` of TypeScript.
+    preserve_jsx: bool,
+}
+
+impl RewriteImportExtensions {
+    fn rewrite(&self, src: &mut Str) {
+        if let Some(mut value) = get_output_extension_wtf8(&src.value) {
+            if !self.preserve_jsx && src.value.as_bytes().ends_with(b".tsx") {
+                value = value.slice_to(value.len() - 1).into();
+            }
+
+            // The original quotes and escapes are kept if the extension is not rewritten.
+            if value != src.value {
+                src.value = value;
+                src.raw = None;
+            }
+        }
+    }
+}
+
+impl VisitMut for RewriteImportExtensions {
+    fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
+        n.visit_mut_children_with(self);
+
+        if !n.callee.is_import() {
+            return;
+        }
+
+        let Some(arg) = n.args.first_mut() else {
+            return;
+        };
+
+        if arg.spread.is_none() {
+            if let Expr::Lit(Lit::Str(src)) = &mut *arg.expr {
+                self.rewrite(src);
+                return;
+            }
+        }
+
+        if HANDLER.is_set() {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_warn(
+                        arg.span(),
+                        "The extension of a dynamic import with a non-literal argument is not \
+                         rewritten",
+                    )
+                    .emit();
+            });
+        }
+    }
+
+    fn visit_mut_import_decl(&mut self, n: &mut ImportDecl) {
+        self.rewrite(&mut n.src);
+    }
+
+    fn visit_mut_named_export(&mut self, n: &mut NamedExport) {
+        if let Some(src) = &mut n.src {
+            self.rewrite(src);
+        }
+    }
+
+    fn visit_mut_export_all(&mut self, n: &mut ExportAll) {
+        self.rewrite(&mut n.src);
+    }
+
+    fn visit_mut_ts_external_module_ref(&mut self, n: &mut TsExternalModuleRef) {
+        self.rewrite(&mut n.expr);
+    }
+}
+
 struct TsStrip {
     src: BytesStr,
 
@@ -619,15 +1246,19 @@ struct TsStrip {
     overwrites: Vec<(BytePos, u8)>,
 
     tokens: std::vec::Vec<TokenAndSpan>,
+
+    /// Whether the input is Flow, which has variance sigils on class members.
+    flow: bool,
 }
 
 impl TsStrip {
-    fn new(src: BytesStr, tokens: std::vec::Vec<TokenAndSpan>) -> Self {
+    fn new(src: BytesStr, tokens: std::vec::Vec<TokenAndSpan>, flow: bool) -> Self {
         TsStrip {
             src,
             replacements: Default::default(),
             overwrites: Default::default(),
             tokens,
+            flow,
         }
     }
 }
@@ -756,8 +1387,8 @@ impl TsStrip {
 
             // see ts_next_token_can_follow_modifier
             // class { public public() {} }
-            if !next.token.is_word()
-                && !matches!(
+            let can_follow_modifier = next.token.is_word()
+                || matches!(
                     next.token,
                     Token::LBracket
                         | Token::LBrace
@@ -768,7 +1399,8 @@ impl TsStrip {
                         | Token::Num
                         | Token::BigInt
                 )
-            {
+                || (self.flow && matches!(next.token, Token::Plus | Token::Minus));
+            if !can_follow_modifier {
                 return;
             }
 
@@ -782,6 +1414,10 @@ impl TsStrip {
                 Token::Override => {
                     self.add_replacement(*span);
                 }
+                // Flow variance sigils
+                Token::Plus | Token::Minus if self.flow => {
+                    self.add_replacement(*span);
+                }
                 _ => {
                     return;
                 }
@@ -789,6 +1425,52 @@ impl TsStrip {
         }
     }
 
+    /// Returns `true` if the class member key at `key_pos` is preceded by a
+    /// Flow variance sigil (`+foo` or `-foo`).
+    fn has_flow_variance(&self, start_pos: BytePos, key_pos: BytePos) -> bool {
+        let index = self.get_next_token_index(key_pos);
+        if index == 0 {
+            return false;
+        }
+
+        let prev = &self.tokens[index - 1];
+        prev.span.lo >= start_pos && matches!(prev.token, Token::Plus | Token::Minus)
+    }
+
+    /// Returns the end of the Flow predicate (`%checks` or `%checks(expr)`)
+    /// at `pos`, which is not a part of the preceding return type.
+    fn flow_predicate_end(&self, pos: BytePos) -> Option<BytePos> {
+        if !self.flow {
+            return None;
+        }
+
+        let index = self.get_next_token_index(pos);
+        if self.tokens.get(index)?.token != Token::Percent {
+            return None;
+        }
+
+        let checks = self.tokens.get(index + 1)?;
+        if self.tokens.get(index + 2)?.token != Token::LParen {
+            return Some(checks.span.hi);
+        }
+
+        let mut depth = 0usize;
+        for t in &self.tokens[index + 2..] {
+            match t.token {
+                Token::LParen => depth += 1,
+                Token::RParen => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(t.span.hi);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
     fn strip_definite_mark(&mut self, index: usize) {
         self.strip_token(index, Token::Bang);
     }
@@ -837,26 +1519,10 @@ impl TsStrip {
         assertion_span: Span,
         assertion_expr: &Expr,
     ) -> bool {
-        let Some(next_op) = self.next_binary_op_after_assertion_chain(assertion_span) else {
-            return false;
-        };
-
-        let Some(base_op) = Self::base_binary_op_of_assertion_chain(assertion_expr) else {
-            return false;
-        };
-
-        let base_precedence = Self::binary_operator_precedence(base_op);
-        let next_precedence = Self::binary_operator_precedence(next_op);
-
-        if next_precedence > base_precedence {
-            return true;
-        }
-
-        if next_precedence < base_precedence {
-            return false;
-        }
-
-        !Self::are_binary_ops_safely_associative(base_op, next_op)
+        self.next_binary_op_after_assertion_chain(assertion_span)
+            .is_some_and(|next_op| {
+                assertion_chain_would_change_binary_grouping(assertion_expr, next_op)
+            })
     }
 
     fn next_binary_op_after_assertion_chain(&self, assertion_span: Span) -> Option<BinaryOp> {
@@ -871,57 +1537,6 @@ impl TsStrip {
         }
     }
 
-    fn base_binary_op_of_assertion_chain(mut expr: &Expr) -> Option<BinaryOp> {
-        loop {
-            match expr {
-                Expr::TsAs(ts_as_expr) => {
-                    expr = &ts_as_expr.expr;
-                }
-                Expr::TsSatisfies(ts_satisfies_expr) => {
-                    expr = &ts_satisfies_expr.expr;
-                }
-                Expr::TsConstAssertion(ts_const_assertion) => {
-                    expr = &ts_const_assertion.expr;
-                }
-                Expr::Bin(bin_expr) => {
-                    return Some(bin_expr.op);
-                }
-                _ => return None,
-            }
-        }
-    }
-
-    fn binary_operator_precedence(op: BinaryOp) -> u8 {
-        match op {
-            BinaryOp::Exp => 15,
-            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 14,
-            BinaryOp::Add | BinaryOp::Sub => 13,
-            BinaryOp::LShift | BinaryOp::RShift | BinaryOp::ZeroFillRShift => 12,
-            BinaryOp::Lt
-            | BinaryOp::LtEq
-            | BinaryOp::Gt
-            | BinaryOp::GtEq
-            | BinaryOp::In
-            | BinaryOp::InstanceOf => 11,
-            BinaryOp::EqEq | BinaryOp::NotEq | BinaryOp::EqEqEq | BinaryOp::NotEqEq => 10,
-            BinaryOp::BitAnd => 9,
-            BinaryOp::BitXor => 8,
-            BinaryOp::BitOr => 7,
-            BinaryOp::LogicalAnd => 6,
-            BinaryOp::LogicalOr => 5,
-            BinaryOp::NullishCoalescing => 4,
-        }
-    }
-
-    fn are_binary_ops_safely_associative(left: BinaryOp, right: BinaryOp) -> bool {
-        // `**` is right-associative, so flattening equal-precedence groups is not safe.
-        if left == BinaryOp::Exp || right == BinaryOp::Exp {
-            return false;
-        }
-
-        true
-    }
-
     fn emit_unsafe_assertion_error(span: Span) {
         if HANDLER.is_set() {
             HANDLER.with(|handler| {
@@ -1192,8 +1807,11 @@ impl Visit for TsStrip {
             return;
         }
 
-        let has_modifier = n.readonly || n.is_override || n.accessibility.is_some();
         let start_pos = n.decorators.last().map_or(n.span.lo, |d| d.span.hi);
+        let has_modifier = n.readonly
+            || n.is_override
+            || n.accessibility.is_some()
+            || (self.flow && self.has_flow_variance(start_pos, n.key.span_lo()));
 
         if has_modifier {
             self.strip_class_modifier(start_pos, n.key.span_lo());
@@ -1462,8 +2080,12 @@ impl Visit for TsStrip {
             span: as_span,
             ..
         } = self.get_next_token(n.expr.span_hi());
-        debug_assert_eq!(token, &Token::As);
-        self.fix_asi_in_expr(span(as_span.lo, n.span.hi));
+        // Flow type casts (`(expr: T)`) are always parenthesized, so they
+        // cannot cause ASI hazards.
+        if token != &Token::Colon {
+            debug_assert_eq!(token, &Token::As);
+            self.fix_asi_in_expr(span(as_span.lo, n.span.hi));
+        }
 
         n.expr.visit_children_with(self);
     }
@@ -1598,7 +2220,10 @@ impl Visit for TsStrip {
     }
 
     fn visit_ts_type_ann(&mut self, n: &TsTypeAnn) {
-        self.add_replacement(n.span);
+        // The parser does not include `%checks` in the span of the return type.
+        let hi = self.flow_predicate_end(n.span.hi).unwrap_or(n.span.hi);
+
+        self.add_replacement(span(n.span.lo, hi));
     }
 
     /// We do not strip type assertion because it's not safe.
@@ -1830,3 +2455,564 @@ impl U8Helper for u8 {
 fn span(lo: BytePos, hi: BytePos) -> Span {
     Span::new(lo, hi)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Collects messages of diagnostics, prefixed with their level.
+    #[derive(Clone, Default)]
+    struct Diagnostics(Lrc<std::sync::Mutex<Vec<String>>>);
+
+    impl swc_common::errors::Emitter for Diagnostics {
+        fn emit(&mut self, db: &mut swc_common::errors::DiagnosticBuilder<'_>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{}: {}", db.level, db.message()));
+        }
+    }
+
+    /// Returns the result of [operate] and the diagnostics it emitted.
+    fn run(src: &str, options: Options) -> (Result<TransformOutput, TsError>, Vec<String>) {
+        let cm = Lrc::new(SourceMap::default());
+        let diagnostics = Diagnostics::default();
+        let handler = Handler::with_emitter(true, false, Box::new(diagnostics.clone()));
+
+        let output = swc_common::GLOBALS.set(&Default::default(), || {
+            HANDLER.set(&handler, || {
+                operate(&cm, &handler, src.to_string(), options)
+            })
+        });
+
+        let diagnostics = diagnostics.0.lock().unwrap().clone();
+        (output, diagnostics)
+    }
+
+    fn transform_options(transform: TransformConfig) -> Options {
+        Options {
+            mode: Mode::Transform,
+            transform: Some(transform),
+            ..Default::default()
+        }
+    }
+
+    fn preserve_formatting_options() -> Options {
+        Options {
+            preserve_formatting: true,
+            ..transform_options(Default::default())
+        }
+    }
+
+    #[test]
+    fn preserves_formatting_of_unchanged_code() {
+        let src = "const a   =  1; // one
+
+
+function f(x: number) {
+  if (x) {
+    return x   + a;
+  }
+}
+enum E { A }
+";
+
+        let (output, diagnostics) = run(src, preserve_formatting_options());
+
+        assert_eq!(
+            output.unwrap().code,
+            "const a   =  1; // one
+
+function f(x) {
+    if (x) {
+      return x   + a;
+    }
+}
+var E = /*#__PURE__*/ function(E) {
+    E[E[\"A\"] = 0] = \"A\";
+    return E;
+}(E || {});
+"
+        );
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    #[test]
+    fn terminates_preserved_statements() {
+        let src = "let a = b\nnamespace N { export const c = 1 }\n";
+
+        let (output, diagnostics) = run(src, preserve_formatting_options());
+        let code = output.unwrap().code;
+
+        assert!(code.starts_with("let a = b;\n(function(N) {"), "{code}");
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    fn rewrite_extensions_options(tsx: bool) -> Options {
+        Options {
+            parser: TsSyntax {
+                tsx,
+                ..Default::default()
+            },
+            ..transform_options(TransformConfig {
+                rewrite_relative_import_extensions: true,
+                ..Default::default()
+            })
+        }
+    }
+
+    #[test]
+    fn rewrites_relative_import_extensions() {
+        let (output, diagnostics) = run(
+            "import a from './a.ts';\nexport * from '../b.mts';\nexport { c } from \
+             './c.cts';\nimport './d.d.ts';\nimport 'pkg/e.ts';\nconst f = import('./f.ts');\nexport \
+             { a, f };\n",
+            rewrite_extensions_options(false),
+        );
+
+        assert_eq!(
+            output.unwrap().code,
+            "import a from \"./a.js\";\nexport * from \"../b.mjs\";\nexport { c } from \
+             \"./c.cjs\";\nimport './d.d.ts';\nimport 'pkg/e.ts';\nconst f = \
+             import(\"./f.js\");\nexport { a, f };\n"
+        );
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    #[test]
+    fn keeps_specifiers_which_are_not_rewritten() {
+        let (output, _) = run(
+            "import a from './a.js';\nimport b from \"./b\\u002ets\";\nexport { a, b };\n",
+            rewrite_extensions_options(false),
+        );
+
+        assert_eq!(
+            output.unwrap().code,
+            "import a from './a.js';\nimport b from \"./b.js\";\nexport { a, b };\n"
+        );
+    }
+
+    #[test]
+    fn rewrites_import_equals_declarations() {
+        let (output, _) = run(
+            "import a = require('./a.ts');\na();\n",
+            rewrite_extensions_options(false),
+        );
+
+        let code = output.unwrap().code;
+        assert!(code.contains("require(\"./a.js\")"), "{code}");
+    }
+
+    #[test]
+    fn rewrites_tsx_extensions() {
+        let (output, _) = run(
+            "import A from './a.tsx';\nexport const b = <A />;\n",
+            rewrite_extensions_options(true),
+        );
+        let code = output.unwrap().code;
+
+        if cfg!(feature = "nightly") {
+            assert!(code.contains("from \"./a.js\""), "{code}");
+        } else {
+            assert!(code.starts_with("import A from \"./a.jsx\";"), "{code}");
+        }
+    }
+
+    #[test]
+    fn warns_on_non_literal_dynamic_imports() {
+        let (output, diagnostics) = run(
+            "const name = './a.ts';\nimport(name);\n",
+            rewrite_extensions_options(false),
+        );
+        let code = output.unwrap().code;
+
+        assert!(code.contains("import(name)"), "{code}");
+        assert_eq!(
+            diagnostics,
+            vec![
+                "warning: The extension of a dynamic import with a non-literal argument is not \
+                 rewritten"
+            ]
+        );
+    }
+
+    #[test]
+    fn transforms_experimental_decorators() {
+        let src = "class C {\n  @dec m(a: string) {}\n}\n";
+        let options = |transform| Options {
+            parser: default_ts_syntax(),
+            ..transform_options(transform)
+        };
+
+        let (output, _) = run(src, options(Default::default()));
+        let code = output.unwrap().code;
+        assert!(code.contains("@dec\n    m(a)"), "{code}");
+
+        let (output, _) = run(
+            src,
+            options(TransformConfig {
+                experimental_decorators: true,
+                ..Default::default()
+            }),
+        );
+        let code = output.unwrap().code;
+        assert!(code.contains("], C.prototype, \"m\", null);"), "{code}");
+        assert!(!code.contains("@dec"), "{code}");
+    }
+
+    /// Source map of a file generated from `original.ts`, whose first line is
+    /// mapped to the start of the original file.
+    const INPUT_SOURCE_MAP: &str =
+        r#"{"version":3,"sources":["original.ts"],"names":[],"mappings":"AAAA"}"#;
+
+    fn source_map_options(input_source_map: InputSourceMap) -> Options {
+        Options {
+            filename: Some("input.ts".into()),
+            source_map: true,
+            input_source_map,
+            ..transform_options(Default::default())
+        }
+    }
+
+    fn sources(map: &str) -> Vec<String> {
+        let map = swc_sourcemap::SourceMap::from_slice(map.as_bytes()).unwrap();
+
+        map.sources().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn composes_inline_input_source_map() {
+        // `INPUT_SOURCE_MAP` encoded in base64.
+        let data_url = "data:application/json;base64,\
+                        eyJ2ZXJzaW9uIjozLCJzb3VyY2VzIjpbIm9yaWdpbmFsLnRzIl0sIm5hbWVzIjpbXSwibWFwcGluZ3MiOiJBQUFBIn0=";
+        let src = format!("let a: number = 1;\n//# sourceMappingURL={data_url}\n");
+
+        let (output, _) = run(&src, source_map_options(InputSourceMap::Bool(true)));
+        let output = output.unwrap();
+
+        assert_eq!(output.code, "let a = 1;\n");
+        assert_eq!(sources(&output.map.unwrap()), ["original.ts"]);
+    }
+
+    #[test]
+    fn composes_input_source_map_string() {
+        let src = "let a: number = 1;\n//# sourceMappingURL=input.js.map\n";
+
+        let (output, _) = run(
+            src,
+            source_map_options(InputSourceMap::Str(INPUT_SOURCE_MAP.into())),
+        );
+        let output = output.unwrap();
+
+        assert!(!output.code.contains("sourceMappingURL"), "{}", output.code);
+        assert_eq!(sources(&output.map.unwrap()), ["original.ts"]);
+    }
+
+    #[test]
+    fn reports_invalid_input_source_map_string() {
+        let (output, diagnostics) = run(
+            "let a: number = 1;\n",
+            source_map_options(InputSourceMap::Str("{".into())),
+        );
+
+        assert!(output.is_err());
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert!(
+            diagnostics[0].starts_with("error: failed to read input source map"),
+            "{diagnostics:?}"
+        );
+    }
+
+    #[test]
+    fn keeps_reference_to_external_source_map() {
+        let src = "let a: number = 1;\n//# sourceMappingURL=input.js.map\n";
+
+        let (output, _) = run(src, source_map_options(InputSourceMap::Bool(true)));
+        let output = output.unwrap();
+
+        assert!(
+            output.code.contains("//# sourceMappingURL=input.js.map"),
+            "{}",
+            output.code
+        );
+        assert_eq!(sources(&output.map.unwrap()), ["input.ts"]);
+    }
+
+    fn source_map(options: Options) -> (String, swc_sourcemap::SourceMap) {
+        let (output, _) = run("let a: number = 1;\n", options);
+        let map = output.unwrap().map.unwrap();
+        let parsed = swc_sourcemap::SourceMap::from_slice(map.as_bytes()).unwrap();
+
+        (map, parsed)
+    }
+
+    #[test]
+    fn source_map_names_are_original() {
+        let (output, _) = run(
+            "namespace N {\n    const N = 1;\n    export const b = N;\n}\n",
+            Options {
+                source_map_names: true,
+                ..source_map_options(InputSourceMap::Bool(false))
+            },
+        );
+        let output = output.unwrap();
+        let map = swc_sourcemap::SourceMap::from_slice(output.map.unwrap().as_bytes()).unwrap();
+
+        // The inner `N` is renamed to `N1` by the transforms.
+        assert!(output.code.contains("const N1 = 1;"));
+        assert_eq!(map.names().map(|n| &**n).collect::<Vec<_>>(), ["N", "b"]);
+    }
+
+    #[test]
+    fn source_map_fields() {
+        let (raw, map) = source_map(Options {
+            source_root: Some("/project/src/".into()),
+            source_map_file: Some("input.js".into()),
+            ..source_map_options(InputSourceMap::Bool(false))
+        });
+
+        assert!(raw.contains(r#""sources":["input.ts"]"#), "{raw}");
+        assert_eq!(map.get_source_root().map(|s| &**s), Some("/project/src/"));
+        assert_eq!(map.get_file().map(|s| &**s), Some("input.js"));
+        assert_eq!(map.ignore_list().count(), 0);
+    }
+
+    #[test]
+    fn sources_content_defaults_to_anonymous_input() {
+        let (_, map) = source_map(source_map_options(InputSourceMap::Bool(false)));
+        assert_eq!(map.get_source_contents(0), None);
+
+        let (_, map) = source_map(Options {
+            inline_sources_content: Some(true),
+            ..source_map_options(InputSourceMap::Bool(false))
+        });
+        assert_eq!(
+            map.get_source_contents(0).map(|s| &**s),
+            Some("let a: number = 1;\n")
+        );
+
+        let (_, map) = source_map(Options {
+            filename: None,
+            ..source_map_options(InputSourceMap::Bool(false))
+        });
+        assert_eq!(
+            map.get_source_contents(0).map(|s| &**s),
+            Some("let a: number = 1;\n")
+        );
+    }
+
+    #[test]
+    fn ignore_list_of_anonymous_input() {
+        let (raw, map) = source_map(Options {
+            filename: None,
+            ..source_map_options(InputSourceMap::Bool(false))
+        });
+        assert_eq!(map.ignore_list().copied().collect::<Vec<_>>(), [0]);
+        assert!(!raw.contains("x_google_ignoreList"), "{raw}");
+
+        let (raw, _) = source_map(Options {
+            filename: None,
+            x_google_ignore_list: true,
+            ..source_map_options(InputSourceMap::Bool(false))
+        });
+        assert!(raw.contains(r#""x_google_ignoreList":[0]"#), "{raw}");
+    }
+
+    #[cfg(feature = "flow")]
+    fn flow_options(mode: Mode) -> Options {
+        Options {
+            language: Language::Flow,
+            flow_parser: FlowSyntax {
+                all: true,
+                ..Default::default()
+            },
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "flow")]
+    fn strips_flow_annotations() {
+        let src = "import type { T } from './t';
+import { type U, v } from './u';
+type A = { +x: number, -y: ?string };
+opaque type B = string;
+export type { A };
+class C<T> {
+  +p: T;
+  -q: number = 1;
+  m(a: T, b?: string): void {}
+}
+const d = (v: any);
+declare var e: number;
+declare export function f(): void;
+";
+
+        let (output, diagnostics) = run(src, flow_options(Mode::StripOnly));
+        let code = output.unwrap().code;
+
+        // Types are replaced with whitespace, so positions are kept.
+        assert_eq!(code.len(), src.len());
+        assert_eq!(
+            code.lines().map(str::trim_end).collect::<Vec<_>>(),
+            [
+                "",
+                "import {         v } from './u';",
+                "",
+                "",
+                "",
+                "class C    {",
+                "   p   ;",
+                "   q         = 1;",
+                "  m(a   , b         )       {}",
+                "}",
+                "const d = (v     );",
+                "",
+                "",
+            ]
+        );
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    #[test]
+    #[cfg(feature = "flow")]
+    fn strips_flow_predicates() {
+        let src = "function f(x: mixed): boolean %checks { return !!x; }
+function g(x: mixed): %checks(typeof x === 'string') { return typeof x === 'string'; }
+function h(x: mixed): boolean %checks(isString((x))) { return isString(x); }
+";
+
+        let (output, diagnostics) = run(src, flow_options(Mode::StripOnly));
+
+        assert_eq!(
+            output.unwrap().code,
+            "function f(x       )                  { return !!x; }
+function g(x       )                                 { return typeof x === 'string'; }
+function h(x       )                                 { return isString(x); }
+"
+        );
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    #[test]
+    #[cfg(feature = "flow")]
+    fn transforms_flow() {
+        let src = "import type { T } from './t';
+class C<T> {
+  +p: T;
+  m(a: T): void {}
+}
+export const d = (new C(): C<number>);
+";
+
+        let (output, diagnostics) = run(src, flow_options(Mode::Transform));
+
+        assert_eq!(
+            output.unwrap().code,
+            "class C {\n    p;\n    m(a) {}\n}\nexport const d = new C();\n"
+        );
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+
+    #[test]
+    #[cfg(not(feature = "flow"))]
+    fn flow_requires_the_feature() {
+        let options = Options {
+            language: Language::Flow,
+            ..Default::default()
+        };
+        let (output, _) = run("let a: number = 1;\n", options);
+
+        assert!(matches!(
+            output.unwrap_err().code,
+            ErrorCode::UnsupportedSyntax
+        ));
+    }
+
+    /// The duplicate constructor is reported, but the parser recovers from it.
+    const RECOVERABLE: &str = "let a: number = 1;\nclass C { constructor() {} constructor() {} }\n";
+
+    #[test]
+    fn recovers_from_syntax_errors() {
+        let options = Options {
+            recover: true,
+            ..Default::default()
+        };
+        let (output, diagnostics) = run(RECOVERABLE, options);
+        let output = output.unwrap();
+
+        assert_eq!(
+            output.code,
+            "let a         = 1;\nclass C { constructor() {} constructor() {} }\n"
+        );
+        assert!(output.partial);
+        assert_eq!(
+            diagnostics,
+            ["warning: A class can only have one constructor"]
+        );
+    }
+
+    #[test]
+    fn recovers_from_syntax_errors_in_transform_mode() {
+        let options = Options {
+            recover: true,
+            ..transform_options(Default::default())
+        };
+        let (output, diagnostics) = run(RECOVERABLE, options);
+        let output = output.unwrap();
+
+        assert!(output.code.starts_with("let a = 1;\n"), "{}", output.code);
+        assert!(output.partial);
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    }
+
+    #[test]
+    fn fails_on_recovered_errors_by_default() {
+        let (output, diagnostics) = run(RECOVERABLE, Default::default());
+
+        assert!(matches!(
+            output,
+            Err(TsError {
+                code: ErrorCode::InvalidSyntax,
+                ..
+            })
+        ));
+        assert_eq!(
+            diagnostics,
+            ["error: A class can only have one constructor"]
+        );
+    }
+
+    #[test]
+    fn fails_on_unrecoverable_errors() {
+        let options = Options {
+            recover: true,
+            ..Default::default()
+        };
+        let (output, diagnostics) = run("let a: number = 1;\nlet {\n", options);
+
+        assert!(matches!(
+            output,
+            Err(TsError {
+                code: ErrorCode::InvalidSyntax,
+                ..
+            })
+        ));
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+        assert!(diagnostics[0].starts_with("error: "), "{diagnostics:?}");
+    }
+
+    #[test]
+    fn valid_input_is_not_partial() {
+        let options = Options {
+            recover: true,
+            ..Default::default()
+        };
+        let (output, diagnostics) = run("let a: number = 1;\n", options);
+        let output = output.unwrap();
+
+        assert!(!output.partial);
+        assert_eq!(diagnostics, Vec::<String>::new());
+    }
+}
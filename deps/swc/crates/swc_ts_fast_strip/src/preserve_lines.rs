@@ -0,0 +1,408 @@
+use swc_common::{BytePos, SourceFile, Span};
+use swc_ecma_codegen::{
+    text_writer::{BindingStorage, ScopeKind, WriteJs},
+    Result,
+};
+
+/// A writer which places code on the line of its original position.
+///
+/// Line breaks requested by the code generator are replaced with spaces, and
+/// line breaks are inserted when a token of a later line of `fm` is written
+/// at a statement boundary. So generated code which is shorter than the
+/// original is padded with empty lines, and longer code is packed onto the
+/// same line.
+///
+/// Line breaks are inserted only after `;`, `{` and `}` of blocks, because a
+/// line break elsewhere may change the meaning of the code, like one after
+/// `return` or before postfix `++`, or the value of a template literal or
+/// JSX text.
+///
+/// Line breaks which are required for correctness, like the end of a line
+/// comment, are always written.
+pub(crate) struct LinePreservingWriter<'a, W: WriteJs> {
+    inner: W,
+    fm: &'a SourceFile,
+
+    /// Line of the output, which is 0-based like [SourceFile::lookup_line].
+    line: usize,
+    /// Same as `line_start` of `JsWriter`, which is `true` only right after
+    /// [WriteJs::write_line].
+    at_line_start: bool,
+    pending_space: bool,
+    needs_line_break: bool,
+
+    /// `true` if the last token ends a statement or starts a block, so a line
+    /// break can be inserted.
+    at_stmt_boundary: bool,
+    /// Brackets of the output which are not closed yet.
+    brackets: Vec<Bracket>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bracket {
+    /// `{`
+    Brace,
+    /// `${` of a template literal.
+    Substitution,
+    /// `` ` ``
+    Template,
+    /// `<` of a JSX opening element, or `</` of a closing element.
+    JsxTag { closing: bool },
+    /// Children of a JSX element or fragment.
+    JsxChildren,
+}
+
+impl<'a, W: WriteJs> LinePreservingWriter<'a, W> {
+    pub fn new(inner: W, fm: &'a SourceFile) -> Self {
+        LinePreservingWriter {
+            inner,
+            fm,
+            line: 0,
+            at_line_start: true,
+            pending_space: false,
+            needs_line_break: false,
+            at_stmt_boundary: true,
+            brackets: Vec::new(),
+        }
+    }
+
+    /// Writes the final line break.
+    pub fn finish(&mut self) -> Result {
+        self.inner.write_line()
+    }
+
+    fn move_to(&mut self, pos: BytePos) -> Result {
+        if !self.at_stmt_boundary
+            || pos.is_dummy()
+            || pos < self.fm.start_pos
+            || pos > self.fm.end_pos
+        {
+            return Ok(());
+        }
+
+        let Some(target) = self.fm.lookup_line(pos) else {
+            return Ok(());
+        };
+        if target <= self.line {
+            return Ok(());
+        }
+
+        if !self.at_line_start {
+            self.inner.write_line()?;
+            self.line += 1;
+            self.at_line_start = true;
+        }
+        while self.line < target {
+            self.inner.write_blank_line()?;
+            self.line += 1;
+        }
+        self.pending_space = false;
+
+        Ok(())
+    }
+
+    fn before_write(&mut self, span: Option<Span>) -> Result {
+        if let Some(span) = span {
+            self.move_to(span.lo)?;
+        }
+
+        if self.pending_space {
+            self.pending_space = false;
+            if !self.at_line_start {
+                self.inner.write_space()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn after_write(&mut self, s: &str) {
+        if !s.is_empty() {
+            self.line += s.matches('\n').count();
+            self.at_line_start = false;
+            self.at_stmt_boundary = false;
+        }
+    }
+
+    /// Tracks brackets to find out whether `punct` is a statement boundary.
+    fn after_punct(&mut self, punct: &str) {
+        let top = self.brackets.last().copied();
+
+        match (punct, top) {
+            ("{", _) => {
+                self.brackets.push(Bracket::Brace);
+                self.at_stmt_boundary = true;
+            }
+            ("}", _) => {
+                let closed = self.brackets.pop();
+                // `}` of a JSX expression container may be followed by JSX text.
+                self.at_stmt_boundary = closed == Some(Bracket::Brace)
+                    && self.brackets.last() != Some(&Bracket::JsxChildren);
+            }
+            ("${", _) => self.brackets.push(Bracket::Substitution),
+            ("`", Some(Bracket::Template)) => {
+                self.brackets.pop();
+            }
+            ("`", _) => self.brackets.push(Bracket::Template),
+            ("<", _) => self.brackets.push(Bracket::JsxTag { closing: false }),
+            ("/", Some(Bracket::JsxTag { closing: false })) => {
+                // Self-closing element
+                self.brackets.pop();
+                self.brackets.push(Bracket::JsxTag { closing: true });
+            }
+            (">", Some(Bracket::JsxTag { closing })) => {
+                self.brackets.pop();
+                if !closing {
+                    self.brackets.push(Bracket::JsxChildren);
+                }
+            }
+            ("</", Some(Bracket::JsxChildren)) => {
+                self.brackets.pop();
+                self.brackets.push(Bracket::JsxTag { closing: true });
+            }
+            ("<>", _) => self.brackets.push(Bracket::JsxChildren),
+            ("</>", Some(Bracket::JsxChildren)) => {
+                self.brackets.pop();
+            }
+            _ => {}
+        }
+    }
+}
+
+macro_rules! write_fn {
+    ($name:ident($($arg:ident: $ty:ty),*), $span:expr, $s:expr) => {
+        fn $name(&mut self, $($arg: $ty),*) -> Result {
+            self.before_write($span)?;
+            self.inner.$name($($arg),*)?;
+            self.after_write($s);
+            Ok(())
+        }
+    };
+}
+
+impl<W: WriteJs> WriteJs for LinePreservingWriter<'_, W> {
+    fn write_semi(&mut self, span: Option<Span>) -> Result {
+        self.before_write(span)?;
+        self.inner.write_semi(span)?;
+        self.after_write(";");
+        self.at_stmt_boundary = true;
+        Ok(())
+    }
+
+    write_fn!(write_keyword(span: Option<Span>, s: &'static str), span, s);
+
+    write_fn!(write_operator(span: Option<Span>, s: &str), span, s);
+
+    write_fn!(write_param(s: &str), None, s);
+
+    write_fn!(write_property(s: &str), None, s);
+
+    write_fn!(write_lit(span: Span, s: &str), Some(span), s);
+
+    write_fn!(write_str_lit(span: Span, s: &str), Some(span), s);
+
+    write_fn!(write_str(s: &str), None, s);
+
+    write_fn!(write_symbol(span: Span, s: &str), Some(span), s);
+
+    fn increase_indent(&mut self) -> Result {
+        self.inner.increase_indent()
+    }
+
+    fn decrease_indent(&mut self) -> Result {
+        self.inner.decrease_indent()
+    }
+
+    fn write_space(&mut self) -> Result {
+        let at_stmt_boundary = self.at_stmt_boundary;
+
+        self.before_write(None)?;
+        self.inner.write_space()?;
+        self.after_write(" ");
+
+        self.at_stmt_boundary = at_stmt_boundary;
+        Ok(())
+    }
+
+    fn write_comment(&mut self, s: &str) -> Result {
+        let at_stmt_boundary = self.at_stmt_boundary;
+
+        self.before_write(None)?;
+        self.inner.write_comment(s)?;
+        self.after_write(s);
+        self.at_stmt_boundary = at_stmt_boundary;
+
+        if s == "//" {
+            self.needs_line_break = true;
+        }
+
+        Ok(())
+    }
+
+    fn write_punct(
+        &mut self,
+        span: Option<Span>,
+        s: &'static str,
+        commit_pending_semi: bool,
+    ) -> Result {
+        self.before_write(span)?;
+        self.inner.write_punct(span, s, commit_pending_semi)?;
+        self.after_write(s);
+        self.after_punct(s);
+
+        // Shebang
+        if s == "#!" {
+            self.needs_line_break = true;
+        }
+
+        Ok(())
+    }
+
+    fn write_line(&mut self) -> Result {
+        if self.needs_line_break {
+            self.needs_line_break = false;
+
+            if !self.at_line_start {
+                self.inner.write_line()?;
+                self.line += 1;
+                self.at_line_start = true;
+            }
+        } else {
+            self.pending_space = true;
+        }
+
+        Ok(())
+    }
+
+    fn write_blank_line(&mut self) -> Result {
+        self.write_line()
+    }
+
+    fn care_about_srcmap(&self) -> bool {
+        self.inner.care_about_srcmap()
+    }
+
+    fn add_srcmap(&mut self, pos: BytePos) -> Result {
+        self.move_to(pos)?;
+        self.inner.add_srcmap(pos)
+    }
+
+    fn commit_pending_semi(&mut self) -> Result {
+        self.inner.commit_pending_semi()
+    }
+
+    fn can_ignore_invalid_unicodes(&mut self) -> bool {
+        self.inner.can_ignore_invalid_unicodes()
+    }
+
+    fn has_scope_tracking(&self) -> bool {
+        self.inner.has_scope_tracking()
+    }
+
+    fn start_scope(
+        &mut self,
+        name: Option<&str>,
+        kind: ScopeKind,
+        is_stack_frame: bool,
+        is_hidden: bool,
+        original_span: Option<Span>,
+    ) -> Result {
+        self.inner
+            .start_scope(name, kind, is_stack_frame, is_hidden, original_span)
+    }
+
+    fn end_scope(&mut self) -> Result {
+        self.inner.end_scope()
+    }
+
+    fn add_scope_variable(
+        &mut self,
+        name: &str,
+        expression: Option<&str>,
+        storage: BindingStorage,
+    ) -> Result {
+        self.inner.add_scope_variable(name, expression, storage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::{EqIgnoreSpan, FileName, SourceMap};
+    use swc_ecma_ast::{EsVersion, Module};
+    use swc_ecma_parser::{parse_file_as_module, EsSyntax, Syntax, TsSyntax};
+
+    use crate::{operate, Mode, Options};
+
+    fn transform(src: &str, preserve_lines: bool) -> String {
+        testing::run_test(false, |cm, handler| {
+            let options = Options {
+                mode: Mode::Transform,
+                parser: TsSyntax {
+                    tsx: true,
+                    ..Default::default()
+                },
+                preserve_lines,
+                ..Default::default()
+            };
+
+            Ok(operate(&cm, handler, src.to_string(), options)
+                .unwrap()
+                .code)
+        })
+        .unwrap()
+    }
+
+    fn parse(src: &str) -> Module {
+        let cm = SourceMap::default();
+        let fm = cm.new_source_file(FileName::Anon.into(), src.to_string());
+
+        parse_file_as_module(
+            &fm,
+            Syntax::Es(EsSyntax {
+                jsx: true,
+                ..Default::default()
+            }),
+            EsVersion::latest(),
+            None,
+            &mut Vec::new(),
+        )
+        .unwrap_or_else(|err| panic!("failed to parse:\n{src}\n{err:?}"))
+    }
+
+    #[test]
+    fn line_breaks_do_not_change_meaning() {
+        for src in [
+            "function f(a: number) {\n  return (\n    a\n  );\n}",
+            "function* g() {\n  yield (\n    1\n  );\n}",
+            "function h() {\n  throw (\n    new Error()\n  );\n}",
+            "outer: for (;;) {\n  for (;;) {\n    if (a) continue outer;\n    break \
+             outer;\n  }\n}",
+            "let i = 0;\n(\n  i\n)++;\n(\n  i\n)--;",
+            "const f = async (\n  x: number\n): Promise<\n  number\n> => (\n  x\n);",
+            "const g = async (x: number) =>\n  x;",
+            "const t = `a${\n  1\n}b${2}\n  c`;",
+            "const e = <div>\n  {1}\n  text {2}\n  more\n</div>;",
+        ] {
+            let expected = transform(src, false);
+            let actual = transform(src, true);
+
+            assert!(
+                parse(&actual).eq_ignore_span(&parse(&expected)),
+                "input:\n{src}\n\nwith preserveLines:\n{actual}\n\nwithout \
+                 preserveLines:\n{expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn pads_statements() {
+        let src = "enum E {\n  A,\n  B,\n}\n\nlet a: number = 1;\nif (a) {\n  \
+                   foo();\n}\n";
+
+        let output = transform(src, true);
+
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines[5], "let a = 1;", "{output}");
+        assert_eq!(lines[7].trim(), "foo();", "{output}");
+    }
+}
@@ -0,0 +1,531 @@
+//! Maps stack traces of transformed code back to the original source.
+//!
+//! ```ignore
+//! let mut symbolicator = Symbolicator::default();
+//! symbolicator.add_source_map("/app/index.ts", output.map.as_bytes(), Some(output.code))?;
+//!
+//! let stack = symbolicator.symbolicate_stack(&stack);
+//! ```
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Error};
+use bytes_str::BytesStr;
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
+use swc_sourcemap::{DecodedMap, SourceView};
+#[cfg(any(unix, windows, target_os = "wasi"))]
+use url::Url;
+#[cfg(feature = "wasm-bindgen")]
+use wasm_bindgen::prelude::*;
+
+/// A frame of a stack trace.
+///
+/// `line` and `column` are 1-based, like in `Error.stack`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function_name: Option<String>,
+    pub file: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+#[cfg(feature = "wasm-bindgen")]
+#[wasm_bindgen(typescript_custom_section)]
+const Type_StackFrame: &'static str = r#"
+interface StackFrame {
+    functionName?: string;
+    file: string;
+    /**
+     * 1-based line number.
+     */
+    line: number;
+    /**
+     * 1-based column number.
+     */
+    column: number;
+}
+"#;
+
+/// Source maps of generated files, keyed by the name of the generated file
+/// as it appears in stack traces.
+#[derive(Default)]
+pub struct Symbolicator {
+    files: FxHashMap<String, MappedFile>,
+}
+
+struct MappedFile {
+    map: DecodedMap,
+    /// Generated code, which is used to resolve original function names.
+    generated: Option<SourceView>,
+}
+
+impl Symbolicator {
+    /// Adds the source map of `file`.
+    ///
+    /// `generated` is the generated code of `file`, like
+    /// [TransformOutput::code](crate::TransformOutput::code). Function names
+    /// are not mapped if it's [None].
+    pub fn add_source_map(
+        &mut self,
+        file: impl Into<String>,
+        map: &[u8],
+        generated: Option<String>,
+    ) -> Result<(), Error> {
+        let map = DecodedMap::from_reader(map).context("failed to parse source map")?;
+
+        self.files.insert(
+            file.into(),
+            MappedFile {
+                map,
+                generated: generated.map(|code| SourceView::new(BytesStr::from(code))),
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Adds all `*.map` files in `dir` and its subdirectories.
+    ///
+    /// The source map `foo.js.map` is used for the generated file `foo.js`,
+    /// which is also read if it exists.
+    pub fn add_dir(&mut self, dir: &Path) -> Result<(), Error> {
+        self.add_dir_inner(dir, &mut FxHashSet::default())
+    }
+
+    /// `visited` contains canonical paths of visited directories, so symlinks
+    /// to a parent directory are not followed forever.
+    fn add_dir_inner(&mut self, dir: &Path, visited: &mut FxHashSet<PathBuf>) -> Result<(), Error> {
+        let canonical = dir
+            .canonicalize()
+            .with_context(|| format!("failed to resolve `{}`", dir.display()))?;
+        if !visited.insert(canonical) {
+            return Ok(());
+        }
+
+        let entries =
+            fs::read_dir(dir).with_context(|| format!("failed to read `{}`", dir.display()))?;
+
+        for entry in entries {
+            let path = entry?.path();
+
+            if path.is_dir() {
+                self.add_dir_inner(&path, visited)?;
+                continue;
+            }
+
+            if path.extension().is_none_or(|ext| ext != "map") {
+                continue;
+            }
+
+            let map =
+                fs::read(&path).with_context(|| format!("failed to read `{}`", path.display()))?;
+            let file = path.with_extension("");
+            let generated = fs::read_to_string(&file).ok();
+
+            self.add_source_map(file.to_string_lossy(), &map, generated)
+                .with_context(|| format!("failed to add `{}`", path.display()))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the original position of `frame`, or [None] if there's no
+    /// source map for it.
+    ///
+    /// The function name is kept if the original one is unknown.
+    pub fn symbolicate_frame(&self, frame: &StackFrame) -> Option<StackFrame> {
+        let file = self.file(&frame.file)?;
+
+        let line = frame.line.checked_sub(1)?;
+        let column = frame.column.checked_sub(1)?;
+
+        let token = file.map.lookup_token(line, column)?;
+        let source = token.get_source()?;
+
+        let function_name = frame.function_name.as_deref().map(|name| {
+            file.map
+                .get_original_function_name(line, column, Some(name), file.generated.as_ref())
+                .map_or(name, |name| &**name)
+                .to_string()
+        });
+
+        Some(StackFrame {
+            function_name,
+            file: resolve_source(&frame.file, source),
+            line: token.get_src_line() + 1,
+            column: token.get_src_col() + 1,
+        })
+    }
+
+    /// Maps each frame, keeping frames without a source map as-is.
+    pub fn symbolicate_frames(&self, frames: &[StackFrame]) -> Vec<StackFrame> {
+        frames
+            .iter()
+            .map(|frame| {
+                self.symbolicate_frame(frame)
+                    .unwrap_or_else(|| frame.clone())
+            })
+            .collect()
+    }
+
+    /// Rewrites frames of `stack`, which is the value of `Error.stack`.
+    ///
+    /// Both the V8 format (`    at foo (file.js:1:2)`) and the
+    /// SpiderMonkey/JavaScriptCore format (`foo@file.js:1:2`) are supported.
+    /// Other lines are kept as-is.
+    pub fn symbolicate_stack(&self, stack: &str) -> String {
+        let mut out = String::with_capacity(stack.len());
+
+        for (i, line) in stack.lines().enumerate() {
+            if i != 0 {
+                out.push('\n');
+            }
+
+            match parse_stack_line(line) {
+                Some(mut parsed) => {
+                    if let Some(frame) = self.symbolicate_frame(&parsed.frame) {
+                        parsed.frame = frame;
+                    }
+                    parsed.write(&mut out);
+                }
+                None => out.push_str(line),
+            }
+        }
+
+        out
+    }
+
+    fn file(&self, name: &str) -> Option<&MappedFile> {
+        self.files.get(name).or_else(|| {
+            let path = file_url_to_path(name)?;
+            self.files.get(path.to_str()?)
+        })
+    }
+}
+
+#[cfg(any(unix, windows, target_os = "wasi"))]
+fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    let url = Url::parse(url).ok()?;
+    if url.scheme() != "file" {
+        return None;
+    }
+
+    url.to_file_path().ok()
+}
+
+/// [Url::to_file_path] is not available on targets without a file system.
+#[cfg(not(any(unix, windows, target_os = "wasi")))]
+fn file_url_to_path(url: &str) -> Option<PathBuf> {
+    url.strip_prefix("file://").map(PathBuf::from)
+}
+
+/// Resolves `source` of a source map relative to the generated file.
+fn resolve_source(generated: &str, source: &str) -> String {
+    if source.starts_with('/') || source.contains("://") || Path::new(source).is_absolute() {
+        return source.to_string();
+    }
+
+    match generated.rfind(['/', '\\']) {
+        Some(idx) => format!("{}{}", &generated[..=idx], source),
+        None => source.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StackFormat {
+    /// `    at foo (file.js:1:2)`
+    V8,
+    /// `foo@file.js:1:2`
+    Gecko,
+}
+
+struct StackLine<'a> {
+    format: StackFormat,
+    indent: &'a str,
+    /// `async ` or `new ` of V8
+    prefix: &'a str,
+    frame: StackFrame,
+}
+
+impl StackLine<'_> {
+    fn write(&self, out: &mut String) {
+        let StackFrame {
+            function_name,
+            file,
+            line,
+            column,
+        } = &self.frame;
+
+        out.push_str(self.indent);
+
+        match self.format {
+            StackFormat::V8 => {
+                out.push_str("at ");
+                out.push_str(self.prefix);
+                match function_name {
+                    Some(name) => out.push_str(&format!("{name} ({file}:{line}:{column})")),
+                    None => out.push_str(&format!("{file}:{line}:{column}")),
+                }
+            }
+            StackFormat::Gecko => {
+                out.push_str(&format!(
+                    "{}@{file}:{line}:{column}",
+                    function_name.as_deref().unwrap_or_default()
+                ));
+            }
+        }
+    }
+}
+
+fn parse_stack_line(line: &str) -> Option<StackLine<'_>> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    if let Some(rest) = trimmed.strip_prefix("at ") {
+        let (prefix, rest) = ["async ", "new "]
+            .iter()
+            .find_map(|&prefix| Some((prefix, rest.strip_prefix(prefix)?)))
+            .unwrap_or(("", rest));
+
+        let (function_name, location) = match rest
+            .strip_suffix(')')
+            .and_then(|rest| rest.split_once(" ("))
+        {
+            Some((name, location)) => (Some(name), location),
+            None => (None, rest),
+        };
+
+        return Some(StackLine {
+            format: StackFormat::V8,
+            indent,
+            prefix,
+            frame: parse_location(function_name, location)?,
+        });
+    }
+
+    let (function_name, location) = trimmed.split_once('@')?;
+
+    // The whole line should be a frame, so that messages containing `@` are
+    // not parsed.
+    if !is_gecko_function_name(function_name) || location.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(StackLine {
+        format: StackFormat::Gecko,
+        indent,
+        prefix: "",
+        frame: parse_location(
+            Some(function_name).filter(|name| !name.is_empty()),
+            location,
+        )?,
+    })
+}
+
+/// Function names of SpiderMonkey do not contain whitespace, but the ones of
+/// JavaScriptCore may be `global code` and so on.
+fn is_gecko_function_name(name: &str) -> bool {
+    !name.contains(char::is_whitespace)
+        || matches!(name, "global code" | "module code" | "eval code")
+}
+
+/// Parses `file:line:column`.
+fn parse_location(function_name: Option<&str>, location: &str) -> Option<StackFrame> {
+    let (rest, column) = location.rsplit_once(':')?;
+    let (file, line) = rest.rsplit_once(':')?;
+
+    Some(StackFrame {
+        function_name: function_name.map(|name| name.to_string()),
+        file: file.to_string(),
+        line: line.parse().ok()?,
+        column: column.parse().ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_common::{errors::Handler, sync::Lrc, SourceMap, GLOBALS};
+
+    use super::*;
+    use crate::{operate, Mode, Options, TransformOutput};
+
+    const SRC: &str = "type Name = string;
+
+function greet(name: Name): never {
+    throw new Error(name);
+}
+";
+
+    /// Transforms [SRC], which moves `throw` from line 4 to line 2.
+    fn transform() -> TransformOutput {
+        let cm = Lrc::new(SourceMap::default());
+        let handler = Handler::with_emitter_writer(Box::new(std::io::sink()), None);
+
+        GLOBALS.set(&Default::default(), || {
+            operate(
+                &cm,
+                &handler,
+                SRC.to_string(),
+                Options {
+                    filename: Some("index.ts".into()),
+                    mode: Mode::Transform,
+                    source_map: true,
+                    source_map_names: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+        })
+    }
+
+    fn symbolicator() -> Symbolicator {
+        let output = transform();
+        let mut symbolicator = Symbolicator::default();
+        symbolicator
+            .add_source_map(
+                "/app/dist/index.js",
+                output.map.unwrap().as_bytes(),
+                Some(output.code),
+            )
+            .unwrap();
+
+        symbolicator
+    }
+
+    #[test]
+    fn symbolicates_v8_stack() {
+        let stack = "Error: a@b:1:2
+    at greet (/app/dist/index.js:2:5)
+    at async file:///app/dist/index.js:2:5
+    at new Foo (/app/dist/other.js:1:1)";
+
+        assert_eq!(
+            symbolicator().symbolicate_stack(stack),
+            "Error: a@b:1:2
+    at greet (/app/dist/index.ts:4:5)
+    at async file:///app/dist/index.ts:4:5
+    at new Foo (/app/dist/other.js:1:1)"
+        );
+    }
+
+    #[test]
+    fn decodes_file_urls() {
+        let output = transform();
+        let mut symbolicator = Symbolicator::default();
+        symbolicator
+            .add_source_map(
+                "/app/my dist/index.js",
+                output.map.unwrap().as_bytes(),
+                Some(output.code),
+            )
+            .unwrap();
+
+        assert_eq!(
+            symbolicator.symbolicate_stack("    at file:///app/my%20dist/index.js:2:5"),
+            "    at file:///app/my%20dist/index.ts:4:5"
+        );
+    }
+
+    #[test]
+    fn symbolicates_gecko_stack() {
+        let stack = "greet@/app/dist/index.js:2:5
+global code@/app/dist/index.js:2:5
+@/app/dist/other.js:1:1";
+
+        assert_eq!(
+            symbolicator().symbolicate_stack(stack),
+            "greet@/app/dist/index.ts:4:5
+global code@/app/dist/index.ts:4:5
+@/app/dist/other.js:1:1"
+        );
+    }
+
+    #[test]
+    fn keeps_unmapped_frames() {
+        let frames = [
+            StackFrame {
+                function_name: None,
+                file: "/app/dist/index.js".into(),
+                line: 2,
+                column: 5,
+            },
+            StackFrame {
+                function_name: Some("main".into()),
+                file: "/app/dist/main.js".into(),
+                line: 1,
+                column: 1,
+            },
+        ];
+
+        assert_eq!(
+            symbolicator().symbolicate_frames(&frames),
+            [
+                StackFrame {
+                    function_name: None,
+                    file: "/app/dist/index.ts".into(),
+                    line: 4,
+                    column: 5,
+                },
+                frames[1].clone(),
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_source_maps_in_dir() {
+        let output = transform();
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("dist");
+        fs::create_dir(&nested).unwrap();
+        fs::write(nested.join("index.js"), &output.code).unwrap();
+        fs::write(nested.join("index.js.map"), output.map.unwrap()).unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path(), nested.join("loop")).unwrap();
+
+        let mut symbolicator = Symbolicator::default();
+        symbolicator.add_dir(dir.path()).unwrap();
+
+        let generated = nested.join("index.js").to_string_lossy().into_owned();
+        let frame = symbolicator
+            .symbolicate_frame(&StackFrame {
+                function_name: None,
+                file: generated,
+                line: 2,
+                column: 5,
+            })
+            .unwrap();
+
+        assert_eq!(Path::new(&frame.file), nested.join("index.ts"), "{frame:?}");
+        assert_eq!((frame.line, frame.column), (4, 5));
+    }
+
+    #[test]
+    fn resolves_sources_relative_to_generated_file() {
+        assert_eq!(
+            resolve_source("/app/dist/index.js", "../src/a.ts"),
+            "/app/dist/../src/a.ts"
+        );
+        assert_eq!(
+            resolve_source("/app/dist/index.js", "/src/a.ts"),
+            "/src/a.ts"
+        );
+        assert_eq!(
+            resolve_source("/app/dist/index.js", "webpack://app/a.ts"),
+            "webpack://app/a.ts"
+        );
+        assert_eq!(resolve_source("index.js", "a.ts"), "a.ts");
+    }
+
+    #[test]
+    fn ignores_non_frame_lines() {
+        assert!(parse_stack_line("Error: user@example.com failed").is_none());
+        assert!(parse_stack_line("    at <anonymous>").is_none());
+        assert!(parse_stack_line("Error: something went wrong").is_none());
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(feature = "nightly")]
+use swc_ecma_loader::tsconfig::Jsx;
+use swc_ecma_loader::tsconfig::TsConfig;
+use swc_ecma_transforms_typescript::typescript;
+
+use crate::TransformConfig;
+#[cfg(feature = "nightly")]
+use crate::{JsxConfig, JsxTransform};
+
+impl TransformConfig {
+    /// Creates options of [crate::Mode::Transform] from `compilerOptions` of
+    /// `tsconfig.json`.
+    ///
+    pub fn from_tsconfig(tsconfig: &TsConfig) -> Self {
+        let options = &tsconfig.compiler_options;
+
+        TransformConfig {
+            // Only the automatic runtime is supported.
+            #[cfg(feature = "nightly")]
+            jsx: match options.jsx {
+                Some(Jsx::ReactJsx) => Some(JsxTransform::ReactJsx),
+                Some(Jsx::ReactJsxDev) => Some(JsxTransform::ReactJsxDev),
+                _ => None,
+            }
+            .map(|transform| JsxConfig {
+                transform: Some(transform),
+                import_source: options.jsx_import_source.clone().map(From::from),
+            }),
+            rewrite_relative_import_extensions: options
+                .rewrite_relative_import_extensions
+                .unwrap_or_default(),
+            experimental_decorators: options.experimental_decorators.unwrap_or_default(),
+            emit_decorator_metadata: options.emit_decorator_metadata.unwrap_or_default(),
+            typescript: typescript::Config {
+                verbatim_module_syntax: options.verbatim_module_syntax.unwrap_or_default(),
+                native_class_properties: options.use_define_for_class_fields(),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use swc_ecma_loader::tsconfig::CompilerOptions;
+
+    use super::*;
+
+    fn native_class_properties(compiler_options: CompilerOptions) -> bool {
+        let mut tsconfig = TsConfig::default();
+        tsconfig.compiler_options = compiler_options;
+
+        TransformConfig::from_tsconfig(&tsconfig)
+            .typescript
+            .native_class_properties
+    }
+
+    #[test]
+    fn class_fields_follow_target() {
+        assert!(!native_class_properties(CompilerOptions {
+            target: Some("ES2020".into()),
+            ..Default::default()
+        }));
+        assert!(native_class_properties(CompilerOptions {
+            target: Some("ESNext".into()),
+            ..Default::default()
+        }));
+        assert!(!native_class_properties(CompilerOptions {
+            target: Some("ES2022".into()),
+            use_define_for_class_fields: Some(false),
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn maps_experimental_decorators() {
+        let mut tsconfig = TsConfig::default();
+        let config = TransformConfig::from_tsconfig(&tsconfig);
+        assert!(!config.experimental_decorators);
+        assert!(!config.emit_decorator_metadata);
+
+        tsconfig.compiler_options = CompilerOptions {
+            experimental_decorators: Some(true),
+            emit_decorator_metadata: Some(true),
+            ..Default::default()
+        };
+        let config = TransformConfig::from_tsconfig(&tsconfig);
+        assert!(config.experimental_decorators);
+        assert!(config.emit_decorator_metadata);
+    }
+}